@@ -0,0 +1,259 @@
+use soroban_sdk::{contract, contractimpl, contractmeta, xdr::ToXdr, Address, Bytes, BytesN, Env};
+use access_control::role::Role;
+use yield_adapter::contract_types::{SupportedAdapter, SupportedYieldType};
+use crate::cusd_manager::{Client as CUSDManagerClient, Role as CUSDManagerRole};
+use crate::yield_adapter_registry::{Client as YieldAdapterRegistryClient, Role as YieldAdapterRegistryRole};
+use crate::events::CoopstableFactoryEvents;
+use crate::storage_types::{Deployment, DeploymentConfig, WasmHashes};
+use crate::storage;
+use crate::migrations;
+
+contractmeta!(
+    key = "Description",
+    val = "Deploys and wires a complete Coopstable protocol"
+);
+
+// Salt suffixes for the contracts of one deployment
+const CUSD_MANAGER_SALT: u32 = 0;
+const YIELD_ADAPTER_REGISTRY_SALT: u32 = 1;
+const YIELD_DISTRIBUTOR_SALT: u32 = 2;
+const LENDING_YIELD_CONTROLLER_SALT: u32 = 3;
+const BLEND_CAPITAL_ADAPTER_SALT: u32 = 4;
+
+fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
+
+/// Derive the salt of one contract from the owner and salt of the deployment. Including the owner keeps
+/// anyone who sees a pending deployment from taking its addresses with the same salt first.
+fn contract_salt(e: &Env, owner: &Address, salt: &BytesN<32>, index: u32) -> BytesN<32> {
+    let mut bytes = owner.clone().to_xdr(e);
+    bytes.append(&Bytes::from(salt));
+    bytes.extend_from_array(&index.to_be_bytes());
+    e.crypto().sha256(&bytes).to_bytes()
+}
+
+pub trait CoopstableFactoryTrait {
+    /// Initialize the Coopstable Factory contract
+    ///
+    /// ### Arguments
+    /// * `owner` - The address of the contract owner (sets WASM hashes and can upgrade the contract)
+    /// * `wasm_hashes` - The hashes of the uploaded protocol WASM
+    fn __constructor(e: Env, owner: Address, wasm_hashes: WasmHashes);
+
+    /// Deploy the cUSD manager, adapter registry, yield distributor, lending yield controller and
+    /// Blend adapter, wire them to each other and register the adapter for `config.assets`
+    ///
+    /// The factory administers the manager and registry only while wiring them; `config.owner` and
+    /// `config.admin` hold every contract afterwards. Handing the cUSD token admin to the manager
+    /// stays with the token issuer.
+    ///
+    /// Returns the addresses of the deployed contracts
+    ///
+    /// ### Arguments
+    /// * `config` - The owner, admin and parameters of the deployment
+    /// * `salt` - Unique per deployment of an owner; the contract addresses are derived from it and `config.owner`
+    ///
+    /// ### Panics
+    /// If `config.owner` does not authorize the deployment
+    /// If a deployment with the same owner and salt already exists
+    fn deploy(e: &Env, config: DeploymentConfig, salt: BytesN<32>) -> Deployment;
+
+    /// (Owner only) Set the WASM hashes used by later deployments
+    ///
+    /// ### Arguments
+    /// * `wasm_hashes` - The hashes of the uploaded protocol WASM
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn set_wasm_hashes(e: &Env, wasm_hashes: WasmHashes);
+
+    /// Fetch the WASM hashes used by deployments
+    fn get_wasm_hashes(e: &Env) -> WasmHashes;
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Upgrade the contract to a new WASM bytecode
+    ///
+    /// ### Arguments
+    /// * `new_wasm_hash` - The hash of the new WASM bytecode (must be uploaded first)
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
+}
+
+/// ### CoopstableFactory
+///
+/// Brings up a complete Coopstable protocol in one transaction from uploaded WASM, so no wiring
+/// step can be missed.
+#[contract]
+pub struct CoopstableFactory;
+
+#[contractimpl]
+impl CoopstableFactoryTrait for CoopstableFactory {
+    fn __constructor(e: Env, owner: Address, wasm_hashes: WasmHashes) {
+        storage::write_owner(&e, owner);
+        storage::write_wasm_hashes(&e, &wasm_hashes);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
+    }
+
+    fn deploy(e: &Env, config: DeploymentConfig, salt: BytesN<32>) -> Deployment {
+        // The owner also authorizes handing the manager and registry admin over at the end
+        config.owner.require_auth();
+        let hashes = storage::read_wasm_hashes(e);
+        let factory = e.current_contract_address();
+
+        // The distributor and controller reference each other, so fix the controller address first
+        let controller_deployer = e
+            .deployer()
+            .with_current_contract(contract_salt(e, &config.owner, &salt, LENDING_YIELD_CONTROLLER_SALT));
+        let lending_yield_controller = controller_deployer.deployed_address();
+
+        let cusd_manager = e
+            .deployer()
+            .with_current_contract(contract_salt(e, &config.owner, &salt, CUSD_MANAGER_SALT))
+            .deploy_v2(
+                hashes.cusd_manager,
+                (config.cusd_id.clone(), config.owner.clone(), factory.clone()),
+            );
+        let yield_adapter_registry = e
+            .deployer()
+            .with_current_contract(contract_salt(e, &config.owner, &salt, YIELD_ADAPTER_REGISTRY_SALT))
+            .deploy_v2(hashes.yield_adapter_registry, (factory, config.owner.clone()));
+        let yield_distributor = e
+            .deployer()
+            .with_current_contract(contract_salt(e, &config.owner, &salt, YIELD_DISTRIBUTOR_SALT))
+            .deploy_v2(
+                hashes.yield_distributor,
+                (
+                    config.treasury.clone(),
+                    config.treasury_share_bps,
+                    lending_yield_controller.clone(),
+                    config.distribution_period,
+                    config.owner.clone(),
+                    config.admin.clone(),
+                ),
+            );
+        controller_deployer.deploy_v2(
+            hashes.lending_yield_controller,
+            (
+                yield_distributor.clone(),
+                yield_adapter_registry.clone(),
+                cusd_manager.clone(),
+                config.admin.clone(),
+                config.owner.clone(),
+            ),
+        );
+        let blend_capital_adapter = e
+            .deployer()
+            .with_current_contract(contract_salt(e, &config.owner, &salt, BLEND_CAPITAL_ADAPTER_SALT))
+            .deploy_v2(
+                hashes.blend_capital_adapter,
+                (
+                    lending_yield_controller.clone(),
+                    config.blend_pool_id.clone(),
+                    config.blend_token_id.clone(),
+                ),
+            );
+
+        let manager = CUSDManagerClient::new(e, &cusd_manager);
+        manager.set_yield_controller(&lending_yield_controller);
+        manager.grant_role(&CUSDManagerRole::Admin, &config.admin);
+
+        let registry = YieldAdapterRegistryClient::new(e, &yield_adapter_registry);
+        let yield_type = SupportedYieldType::Lending.id();
+        let protocol = SupportedAdapter::BlendCapital.id();
+        registry.register_adapter(&yield_type, &protocol, &blend_capital_adapter);
+        for asset in config.assets.iter() {
+            registry.add_support_for_asset(&yield_type, &protocol, &asset);
+        }
+        registry.grant_role(&YieldAdapterRegistryRole::Admin, &config.admin);
+
+        let deployment = Deployment {
+            cusd_manager,
+            yield_adapter_registry,
+            yield_distributor,
+            lending_yield_controller,
+            blend_capital_adapter,
+        };
+        CoopstableFactoryEvents::deploy(e, config.owner, deployment.clone());
+
+        deployment
+    }
+
+    fn set_wasm_hashes(e: &Env, wasm_hashes: WasmHashes) {
+        require_owner(e);
+        storage::write_wasm_hashes(e, &wasm_hashes);
+        CoopstableFactoryEvents::set_wasm_hashes(e, wasm_hashes);
+    }
+
+    fn get_wasm_hashes(e: &Env) -> WasmHashes {
+        storage::read_wasm_hashes(e)
+    }
+
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>) {
+        require_owner(e);
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    fn migrate(e: &Env) -> u32 {
+        require_owner(e);
+        migrations::migrate(e)
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
+}
@@ -0,0 +1,16 @@
+use soroban_sdk::{Address, Env, Symbol};
+use crate::storage_types::{Deployment, WasmHashes};
+
+pub struct CoopstableFactoryEvents {}
+
+impl CoopstableFactoryEvents {
+    pub fn deploy(e: &Env, owner: Address, deployment: Deployment) {
+        let topics = (Symbol::new(e, "deploy"), owner);
+        e.events().publish(topics, deployment);
+    }
+
+    pub fn set_wasm_hashes(e: &Env, hashes: WasmHashes) {
+        let topics = (Symbol::new(e, "set_wasm_hashes"),);
+        e.events().publish(topics, hashes);
+    }
+}
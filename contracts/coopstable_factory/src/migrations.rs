@@ -0,0 +1,16 @@
+use soroban_sdk::Env;
+
+/// Schema version written by this build
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// Bring stored data up to `SCHEMA_VERSION` and return the resulting version
+pub fn migrate(e: &Env) -> u32 {
+    versioning::migrate(e, SCHEMA_VERSION, SCHEMA_VERSION, step)
+}
+
+pub fn version(e: &Env) -> u32 {
+    versioning::read_version(e, SCHEMA_VERSION)
+}
+
+/// No layout changes yet
+fn step(_e: &Env, _from: u32) {}
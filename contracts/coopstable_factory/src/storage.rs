@@ -0,0 +1,29 @@
+use soroban_sdk::{Address, Env};
+use access_control::role::Role;
+use crate::storage_types::{
+    DataKey,
+    WasmHashes,
+    INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+pub fn read_owner(e: &Env) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, Role::Owner).unwrap()
+}
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+
+pub fn read_wasm_hashes(e: &Env) -> WasmHashes {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::WasmHashes).unwrap()
+}
+
+pub fn write_wasm_hashes(e: &Env, hashes: &WasmHashes) {
+    e.storage().instance().set(&DataKey::WasmHashes, hashes);
+}
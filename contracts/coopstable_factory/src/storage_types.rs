@@ -0,0 +1,48 @@
+use soroban_sdk::{contracttype, Address, BytesN, Vec};
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Hashes of the uploaded WASM the factory deploys
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WasmHashes {
+    pub cusd_manager: BytesN<32>,
+    pub yield_adapter_registry: BytesN<32>,
+    pub yield_distributor: BytesN<32>,
+    pub lending_yield_controller: BytesN<32>,
+    pub blend_capital_adapter: BytesN<32>,
+}
+
+/// Parameters for one protocol deployment
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DeploymentConfig {
+    pub owner: Address,
+    pub admin: Address,
+    pub cusd_id: Address,
+    pub treasury: Address,
+    pub treasury_share_bps: u32,
+    pub distribution_period: u64,
+    pub blend_pool_id: Address,
+    pub blend_token_id: Address,
+    pub assets: Vec<Address>, // Assets the Blend adapter is registered for
+}
+
+/// Addresses of the contracts created by one deployment
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Deployment {
+    pub cusd_manager: Address,
+    pub yield_adapter_registry: Address,
+    pub yield_distributor: Address,
+    pub lending_yield_controller: Address,
+    pub blend_capital_adapter: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    WasmHashes,
+}
@@ -0,0 +1,160 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, BytesN as _},
+    vec, Address, BytesN, Env, Symbol,
+};
+
+use crate::contract::{CoopstableFactory, CoopstableFactoryClient};
+use crate::cusd_manager::{self, Role as CUSDManagerRole};
+use crate::yield_adapter_registry::{self, Role as YieldAdapterRegistryRole};
+use crate::storage_types::{DeploymentConfig, WasmHashes};
+
+mod yield_distributor {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/yield_distributor.wasm"
+    );
+}
+
+mod lending_yield_controller {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/lending_yield_controller.wasm"
+    );
+}
+
+// The adapter's interface references Blend pool types, so only its bytecode is imported
+const BLEND_CAPITAL_ADAPTER_WASM: &[u8] =
+    include_bytes!("../../../target/wasm32v1-none/release/blend_capital_adapter.wasm");
+
+struct TestFixture {
+    env: Env,
+    factory: CoopstableFactoryClient<'static>,
+    config: DeploymentConfig,
+}
+
+impl TestFixture {
+    fn create() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let deployer = env.deployer();
+        let wasm_hashes = WasmHashes {
+            cusd_manager: deployer.upload_contract_wasm(cusd_manager::WASM),
+            yield_adapter_registry: deployer.upload_contract_wasm(yield_adapter_registry::WASM),
+            yield_distributor: deployer.upload_contract_wasm(yield_distributor::WASM),
+            lending_yield_controller: deployer.upload_contract_wasm(lending_yield_controller::WASM),
+            blend_capital_adapter: deployer.upload_contract_wasm(BLEND_CAPITAL_ADAPTER_WASM),
+        };
+        let factory_id = env.register(CoopstableFactory, (Address::generate(&env), wasm_hashes));
+        let factory = CoopstableFactoryClient::new(&env, &factory_id);
+
+        let config = DeploymentConfig {
+            owner: Address::generate(&env),
+            admin: Address::generate(&env),
+            cusd_id: Address::generate(&env),
+            treasury: Address::generate(&env),
+            treasury_share_bps: 1000,
+            distribution_period: 86400,
+            blend_pool_id: Address::generate(&env),
+            blend_token_id: Address::generate(&env),
+            assets: vec![&env, Address::generate(&env)],
+        };
+
+        Self { env, factory, config }
+    }
+}
+
+#[test]
+fn test_deploy_wires_protocol() {
+    let fixture = TestFixture::create();
+    let env = &fixture.env;
+    let config = &fixture.config;
+
+    let deployment = fixture.factory.deploy(config, &BytesN::random(env));
+
+    let manager = cusd_manager::Client::new(env, &deployment.cusd_manager);
+    assert_eq!(manager.get_cusd_id(), config.cusd_id);
+    assert_eq!(manager.get_yield_controller(), deployment.lending_yield_controller);
+
+    let distributor = yield_distributor::Client::new(env, &deployment.yield_distributor);
+    assert_eq!(distributor.get_yield_controller(), deployment.lending_yield_controller);
+    assert_eq!(distributor.get_treasury(), config.treasury);
+    assert_eq!(distributor.get_treasury_share(), config.treasury_share_bps);
+    assert_eq!(distributor.get_distribution_period(), config.distribution_period);
+
+    let controller = lending_yield_controller::Client::new(env, &deployment.lending_yield_controller);
+    assert_eq!(controller.get_yield_distributor(), deployment.yield_distributor);
+    assert_eq!(controller.get_adapter_registry(), deployment.yield_adapter_registry);
+    assert_eq!(controller.get_cusd_manager(), deployment.cusd_manager);
+
+    let registry = yield_adapter_registry::Client::new(env, &deployment.yield_adapter_registry);
+    let yield_type = Symbol::new(env, "LEND");
+    let protocol = Symbol::new(env, "BC_LA");
+    assert_eq!(registry.get_adapter(&yield_type, &protocol), deployment.blend_capital_adapter);
+    assert!(registry.is_supported_asset(&yield_type, &protocol, &config.assets.get(0).unwrap()));
+
+    // The factory keeps no role on the deployed contracts
+    assert!(manager.has_role(&CUSDManagerRole::Owner, &config.owner));
+    assert!(manager.has_role(&CUSDManagerRole::Admin, &config.admin));
+    assert!(!manager.has_role(&CUSDManagerRole::Admin, &fixture.factory.address));
+    assert!(registry.has_role(&YieldAdapterRegistryRole::Owner, &config.owner));
+    assert!(registry.has_role(&YieldAdapterRegistryRole::Admin, &config.admin));
+    assert!(!registry.has_role(&YieldAdapterRegistryRole::Admin, &fixture.factory.address));
+}
+
+#[test]
+fn test_deploy_with_new_salt() {
+    let fixture = TestFixture::create();
+
+    let first = fixture.factory.deploy(&fixture.config, &BytesN::random(&fixture.env));
+    let second = fixture.factory.deploy(&fixture.config, &BytesN::random(&fixture.env));
+
+    assert_ne!(first.cusd_manager, second.cusd_manager);
+    assert_ne!(first.lending_yield_controller, second.lending_yield_controller);
+}
+
+#[test]
+fn test_deploy_same_salt_other_owner() {
+    let fixture = TestFixture::create();
+    let salt = BytesN::random(&fixture.env);
+    let first = fixture.factory.deploy(&fixture.config, &salt);
+
+    // Another owner reusing a salt gets their own addresses instead of the first owner's
+    let mut config = fixture.config.clone();
+    config.owner = Address::generate(&fixture.env);
+    let second = fixture.factory.deploy(&config, &salt);
+
+    assert_ne!(first.cusd_manager, second.cusd_manager);
+    assert_ne!(first.yield_distributor, second.yield_distributor);
+    assert_ne!(first.lending_yield_controller, second.lending_yield_controller);
+}
+
+#[test]
+#[should_panic(expected = "Error(Storage, ExistingValue)")]
+fn test_deploy_same_salt_twice() {
+    let fixture = TestFixture::create();
+    let salt = BytesN::random(&fixture.env);
+
+    fixture.factory.deploy(&fixture.config, &salt);
+    fixture.factory.deploy(&fixture.config, &salt);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_deploy_unauthorized() {
+    let fixture = TestFixture::create();
+
+    fixture.env.mock_auths(&[]);
+    fixture.factory.deploy(&fixture.config, &BytesN::random(&fixture.env));
+}
+
+#[test]
+fn test_set_wasm_hashes() {
+    let fixture = TestFixture::create();
+    let mut hashes = fixture.factory.get_wasm_hashes();
+    hashes.blend_capital_adapter = BytesN::random(&fixture.env);
+
+    fixture.factory.set_wasm_hashes(&hashes);
+
+    assert_eq!(fixture.factory.get_wasm_hashes(), hashes);
+}
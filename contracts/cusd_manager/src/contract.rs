@@ -1,8 +1,10 @@
+use access_control::{error::AccessControlError, role::Role};
 use crate::events::CUSDManagerEvents;
 use crate::error::CUSDManagerError;
-use crate::storage_types::DataKey;
+use crate::storage_types::{DataKey, PauseFlag};
 use crate::token;
 use crate::storage;
+use crate::migrations;
 use soroban_sdk::{ contract, contractimpl, contractmeta, Address, BytesN, Env, panic_with_error };
 
 contractmeta!(
@@ -19,6 +21,12 @@ fn check_nonnegative_amount(e: &Env, amount: i128) {
 fn require_admin(e: &Env) { storage::read_admin(e).require_auth(); }
 fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
 
+fn require_not_paused(e: &Env, flag: PauseFlag) {
+    if storage::is_paused(e, flag) {
+        panic_with_error!(e, CUSDManagerError::Paused);
+    }
+}
+
 /// ### CUSDManager
 ///
 /// Manager contract responsible for the cUSD stablecoin operations including minting,
@@ -43,6 +51,7 @@ pub trait CUSDManagerTrait {
     ///
     /// ### Panics
     /// If the caller is not the authorized yield controller
+    /// If minting is paused
     /// If the amount is negative
     fn issue_cusd(e: &Env, to: Address, amount: i128);
     
@@ -53,6 +62,7 @@ pub trait CUSDManagerTrait {
     /// * `amount` - The amount of cUSD tokens to burn
     ///
     /// ### Panics
+    /// If withdrawals are paused
     /// If the amount is negative
     /// If the address has insufficient balance
     fn burn_cusd(e: &Env, from: Address, amount: i128);
@@ -70,7 +80,124 @@ pub trait CUSDManagerTrait {
     ///
     /// ### Panics
     /// If the caller is not the owner
-    fn set_admin(e: &Env, new_admin: Address); 
+    fn set_admin(e: &Env, new_admin: Address);
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Propose a new admin who takes over once they accept, as an alternative to setting it directly
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The proposed admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_admin(e: &Env, new_admin: Address);
+
+    /// (Proposed admin only) Accept a pending admin transfer
+    ///
+    /// ### Panics
+    /// If no admin transfer is pending
+    /// If the caller is not the proposed admin
+    fn accept_admin(e: &Env);
+
+    /// (Owner only) Cancel a pending admin transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no admin transfer is pending
+    fn cancel_admin_transfer(e: &Env);
+
+    /// Fetch the proposed admin, if an admin transfer is pending
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Grant a role to an account. Granting the admin role replaces the current admin
+    ///
+    /// ### Arguments
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner role
+    fn grant_role(e: &Env, role: Role, account: Address);
+
+    /// (Owner only) Revoke a role from an account
+    ///
+    /// ### Arguments
+    /// * `role` - The role to revoke
+    /// * `account` - The account losing the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner or admin role
+    fn revoke_role(e: &Env, role: Role, account: Address);
+
+    /// Give up a role held by `account`
+    ///
+    /// ### Arguments
+    /// * `account` - The account giving up the role
+    /// * `role` - The role to give up
+    ///
+    /// ### Panics
+    /// If `account` does not authorize the call
+    /// If `role` is the owner or admin role
+    fn renounce_role(e: &Env, account: Address, role: Role);
+
+    /// Check whether an account holds a role
+    ///
+    /// ### Arguments
+    /// * `role` - The role to check
+    /// * `account` - The account to check
+    fn has_role(e: &Env, role: Role, account: Address) -> bool;
+
+    /// (Pauser only) Pause an operation
+    ///
+    /// ### Arguments
+    /// * `caller` - An account holding the pauser role
+    /// * `flag` - The operation to pause
+    ///
+    /// ### Panics
+    /// If the caller does not hold the pauser role
+    fn pause(e: &Env, caller: Address, flag: PauseFlag);
+
+    /// (Owner only) Unpause an operation
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to unpause
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn unpause(e: &Env, flag: PauseFlag);
+
+    /// Check whether an operation is paused
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to check
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool;
     
     /// (Admin only) Set a new yield controller address
     ///
@@ -80,6 +207,9 @@ pub trait CUSDManagerTrait {
     /// ### Panics
     /// If the caller is not the admin
     fn set_yield_controller(e: &Env, new_controller: Address);
+
+    /// Fetch the yield controller allowed to issue and burn cUSD
+    fn get_yield_controller(e: &Env) -> Address;
     
     /// (Admin only) Set a new cUSD token contract address
     ///
@@ -107,6 +237,17 @@ pub trait CUSDManagerTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
 }
 
 #[contractimpl]
@@ -117,6 +258,7 @@ impl CUSDManagerTrait for CUSDManager {
         storage::write_cusd(&e, cusd_id); 
 
         e.storage().persistent().set(&DataKey::CusdSupply, &0i128);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
     }
     
     fn set_admin(e: &Env, new_admin: Address) {
@@ -125,12 +267,89 @@ impl CUSDManagerTrait for CUSDManager {
         CUSDManagerEvents::set_admin(&e, new_admin);
     }
 
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn propose_admin(e: &Env, new_admin: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Admin, &new_admin);
+    }
+
+    fn accept_admin(e: &Env) {
+        let new_admin = access_control::accept_transfer(e, Role::Admin);
+        CUSDManagerEvents::set_admin(e, new_admin);
+    }
+
+    fn cancel_admin_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Admin);
+    }
+
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Admin)
+    }
+
+    fn grant_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        if role == Role::Owner {
+            panic_with_error!(e, AccessControlError::InvalidRole);
+        }
+        access_control::grant_role(e, role, &account);
+    }
+
+    fn revoke_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        access_control::revoke_role(e, role, &account);
+    }
+
+    fn renounce_role(e: &Env, account: Address, role: Role) {
+        access_control::renounce_role(e, role, &account);
+    }
+
+    fn has_role(e: &Env, role: Role, account: Address) -> bool {
+        access_control::has_role(e, role, &account)
+    }
+
+    fn pause(e: &Env, caller: Address, flag: PauseFlag) {
+        access_control::require_role(e, Role::Pauser, &caller);
+        storage::write_paused(e, flag, true);
+        CUSDManagerEvents::pause(e, flag);
+    }
+
+    fn unpause(e: &Env, flag: PauseFlag) {
+        require_owner(e);
+        storage::write_paused(e, flag, false);
+        CUSDManagerEvents::unpause(e, flag);
+    }
+
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool { storage::is_paused(e, flag) }
+
     fn get_cusd_id(e: &Env) -> Address {
         storage::read_cusd_id(e)
     }
 
+    fn get_yield_controller(e: &Env) -> Address {
+        storage::read_yield_controller(e)
+    }
+
     fn issue_cusd(e: &Env, to: Address, amount: i128) {
         storage::read_yield_controller(e).require_auth();
+        require_not_paused(e, PauseFlag::Mint);
         check_nonnegative_amount(e, amount);
         token::process_token_mint(&e, to.clone(), amount);
         CUSDManagerEvents::issue_cusd(&e, to, amount);
@@ -149,6 +368,7 @@ impl CUSDManagerTrait for CUSDManager {
     }
 
     fn burn_cusd(e: &Env, from: Address, amount: i128) {
+        require_not_paused(e, PauseFlag::Withdraw);
         check_nonnegative_amount(e, amount);
         token::process_token_burn(&e, from.clone(), amount);
         CUSDManagerEvents::burn_cusd(&e, from, amount);
@@ -166,4 +386,16 @@ impl CUSDManagerTrait for CUSDManager {
         require_owner(e);
         e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
+
+    fn migrate(e: &Env) -> u32 {
+        // Migrate before authorizing: an old layout may keep the owner where `require_owner` can't
+        // read it, and a failed authorization reverts the migration
+        let version = migrations::migrate(e);
+        require_owner(e);
+        version
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
 }
@@ -11,5 +11,8 @@ pub enum CUSDManagerError {
     UnauthorizedError = 4,
     NegativeAmountError = 8,
     BalanceError = 10,
-    OverflowError = 12
+    OverflowError = 12,
+
+    // CUSDManager Errors
+    Paused = 1300,
 }
@@ -1,4 +1,5 @@
 use soroban_sdk::{Address, Env, Symbol};
+use crate::storage_types::PauseFlag;
 
 pub struct CUSDManagerEvents {}
 
@@ -32,4 +33,14 @@ impl CUSDManagerEvents {
         let topics = (Symbol::new(e, "set_yield_controller"),);
         e.events().publish(topics, yield_controller);
     }
+
+    pub fn pause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "pause"),);
+        e.events().publish(topics, flag);
+    }
+
+    pub fn unpause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "unpause"),);
+        e.events().publish(topics, flag);
+    }
 }
@@ -2,6 +2,7 @@
 pub mod contract;
 mod events;
 mod storage_types;
+mod migrations;
 mod storage;
 mod test;
 pub mod token;
@@ -1,7 +1,8 @@
-use soroban_sdk::{Address, Env}; 
+use soroban_sdk::{Address, Env};
+use access_control::role::Role;
 
 use crate::storage_types::{
-    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, DataKey, PauseFlag, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD
 };
 
 pub fn extend_instance(e: &Env) {
@@ -16,8 +17,15 @@ fn extend_persistent(e: &Env, key: &DataKey) {
         .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 }
 
-pub fn read_admin(e: &Env) -> Address { read_address(e, &DataKey::Admin)}
-pub fn read_owner(e: &Env) -> Address { read_address(e, &DataKey::Owner)}
+pub fn read_admin(e: &Env) -> Address { read_role_holder(e, Role::Admin) }
+pub fn read_owner(e: &Env) -> Address { read_role_holder(e, Role::Owner) }
+pub fn write_admin(e: &Env, new_admin: Address) { access_control::grant_role(e, Role::Admin, &new_admin); }
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+
+fn read_role_holder(e: &Env, role: Role) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, role).unwrap()
+}
 
 fn read_address(e: &Env, key: &DataKey) -> Address {
     extend_instance(e);
@@ -51,7 +59,21 @@ fn write_cusd_supply(e: &Env, amount: i128) {
 
 pub fn read_cusd_id(e: &Env) -> Address { read_address(e, &DataKey::Cusd) }
 pub fn read_yield_controller(e: &Env) -> Address { read_address(e, &DataKey::YieldController) }
-pub fn write_admin(e: &Env, new_admin: Address) { write_address(e, &DataKey::Admin, &new_admin);}
-pub fn write_owner(e: &Env, new_owner: Address) { write_address(e, &DataKey::Owner, &new_owner);}
 pub fn write_cusd(e: &Env, new_cusd: Address) { write_address(e, &DataKey::Cusd, &new_cusd);}
 pub fn write_yield_controller(e: &Env, new_controller: Address) { write_address(e, &DataKey::YieldController, &new_controller);}
+
+// Pause storage functions
+
+pub fn is_paused(e: &Env, flag: PauseFlag) -> bool {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::Paused(flag)).unwrap_or(false)
+}
+
+pub fn write_paused(e: &Env, flag: PauseFlag, paused: bool) {
+    if paused {
+        e.storage().instance().set(&DataKey::Paused(flag), &true);
+    } else {
+        e.storage().instance().remove(&DataKey::Paused(flag));
+    }
+    extend_instance(e);
+}
@@ -6,13 +6,23 @@ pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_I
 pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
 pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
+/// Operations that can be paused independently during an incident
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PauseFlag {
+    Deposit,
+    Withdraw,
+    Harvest,
+    Distribution,
+    Mint,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    Owner,
-    Admin,
     Manager,
     Cusd,
     YieldController,
     CusdSupply,
+    Paused(PauseFlag),
 }
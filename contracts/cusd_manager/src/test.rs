@@ -1,12 +1,15 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::{Address as _, Events, Ledger},
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
     token::{StellarAssetClient, TokenClient},
     vec, Address, Env, IntoVal, Symbol,
 };
 
+use access_control::role::Role;
 use crate::contract::{CUSDManager, CUSDManagerClient};
+use crate::storage_types::PauseFlag;
+use crate::migrations::LegacyDataKey;
 
 struct TestFixture {
     env: Env,
@@ -124,6 +127,7 @@ fn test_constructor() {
 
     // Verify cusd_id is set correctly
     assert_eq!(fixture.cusd_manager.get_cusd_id(), fixture.cusd_token_id);
+    assert_eq!(fixture.cusd_manager.get_yield_controller(), fixture.yield_controller);
 }
 
 #[test]
@@ -495,4 +499,220 @@ fn test_large_amount_operations() {
     // Burn half (burns directly from user account)
     fixture.cusd_manager.burn_cusd(&fixture.user1, &(large_amount / 2));
     assert_eq!(fixture.token_client().balance(&fixture.user1), large_amount / 2);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pause_and_unpause_mint() {
+    let fixture = TestFixture::create();
+    let pauser = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths_allowing_non_root_auth();
+
+    fixture.cusd_manager.grant_role(&Role::Pauser, &pauser);
+    assert!(fixture.cusd_manager.has_role(&Role::Pauser, &pauser));
+
+    fixture.cusd_manager.pause(&pauser, &PauseFlag::Mint);
+    assert!(fixture.cusd_manager.is_paused(&PauseFlag::Mint));
+    assert!(!fixture.cusd_manager.is_paused(&PauseFlag::Withdraw));
+
+    fixture.cusd_manager.unpause(&PauseFlag::Mint);
+    fixture.cusd_manager.issue_cusd(&fixture.user1, &100_0000000);
+    assert_eq!(fixture.token_client().balance(&fixture.user1), 100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1300)")]
+fn test_issue_cusd_paused() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths_allowing_non_root_auth();
+    fixture.cusd_manager.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.cusd_manager.pause(&fixture.admin, &PauseFlag::Mint);
+
+    fixture.cusd_manager.issue_cusd(&fixture.user1, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1300)")]
+fn test_burn_cusd_paused() {
+    let fixture = TestFixture::create();
+    fixture.issue_tokens_to_user(&fixture.user1, 100_0000000);
+    fixture.env.mock_all_auths_allowing_non_root_auth();
+    fixture.cusd_manager.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.cusd_manager.pause(&fixture.admin, &PauseFlag::Withdraw);
+
+    fixture.cusd_manager.burn_cusd(&fixture.user1, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_grant_role_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.cusd_manager.grant_role(&Role::Pauser, &fixture.admin);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.cusd_manager.propose_owner(&new_owner);
+    assert_eq!(fixture.cusd_manager.get_pending_owner(), Some(new_owner.clone()));
+    // The current owner keeps control until the transfer is accepted
+    assert!(fixture.cusd_manager.has_role(&Role::Owner, &fixture.owner));
+
+    fixture.env.mock_auths(&[MockAuth {
+        address: &new_owner,
+        invoke: &MockAuthInvoke {
+            contract: &fixture.cusd_manager.address,
+            fn_name: "accept_owner",
+            args: ().into_val(&fixture.env),
+            sub_invokes: &[],
+        },
+    }]);
+    fixture.cusd_manager.accept_owner();
+
+    assert!(fixture.cusd_manager.has_role(&Role::Owner, &new_owner));
+    assert!(!fixture.cusd_manager.has_role(&Role::Owner, &fixture.owner));
+    assert_eq!(fixture.cusd_manager.get_pending_owner(), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_accept_owner_by_other_account() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+    fixture.cusd_manager.propose_owner(&new_owner);
+
+    fixture.env.mock_auths(&[MockAuth {
+        address: &fixture.owner,
+        invoke: &MockAuthInvoke {
+            contract: &fixture.cusd_manager.address,
+            fn_name: "accept_owner",
+            args: ().into_val(&fixture.env),
+            sub_invokes: &[],
+        },
+    }]);
+    fixture.cusd_manager.accept_owner();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1402)")]
+fn test_cancel_ownership_transfer() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.cusd_manager.propose_owner(&new_owner);
+    fixture.cusd_manager.cancel_ownership_transfer();
+    assert_eq!(fixture.cusd_manager.get_pending_owner(), None);
+
+    fixture.cusd_manager.accept_owner();
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_propose_owner_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.cusd_manager.propose_owner(&fixture.user1);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.cusd_manager.propose_admin(&new_admin);
+    assert_eq!(fixture.cusd_manager.get_pending_admin(), Some(new_admin.clone()));
+    assert!(fixture.cusd_manager.has_role(&Role::Admin, &fixture.admin));
+
+    fixture.cusd_manager.accept_admin();
+    assert!(fixture.cusd_manager.has_role(&Role::Admin, &new_admin));
+    assert_eq!(fixture.cusd_manager.get_pending_admin(), None);
+}
+
+#[test]
+fn test_set_admin_drops_pending_admin_transfer() {
+    let fixture = TestFixture::create();
+    let proposed = Address::generate(&fixture.env);
+    let replacement = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.cusd_manager.propose_admin(&proposed);
+    fixture.cusd_manager.set_admin(&replacement);
+
+    assert_eq!(fixture.cusd_manager.get_pending_admin(), None);
+    assert!(fixture.cusd_manager.try_accept_admin().is_err());
+    assert!(fixture.cusd_manager.has_role(&Role::Admin, &replacement));
+}
+
+#[test]
+fn test_grant_admin_role_drops_pending_admin_transfer() {
+    let fixture = TestFixture::create();
+    let proposed = Address::generate(&fixture.env);
+    let replacement = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.cusd_manager.propose_admin(&proposed);
+    fixture.cusd_manager.grant_role(&Role::Admin, &replacement);
+
+    assert_eq!(fixture.cusd_manager.get_pending_admin(), None);
+    assert!(fixture.cusd_manager.has_role(&Role::Admin, &replacement));
+    assert!(!fixture.cusd_manager.has_role(&Role::Admin, &proposed));
+}
+
+/// Lay the contract's storage out as a version 1 deployment: owner and admin under the legacy
+/// keys and no schema version
+fn simulate_v1_storage(env: &Env, contract: &Address) -> (Address, Address) {
+    let legacy_owner = Address::generate(env);
+    let legacy_admin = Address::generate(env);
+    env.as_contract(contract, || {
+        env.storage().instance().set(&LegacyDataKey::Owner, &legacy_owner);
+        env.storage().instance().set(&LegacyDataKey::Admin, &legacy_admin);
+        versioning::write_version(env, 1);
+    });
+    (legacy_owner, legacy_admin)
+}
+
+#[test]
+fn test_version_after_deploy() {
+    let fixture = TestFixture::create();
+
+    assert_eq!(fixture.cusd_manager.version(), 2);
+}
+
+#[test]
+fn test_migrate_v1_to_v2() {
+    let fixture = TestFixture::create();
+    let (legacy_owner, legacy_admin) = simulate_v1_storage(&fixture.env, &fixture.cusd_manager.address);
+    assert_eq!(fixture.cusd_manager.version(), 1);
+
+    fixture.env.mock_all_auths();
+    assert_eq!(fixture.cusd_manager.migrate(), 2);
+
+    assert_eq!(fixture.cusd_manager.version(), 2);
+    assert!(fixture.cusd_manager.has_role(&Role::Owner, &legacy_owner));
+    assert!(fixture.cusd_manager.has_role(&Role::Admin, &legacy_admin));
+    fixture.env.as_contract(&fixture.cusd_manager.address, || {
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Owner));
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Admin));
+    });
+
+    // Migrating again is a no-op
+    assert_eq!(fixture.cusd_manager.migrate(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_migrate_unauthorized() {
+    let fixture = TestFixture::create();
+    simulate_v1_storage(&fixture.env, &fixture.cusd_manager.address);
+
+    fixture.env.mock_auths(&[]);
+    fixture.cusd_manager.migrate();
+}
@@ -0,0 +1,357 @@
+use soroban_sdk::{contract, contractimpl, contractmeta, panic_with_error, Address, BytesN, Env, Val};
+use access_control::role::Role;
+use crate::error::GovernanceError;
+use crate::events::GovernanceEvents;
+use crate::storage_types::{GovernanceConfig, Proposal, ProposalAction, ProposalSnapshot, ProposalStatus, VoterSet};
+use crate::storage;
+use crate::migrations;
+use crate::yield_distributor::Client as YieldDistributorClient;
+
+contractmeta!(
+    key = "Description",
+    val = "M-of-N governance for Coopstable cooperative decisions"
+);
+
+fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
+
+fn voter_count(e: &Env, voters: &VoterSet) -> u32 {
+    match voters {
+        VoterSet::Signers(signers) => signers.len(),
+        VoterSet::Members(distributor) => YieldDistributorClient::new(e, distributor).get_member_count(),
+    }
+}
+
+/// A threshold must be reachable by the voters that exist now
+fn check_threshold(e: &Env, voters: &VoterSet, threshold: u32) {
+    if threshold == 0 || threshold > voter_count(e, voters) {
+        panic_with_error!(e, GovernanceError::InvalidThreshold);
+    }
+}
+
+fn check_config(e: &Env, config: &GovernanceConfig) {
+    check_threshold(e, &config.voters, config.threshold);
+}
+
+/// Check whether `account` is in the voter set. Members must have joined by `at`
+fn is_voter(e: &Env, voters: &VoterSet, account: &Address, at: u64) -> bool {
+    match voters {
+        VoterSet::Signers(signers) => signers.contains(account),
+        VoterSet::Members(distributor) => YieldDistributorClient::new(e, distributor)
+            .get_member_joined_at(account)
+            .is_some_and(|joined_at| joined_at <= at),
+    }
+}
+
+fn require_voter(e: &Env, voters: &VoterSet, account: &Address, at: u64) {
+    account.require_auth();
+    if !is_voter(e, voters, account, at) {
+        panic_with_error!(e, GovernanceError::NotVoter);
+    }
+}
+
+/// The voters and threshold a proposal was created under
+fn read_snapshot(e: &Env, id: u64) -> ProposalSnapshot {
+    storage::read_snapshot(e, id).unwrap_or_else(|| panic_with_error!(e, GovernanceError::ProposalNotFound))
+}
+
+/// Load a proposal that is still open for votes and execution
+fn read_open_proposal(e: &Env, id: u64) -> Proposal {
+    let Some(proposal) = storage::read_proposal(e, id) else {
+        panic_with_error!(e, GovernanceError::ProposalNotFound);
+    };
+    if proposal.status != ProposalStatus::Open || e.ledger().timestamp() > proposal.deadline {
+        panic_with_error!(e, GovernanceError::ProposalClosed);
+    }
+    proposal
+}
+
+pub trait GovernanceTrait {
+    /// Initialize the Governance contract
+    ///
+    /// ### Arguments
+    /// * `owner` - The address of the contract owner (can upgrade the contract)
+    /// * `config` - The voter set, threshold and voting period
+    ///
+    /// ### Panics
+    /// If the threshold is zero or higher than the number of signers or active members
+    fn __constructor(e: Env, owner: Address, config: GovernanceConfig);
+
+    /// (Voters only) Create a proposal and return its id
+    ///
+    /// ### Arguments
+    /// * `proposer` - The voter creating the proposal
+    /// * `action` - The call or configuration change to run once the proposal passes
+    ///
+    /// The current voter set and threshold are fixed for the proposal: members who join later
+    /// cannot vote on it, and later configuration changes do not apply to it. A member set is not
+    /// copied, though: members removed after the proposal was created, or removed and added back,
+    /// cannot vote on it either.
+    ///
+    /// ### Panics
+    /// If the proposer is not a voter
+    /// If members were removed so that fewer voters remain than the threshold
+    /// If `action` sets an invalid threshold
+    fn propose(e: &Env, proposer: Address, action: ProposalAction) -> u64;
+
+    /// (Voters only) Vote on an open proposal
+    ///
+    /// ### Arguments
+    /// * `voter` - The voter casting the vote
+    /// * `id` - The proposal id
+    /// * `support` - Whether the voter approves the proposal
+    ///
+    /// ### Panics
+    /// If the voter was not a voter when the proposal was created, or already voted on it
+    /// If the proposal does not exist, is no longer open or its deadline has passed
+    fn vote(e: &Env, voter: Address, id: u64, support: bool);
+
+    /// Execute a proposal that reached the threshold. Anyone can execute; calls are authorized
+    /// by the governance contract.
+    ///
+    /// ### Arguments
+    /// * `id` - The proposal id
+    ///
+    /// ### Panics
+    /// If the proposal does not exist, is no longer open or its deadline has passed
+    /// If the proposal has fewer yes votes than the threshold it was created under
+    /// If the proposal sets a threshold the voters it names can no longer reach
+    fn execute(e: &Env, id: u64);
+
+    /// (Proposer only) Cancel an open proposal
+    ///
+    /// ### Arguments
+    /// * `id` - The proposal id
+    ///
+    /// ### Panics
+    /// If the caller is not the proposer
+    /// If the proposal does not exist or is no longer open
+    fn cancel(e: &Env, id: u64);
+
+    /// Fetch a proposal
+    ///
+    /// ### Arguments
+    /// * `id` - The proposal id
+    fn get_proposal(e: &Env, id: u64) -> Option<Proposal>;
+
+    /// Fetch the voter set and threshold a proposal was created under
+    ///
+    /// ### Arguments
+    /// * `id` - The proposal id
+    fn get_proposal_snapshot(e: &Env, id: u64) -> Option<ProposalSnapshot>;
+
+    /// Fetch the voter set, threshold and voting period
+    fn get_config(e: &Env) -> GovernanceConfig;
+
+    /// Check whether an account can propose and vote
+    ///
+    /// ### Arguments
+    /// * `account` - The account to check
+    fn is_voter(e: &Env, account: Address) -> bool;
+
+    /// Check whether an account voted on a proposal
+    ///
+    /// ### Arguments
+    /// * `id` - The proposal id
+    /// * `voter` - The account to check
+    fn has_voted(e: &Env, id: u64, voter: Address) -> bool;
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Upgrade the contract to a new WASM bytecode
+    ///
+    /// ### Arguments
+    /// * `new_wasm_hash` - The hash of the new WASM bytecode (must be uploaded first)
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
+}
+
+/// ### Governance
+///
+/// Lets a set of signers, or the active yield distribution members, propose and vote on calls to
+/// the Coopstable contracts it administers. A proposal runs once it has `threshold` yes votes.
+#[contract]
+pub struct Governance;
+
+#[contractimpl]
+impl GovernanceTrait for Governance {
+    fn __constructor(e: Env, owner: Address, config: GovernanceConfig) {
+        check_config(&e, &config);
+        storage::write_owner(&e, owner);
+        storage::write_config(&e, &config);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
+    }
+
+    fn propose(e: &Env, proposer: Address, action: ProposalAction) -> u64 {
+        let config = storage::read_config(e);
+        let now = e.ledger().timestamp();
+        require_voter(e, &config.voters, &proposer, now);
+        // Removing members must not leave a threshold nobody can reach
+        check_threshold(e, &config.voters, config.threshold);
+        if let ProposalAction::SetConfig(new_config) = &action {
+            check_config(e, new_config);
+        }
+
+        let id = storage::next_proposal_id(e);
+        let proposal = Proposal {
+            proposer: proposer.clone(),
+            action: action.clone(),
+            yes_votes: 0,
+            no_votes: 0,
+            deadline: now + config.voting_period,
+            status: ProposalStatus::Open,
+        };
+        storage::write_proposal(e, id, &proposal);
+        let snapshot = ProposalSnapshot { voters: config.voters, threshold: config.threshold, created_at: now };
+        storage::write_snapshot(e, id, &snapshot);
+        GovernanceEvents::propose(e, id, proposer, action);
+
+        id
+    }
+
+    fn vote(e: &Env, voter: Address, id: u64, support: bool) {
+        let mut proposal = read_open_proposal(e, id);
+        let snapshot = read_snapshot(e, id);
+        require_voter(e, &snapshot.voters, &voter, snapshot.created_at);
+
+        if storage::has_voted(e, id, &voter) {
+            panic_with_error!(e, GovernanceError::AlreadyVoted);
+        }
+        storage::write_vote(e, id, &voter);
+
+        if support {
+            proposal.yes_votes += 1;
+        } else {
+            proposal.no_votes += 1;
+        }
+        storage::write_proposal(e, id, &proposal);
+        GovernanceEvents::vote(e, id, voter, support);
+    }
+
+    fn execute(e: &Env, id: u64) {
+        let mut proposal = read_open_proposal(e, id);
+        if proposal.yes_votes < read_snapshot(e, id).threshold {
+            panic_with_error!(e, GovernanceError::ThresholdNotReached);
+        }
+        if let ProposalAction::SetConfig(config) = &proposal.action {
+            check_config(e, config);
+        }
+
+        // Close the proposal before calling out so it can never run twice
+        proposal.status = ProposalStatus::Executed;
+        storage::write_proposal(e, id, &proposal);
+
+        match proposal.action {
+            ProposalAction::Call(target, fn_name, args) => {
+                e.invoke_contract::<Val>(&target, &fn_name, args);
+            }
+            ProposalAction::SetConfig(config) => {
+                storage::write_config(e, &config);
+                GovernanceEvents::set_config(e, config);
+            }
+        }
+        GovernanceEvents::execute(e, id);
+    }
+
+    fn cancel(e: &Env, id: u64) {
+        let Some(mut proposal) = storage::read_proposal(e, id) else {
+            panic_with_error!(e, GovernanceError::ProposalNotFound);
+        };
+        proposal.proposer.require_auth();
+        if proposal.status != ProposalStatus::Open {
+            panic_with_error!(e, GovernanceError::ProposalClosed);
+        }
+
+        proposal.status = ProposalStatus::Cancelled;
+        storage::write_proposal(e, id, &proposal);
+        GovernanceEvents::cancel(e, id);
+    }
+
+    fn get_proposal(e: &Env, id: u64) -> Option<Proposal> {
+        storage::read_proposal(e, id)
+    }
+
+    fn get_proposal_snapshot(e: &Env, id: u64) -> Option<ProposalSnapshot> {
+        storage::read_snapshot(e, id)
+    }
+
+    fn get_config(e: &Env) -> GovernanceConfig {
+        storage::read_config(e)
+    }
+
+    fn is_voter(e: &Env, account: Address) -> bool {
+        is_voter(e, &storage::read_config(e).voters, &account, e.ledger().timestamp())
+    }
+
+    fn has_voted(e: &Env, id: u64, voter: Address) -> bool {
+        storage::has_voted(e, id, &voter)
+    }
+
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>) {
+        require_owner(e);
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    fn migrate(e: &Env) -> u32 {
+        require_owner(e);
+        migrations::migrate(e)
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
+}
@@ -0,0 +1,23 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the governance contract. Common errors are codes that match up with the built-in
+/// contracts error reporting. Governance specific errors start at 1600
+pub enum GovernanceError {
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+    UnauthorizedError = 4,
+    NegativeAmountError = 8,
+    BalanceError = 10,
+    OverflowError = 12,
+
+    // Governance Errors
+    ProposalNotFound = 1600,
+    NotVoter = 1601,
+    AlreadyVoted = 1602,
+    ProposalClosed = 1603,
+    ThresholdNotReached = 1604,
+    InvalidThreshold = 1605,
+}
@@ -0,0 +1,31 @@
+use soroban_sdk::{Address, Env, Symbol};
+use crate::storage_types::{GovernanceConfig, ProposalAction};
+
+pub struct GovernanceEvents {}
+
+impl GovernanceEvents {
+    pub fn propose(e: &Env, id: u64, proposer: Address, action: ProposalAction) {
+        let topics = (Symbol::new(e, "propose"), id);
+        e.events().publish(topics, (proposer, action));
+    }
+
+    pub fn vote(e: &Env, id: u64, voter: Address, support: bool) {
+        let topics = (Symbol::new(e, "vote"), id);
+        e.events().publish(topics, (voter, support));
+    }
+
+    pub fn execute(e: &Env, id: u64) {
+        let topics = (Symbol::new(e, "execute"), id);
+        e.events().publish(topics, ());
+    }
+
+    pub fn cancel(e: &Env, id: u64) {
+        let topics = (Symbol::new(e, "cancel"), id);
+        e.events().publish(topics, ());
+    }
+
+    pub fn set_config(e: &Env, config: GovernanceConfig) {
+        let topics = (Symbol::new(e, "set_config"),);
+        e.events().publish(topics, config);
+    }
+}
@@ -0,0 +1,14 @@
+#![no_std]
+pub mod yield_distributor {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/yield_distributor.wasm"
+    );
+}
+
+pub mod contract;
+mod events;
+mod migrations;
+mod storage;
+mod storage_types;
+mod error;
+mod test;
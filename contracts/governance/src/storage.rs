@@ -0,0 +1,85 @@
+use soroban_sdk::{Address, Env};
+use access_control::role::Role;
+use crate::storage_types::{
+    DataKey,
+    GovernanceConfig,
+    Proposal,
+    ProposalSnapshot,
+    INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+    PERSISTENT_BUMP_AMOUNT,
+    PERSISTENT_LIFETIME_THRESHOLD,
+};
+
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn read_owner(e: &Env) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, Role::Owner).unwrap()
+}
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+
+pub fn read_config(e: &Env) -> GovernanceConfig {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::Config).unwrap()
+}
+
+pub fn write_config(e: &Env, config: &GovernanceConfig) {
+    e.storage().instance().set(&DataKey::Config, config);
+}
+
+pub fn next_proposal_id(e: &Env) -> u64 {
+    let id: u64 = e.storage().instance().get(&DataKey::NextProposalId).unwrap_or(0);
+    e.storage().instance().set(&DataKey::NextProposalId, &(id + 1));
+    id
+}
+
+pub fn read_proposal(e: &Env, id: u64) -> Option<Proposal> {
+    let key = DataKey::Proposal(id);
+    let proposal = e.storage().persistent().get(&key);
+    if proposal.is_some() {
+        extend_persistent(e, &key);
+    }
+    proposal
+}
+
+pub fn write_proposal(e: &Env, id: u64, proposal: &Proposal) {
+    let key = DataKey::Proposal(id);
+    e.storage().persistent().set(&key, proposal);
+    extend_persistent(e, &key);
+}
+
+pub fn has_voted(e: &Env, id: u64, voter: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::Vote(id, voter.clone()))
+}
+
+pub fn write_vote(e: &Env, id: u64, voter: &Address) {
+    let key = DataKey::Vote(id, voter.clone());
+    e.storage().persistent().set(&key, &true);
+    extend_persistent(e, &key);
+}
+
+pub fn read_snapshot(e: &Env, id: u64) -> Option<ProposalSnapshot> {
+    let key = DataKey::Snapshot(id);
+    let snapshot = e.storage().persistent().get(&key);
+    if snapshot.is_some() {
+        extend_persistent(e, &key);
+    }
+    snapshot
+}
+
+pub fn write_snapshot(e: &Env, id: u64, snapshot: &ProposalSnapshot) {
+    let key = DataKey::Snapshot(id);
+    e.storage().persistent().set(&key, snapshot);
+    extend_persistent(e, &key);
+}
@@ -0,0 +1,75 @@
+use soroban_sdk::{contracttype, Address, Symbol, Val, Vec};
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Accounts allowed to propose and vote
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum VoterSet {
+    /// A fixed list of signers
+    Signers(Vec<Address>),
+    /// The active members of the given yield distributor
+    Members(Address),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GovernanceConfig {
+    pub voters: VoterSet,
+    pub threshold: u32,      // Yes votes needed to execute a proposal
+    pub voting_period: u64,  // In seconds
+}
+
+/// What a proposal does once executed
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProposalAction {
+    /// Call `fn_name` on `target` with `args`, authorized by the governance contract
+    Call(Address, Symbol, Vec<Val>),
+    /// Replace the voter set, threshold and voting period
+    SetConfig(GovernanceConfig),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProposalStatus {
+    Open,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Proposal {
+    pub proposer: Address,
+    pub action: ProposalAction,
+    pub yes_votes: u32,
+    pub no_votes: u32,
+    pub deadline: u64, // Voting and execution close at this timestamp
+    pub status: ProposalStatus,
+}
+
+/// Who may vote on a proposal and how many yes votes it needs, fixed when it is created. A member set
+/// is kept as the distributor and checked against live membership, so only members who joined by
+/// `created_at` and are still members can vote
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalSnapshot {
+    pub voters: VoterSet,
+    pub threshold: u32,
+    pub created_at: u64, // Members must have joined by this timestamp to vote
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Config,
+    NextProposalId,
+    Proposal(u64),
+    Vote(u64, Address), // Set once an account has voted on a proposal
+    Snapshot(u64),      // Voters and threshold a proposal was created under
+}
@@ -0,0 +1,369 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+use yield_distributor::contract::{YieldDistributor, YieldDistributorClient};
+
+use crate::contract::{Governance, GovernanceClient};
+use crate::storage_types::{GovernanceConfig, ProposalAction, ProposalStatus, VoterSet};
+
+const VOTING_PERIOD: u64 = 7 * 86400;
+
+struct TestFixture {
+    env: Env,
+    governance: GovernanceClient<'static>,
+    distributor: YieldDistributorClient<'static>,
+    signers: Vec<Address>,
+}
+
+impl TestFixture {
+    /// A 2-of-3 governance contract administering a yield distributor
+    fn create() -> Self {
+        let env = Env::default();
+        env.ledger().set_timestamp(1_000_000);
+        env.mock_all_auths();
+
+        let owner = Address::generate(&env);
+        let signers = vec![
+            &env,
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+
+        let distributor_id = env.register(
+            YieldDistributor,
+            (
+                Address::generate(&env),
+                1000u32,
+                Address::generate(&env),
+                86400u64,
+                owner.clone(),
+                owner.clone(),
+            ),
+        );
+        let distributor = YieldDistributorClient::new(&env, &distributor_id);
+
+        let config = GovernanceConfig {
+            voters: VoterSet::Signers(signers.clone()),
+            threshold: 2,
+            voting_period: VOTING_PERIOD,
+        };
+        let governance_id = env.register(Governance, (owner.clone(), config));
+        let governance = GovernanceClient::new(&env, &governance_id);
+
+        // Governance becomes the distributor admin
+        distributor.set_admin(&governance_id);
+
+        Self {
+            env,
+            governance,
+            distributor,
+            signers,
+        }
+    }
+
+    fn signer(&self, index: u32) -> Address {
+        self.signers.get(index).unwrap()
+    }
+
+    /// Propose a call on the distributor
+    fn propose_call(&self, fn_name: &str, args: Vec<Val>) -> u64 {
+        let action = ProposalAction::Call(
+            self.distributor.address.clone(),
+            Symbol::new(&self.env, fn_name),
+            args,
+        );
+        self.governance.propose(&self.signer(0), &action)
+    }
+
+    /// Add `count` distributor members and hand voting over to them with the given threshold
+    fn hand_over_to_members(&self, count: u32, threshold: u32) -> Vec<Address> {
+        let mut members = Vec::new(&self.env);
+        for _ in 0..count {
+            let member = Address::generate(&self.env);
+            self.distributor.add_member(&self.governance.address, &member);
+            members.push_back(member);
+        }
+
+        let config = GovernanceConfig {
+            voters: VoterSet::Members(self.distributor.address.clone()),
+            threshold,
+            voting_period: VOTING_PERIOD,
+        };
+        let id = self.governance.propose(&self.signer(0), &ProposalAction::SetConfig(config));
+        self.governance.vote(&self.signer(0), &id, &true);
+        self.governance.vote(&self.signer(1), &id, &true);
+        self.governance.execute(&id);
+        members
+    }
+
+    fn propose_add_member(&self, member: &Address) -> u64 {
+        self.propose_call(
+            "add_member",
+            vec![
+                &self.env,
+                self.governance.address.into_val(&self.env),
+                member.into_val(&self.env),
+            ],
+        )
+    }
+}
+
+#[test]
+fn test_proposal_executes_at_threshold() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(1), &id, &true);
+
+    // Execution needs no signature: the distributor sees governance, its admin, as the caller
+    fixture.env.mock_auths(&[]);
+    fixture.governance.execute(&id);
+
+    assert_eq!(fixture.distributor.list_members(&0, &10), vec![&fixture.env, member]);
+    assert_eq!(fixture.governance.get_proposal(&id).unwrap().status, ProposalStatus::Executed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1604)")]
+fn test_execute_below_threshold() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(1), &id, &false);
+
+    fixture.governance.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1603)")]
+fn test_execute_twice() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(1), &id, &true);
+    fixture.governance.execute(&id);
+
+    fixture.governance.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1603)")]
+fn test_vote_after_deadline() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.env.ledger().set_timestamp(1_000_000 + VOTING_PERIOD + 1);
+
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1602)")]
+fn test_vote_twice() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1601)")]
+fn test_vote_not_signer() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+    let stranger = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.vote(&stranger, &id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1603)")]
+fn test_cancel_proposal() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+
+    let id = fixture.propose_add_member(&member);
+    fixture.governance.cancel(&id);
+    assert_eq!(fixture.governance.get_proposal(&id).unwrap().status, ProposalStatus::Cancelled);
+
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1605)")]
+fn test_threshold_above_signers() {
+    let fixture = TestFixture::create();
+
+    let config = GovernanceConfig {
+        voters: VoterSet::Signers(fixture.signers.clone()),
+        threshold: 4,
+        voting_period: VOTING_PERIOD,
+    };
+    fixture.governance.propose(&fixture.signer(0), &ProposalAction::SetConfig(config));
+}
+
+#[test]
+fn test_members_govern_treasury_share() {
+    let fixture = TestFixture::create();
+    let members = vec![
+        &fixture.env,
+        Address::generate(&fixture.env),
+        Address::generate(&fixture.env),
+        Address::generate(&fixture.env),
+    ];
+    for member in members.iter() {
+        let id = fixture.propose_add_member(&member);
+        fixture.governance.vote(&fixture.signer(0), &id, &true);
+        fixture.governance.vote(&fixture.signer(1), &id, &true);
+        fixture.governance.execute(&id);
+    }
+
+    // Hand voting over to the active distributor members
+    let config = GovernanceConfig {
+        voters: VoterSet::Members(fixture.distributor.address.clone()),
+        threshold: 2,
+        voting_period: VOTING_PERIOD,
+    };
+    let id = fixture.governance.propose(&fixture.signer(0), &ProposalAction::SetConfig(config.clone()));
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(1), &id, &true);
+    fixture.governance.execute(&id);
+    assert_eq!(fixture.governance.get_config(), config);
+    assert!(!fixture.governance.is_voter(&fixture.signer(0)));
+
+    let action = ProposalAction::Call(
+        fixture.distributor.address.clone(),
+        Symbol::new(&fixture.env, "set_treasury_share"),
+        vec![&fixture.env, 2000u32.into_val(&fixture.env)],
+    );
+    let id = fixture.governance.propose(&members.get(0).unwrap(), &action);
+    fixture.governance.vote(&members.get(0).unwrap(), &id, &true);
+    fixture.governance.vote(&members.get(2).unwrap(), &id, &true);
+    fixture.governance.execute(&id);
+
+    assert_eq!(fixture.distributor.get_treasury_share(), 2000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1605)")]
+fn test_threshold_above_members() {
+    let fixture = TestFixture::create();
+    for _ in 0..3 {
+        fixture.distributor.add_member(&fixture.governance.address, &Address::generate(&fixture.env));
+    }
+
+    let config = GovernanceConfig {
+        voters: VoterSet::Members(fixture.distributor.address.clone()),
+        threshold: 4,
+        voting_period: VOTING_PERIOD,
+    };
+    fixture.governance.propose(&fixture.signer(0), &ProposalAction::SetConfig(config));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1601)")]
+fn test_member_joined_after_proposal_cannot_vote() {
+    let fixture = TestFixture::create();
+    let members = fixture.hand_over_to_members(3, 2);
+
+    let id = fixture.governance.propose(&members.get(0).unwrap(), &ProposalAction::SetConfig(fixture.governance.get_config()));
+    fixture.env.ledger().set_timestamp(1_000_000 + 60);
+    let late = Address::generate(&fixture.env);
+    fixture.distributor.add_member(&fixture.governance.address, &late);
+    assert!(fixture.governance.is_voter(&late));
+
+    fixture.governance.vote(&late, &id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1605)")]
+fn test_propose_with_fewer_members_than_threshold() {
+    let fixture = TestFixture::create();
+    let members = fixture.hand_over_to_members(3, 3);
+    fixture.distributor.remove_member(&fixture.governance.address, &members.get(2).unwrap());
+
+    // Two members left can't reach a threshold of 3, so no proposal may pretend otherwise
+    let new_member = Address::generate(&fixture.env);
+    let action = ProposalAction::Call(
+        fixture.distributor.address.clone(),
+        Symbol::new(&fixture.env, "add_member"),
+        vec![
+            &fixture.env,
+            fixture.governance.address.into_val(&fixture.env),
+            new_member.into_val(&fixture.env),
+        ],
+    );
+    fixture.governance.propose(&members.get(0).unwrap(), &action);
+}
+
+#[test]
+fn test_removed_member_cannot_vote_on_open_proposal() {
+    let fixture = TestFixture::create();
+    let members = fixture.hand_over_to_members(3, 2);
+    let new_member = Address::generate(&fixture.env);
+    let action = ProposalAction::Call(
+        fixture.distributor.address.clone(),
+        Symbol::new(&fixture.env, "add_member"),
+        vec![
+            &fixture.env,
+            fixture.governance.address.into_val(&fixture.env),
+            new_member.into_val(&fixture.env),
+        ],
+    );
+    let id = fixture.governance.propose(&members.get(0).unwrap(), &action);
+    assert_eq!(fixture.governance.get_proposal_snapshot(&id).unwrap().threshold, 2);
+
+    // The snapshot keeps the distributor, not its members
+    fixture.distributor.remove_member(&fixture.governance.address, &members.get(2).unwrap());
+    assert!(fixture.governance.try_vote(&members.get(2).unwrap(), &id, &true).is_err());
+    fixture.governance.vote(&members.get(0).unwrap(), &id, &true);
+    fixture.governance.vote(&members.get(1).unwrap(), &id, &true);
+    fixture.governance.execute(&id);
+
+    assert!(fixture.distributor.is_member(&new_member));
+}
+
+#[test]
+fn test_proposal_keeps_threshold_it_was_created_under() {
+    let fixture = TestFixture::create();
+    let member = Address::generate(&fixture.env);
+    let id = fixture.propose_add_member(&member);
+
+    // Raising the threshold does not apply to proposals already open
+    let config = GovernanceConfig {
+        voters: VoterSet::Signers(fixture.signers.clone()),
+        threshold: 3,
+        voting_period: VOTING_PERIOD,
+    };
+    let raise = fixture.governance.propose(&fixture.signer(0), &ProposalAction::SetConfig(config));
+    fixture.governance.vote(&fixture.signer(0), &raise, &true);
+    fixture.governance.vote(&fixture.signer(1), &raise, &true);
+    fixture.governance.execute(&raise);
+
+    fixture.governance.vote(&fixture.signer(0), &id, &true);
+    fixture.governance.vote(&fixture.signer(1), &id, &true);
+    fixture.governance.execute(&id);
+    assert!(fixture.distributor.is_member(&member));
+}
+
+#[test]
+fn test_version_and_migrate() {
+    let fixture = TestFixture::create();
+
+    assert_eq!(fixture.governance.version(), 1);
+    assert_eq!(fixture.governance.migrate(), 1);
+}
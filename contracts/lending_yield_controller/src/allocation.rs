@@ -0,0 +1,145 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env, Map, Symbol, Vec};
+use yield_adapter::lending_adapter::LendingAdapterClient;
+use crate::error::LendingYieldControllerError;
+use crate::storage;
+use crate::storage_types::{AllocationMode, YIELD_TYPE};
+
+/// Fetch the protocols that currently support an asset in the adapter registry
+pub fn supporting_protocols(e: &Env, asset: &Address) -> Vec<Symbol> {
+    let registry_client = storage::adapter_registry_client(e);
+    let mut protocols = Vec::new(e);
+    for (protocol, assets) in registry_client.get_protocols_with_assets(&YIELD_TYPE.id()).iter() {
+        if assets.contains(asset) {
+            protocols.push_back(protocol);
+        }
+    }
+    protocols
+}
+
+/// Split a deposit of `amount` across the protocols supporting an asset
+///
+/// Follows the asset's allocation config: either its target weights or the protocol
+/// with the highest APY. Assets without a config, or whose weighted protocols no longer
+/// support the asset, are split equally. Exit-only protocols receive nothing.
+pub fn deposit_split(e: &Env, asset: &Address, amount: i128) -> Vec<(Symbol, i128)> {
+    let mut protocols = Vec::new(e);
+    for protocol in supporting_protocols(e, asset).iter() {
+        if !storage::is_exit_only(e, &protocol, asset) {
+            protocols.push_back(protocol);
+        }
+    }
+    if protocols.is_empty() {
+        panic_with_error!(e, LendingYieldControllerError::UnsupportedAsset);
+    }
+
+    let config = storage::read_allocation(e, asset);
+    let mode = match &config {
+        Some(config) => config.mode.clone(),
+        None => AllocationMode::Weighted,
+    };
+
+    match mode {
+        AllocationMode::HighestApy => vec![e, (highest_apy_protocol(e, asset, &protocols), amount)],
+        AllocationMode::Weighted => {
+            let mut weights: Vec<(Symbol, u32)> = Vec::new(e);
+            let mut total_weight: u64 = 0;
+            if let Some(config) = &config {
+                for protocol in protocols.iter() {
+                    let weight = config.weights.get(protocol.clone()).unwrap_or(0);
+                    total_weight += weight as u64;
+                    weights.push_back((protocol, weight));
+                }
+            }
+            if total_weight == 0 {
+                weights = Vec::new(e);
+                for protocol in protocols.iter() {
+                    weights.push_back((protocol, 1));
+                }
+                total_weight = protocols.len() as u64;
+            }
+            split_by_weights(e, &weights, total_weight, amount)
+        }
+    }
+}
+
+/// Split a withdrawal of `amount` across the user's positions in an asset
+///
+/// Routes the withdrawal like a deposit of the same amount, capped by the user's
+/// position in each protocol, and takes any shortfall from the user's other positions.
+pub fn withdraw_split(e: &Env, user: &Address, asset: &Address, amount: i128) -> Vec<(Symbol, i128)> {
+    let mut taken: Map<Symbol, i128> = Map::new(e);
+    let mut remaining = amount;
+
+    for (protocol, target) in deposit_split(e, asset, amount).iter() {
+        let take = target.min(storage::read_user_position(e, user, &protocol, asset));
+        if take > 0 {
+            taken.set(protocol, take);
+            remaining -= take;
+        }
+    }
+
+    for protocol in supporting_protocols(e, asset).iter() {
+        if remaining <= 0 {
+            break;
+        }
+        let already_taken = taken.get(protocol.clone()).unwrap_or(0);
+        let available = storage::read_user_position(e, user, &protocol, asset) - already_taken;
+        let take = available.min(remaining);
+        if take > 0 {
+            taken.set(protocol, already_taken + take);
+            remaining -= take;
+        }
+    }
+
+    if remaining > 0 {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientPosition);
+    }
+
+    let mut split = Vec::new(e);
+    for (protocol, take) in taken.iter() {
+        split.push_back((protocol, take));
+    }
+    split
+}
+
+fn highest_apy_protocol(e: &Env, asset: &Address, protocols: &Vec<Symbol>) -> Symbol {
+    let registry_client = storage::adapter_registry_client(e);
+    let mut best = protocols.get_unchecked(0);
+    let mut best_apy = 0u32;
+    for protocol in protocols.iter() {
+        let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&YIELD_TYPE.id(), &protocol));
+        let apy = adapter.get_apy(asset);
+        if apy > best_apy {
+            best = protocol;
+            best_apy = apy;
+        }
+    }
+    best
+}
+
+/// Split `amount` proportionally to `weights`, giving the rounding remainder to the
+/// protocol with the largest weight
+fn split_by_weights(e: &Env, weights: &Vec<(Symbol, u32)>, total_weight: u64, amount: i128) -> Vec<(Symbol, i128)> {
+    let mut parts: Vec<(Symbol, i128)> = Vec::new(e);
+    let mut allocated: i128 = 0;
+    let mut largest: u32 = 0;
+    for (i, (protocol, weight)) in weights.iter().enumerate() {
+        let part = amount * weight as i128 / total_weight as i128;
+        allocated += part;
+        parts.push_back((protocol, part));
+        if weight > weights.get_unchecked(largest).1 {
+            largest = i as u32;
+        }
+    }
+
+    let (protocol, part) = parts.get_unchecked(largest);
+    parts.set(largest, (protocol, part + amount - allocated));
+
+    let mut split = Vec::new(e);
+    for (protocol, part) in parts.iter() {
+        if part > 0 {
+            split.push_back((protocol, part));
+        }
+    }
+    split
+}
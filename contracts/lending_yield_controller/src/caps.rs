@@ -0,0 +1,69 @@
+use soroban_sdk::{panic_with_error, Address, Env, Symbol};
+use yield_adapter::lending_adapter::LendingAdapterClient;
+use crate::error::LendingYieldControllerError;
+use crate::storage_types::YIELD_TYPE;
+use crate::{allocation, storage};
+
+/// Collateral the controller holds in a protocol for an asset, including accrued yield
+fn protocol_exposure(e: &Env, adapter: &LendingAdapterClient, asset: &Address) -> i128 {
+    adapter.get_balance(&e.current_contract_address(), asset)
+}
+
+/// Collateral the controller holds for an asset across every protocol supporting it
+fn asset_exposure(e: &Env, asset: &Address) -> i128 {
+    let registry_client = storage::adapter_registry_client(e);
+    let mut exposure = 0;
+    for protocol in allocation::supporting_protocols(e, asset).iter() {
+        let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&YIELD_TYPE.id(), &protocol));
+        exposure += protocol_exposure(e, &adapter, asset);
+    }
+    exposure
+}
+
+/// Remaining room under the asset's cap, or `None` if the asset is uncapped
+pub fn asset_capacity(e: &Env, asset: &Address) -> Option<i128> {
+    storage::read_asset_cap(e, asset).map(|cap| (cap - asset_exposure(e, asset)).max(0))
+}
+
+/// Remaining room under the protocol's cap for an asset, or `None` if uncapped
+pub fn protocol_capacity(e: &Env, protocol: &Symbol, asset: &Address) -> Option<i128> {
+    storage::read_protocol_cap(e, protocol, asset).map(|cap| {
+        let registry_client = storage::adapter_registry_client(e);
+        let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&YIELD_TYPE.id(), protocol));
+        (cap - protocol_exposure(e, &adapter, asset)).max(0)
+    })
+}
+
+/// Remaining cUSD that may be issued under the supply cap, or `None` if uncapped
+pub fn cusd_capacity(e: &Env) -> Option<i128> {
+    storage::read_cusd_supply_cap(e)
+        .map(|cap| (cap - storage::cusd_manager_client(e).cusd_total_supply()).max(0))
+}
+
+/// Panic unless `amount` of an asset fits under both the asset and the protocol caps
+pub fn check_deposit(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) {
+    check_protocol_deposit(e, protocol, asset, amount);
+    if let Some(capacity) = asset_capacity(e, asset) {
+        if amount > capacity {
+            panic_with_error!(e, LendingYieldControllerError::DepositCapExceeded);
+        }
+    }
+}
+
+/// Panic unless `amount` of an asset fits under the protocol's cap
+pub fn check_protocol_deposit(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) {
+    if let Some(capacity) = protocol_capacity(e, protocol, asset) {
+        if amount > capacity {
+            panic_with_error!(e, LendingYieldControllerError::DepositCapExceeded);
+        }
+    }
+}
+
+/// Panic unless issuing `amount` cUSD fits under the supply cap
+pub fn check_issue(e: &Env, amount: i128) {
+    if let Some(capacity) = cusd_capacity(e) {
+        if amount > capacity {
+            panic_with_error!(e, LendingYieldControllerError::DepositCapExceeded);
+        }
+    }
+}
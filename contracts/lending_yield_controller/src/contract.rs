@@ -1,7 +1,11 @@
-use soroban_sdk::{ contract, contractimpl, contractmeta, panic_with_error, Address, BytesN, Env, Symbol};
+use soroban_sdk::{ contract, contractimpl, contractmeta, panic_with_error, Address, BytesN, Env, Map, Symbol, Vec};
+use access_control::{error::AccessControlError, role::Role};
 use crate::error::LendingYieldControllerError;
 use crate::events::LendingYieldControllerEvents;
-use crate::{storage, controls};
+use crate::storage_types::{
+    AllocationConfig, AllocationMode, AssetOracleConfig, PauseFlag, RedemptionQueue, RedemptionTicket, ClaimYieldResult, HarvestAllCursor, HarvestAllResult, HarvestState, UserPosition,
+};
+use crate::{storage, controls, pricing, caps, migrations};
 
 contractmeta!(
     key = "Description",
@@ -11,6 +15,34 @@ contractmeta!(
 fn require_admin(e: &Env) { storage::read_admin(e).require_auth(); }
 fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
 
+/// Authorize `caller` as either the admin or the owner.
+fn require_admin_or_owner(e: &Env, caller: &Address) {
+    caller.require_auth();
+    if *caller != storage::read_admin(e) && *caller != storage::read_owner(e) {
+        panic_with_error!(e, LendingYieldControllerError::UnauthorizedError);
+    }
+}
+
+/// Authorize `caller` to drive the harvest pipeline and return whether it acts as a keeper.
+/// The admin and keeper role holders may always call; anyone else only once distribution is available.
+fn require_admin_or_keeper(e: &Env, caller: &Address) -> bool {
+    caller.require_auth();
+    if *caller == storage::read_admin(e) {
+        return false;
+    }
+    if !access_control::has_role(e, Role::Keeper, caller)
+        && !storage::distributor_client(e).is_distribution_available()
+    {
+        panic_with_error!(e, LendingYieldControllerError::YieldUnavailable);
+    }
+    true
+}
+
+/// Whether `caller` holds the admin or keeper role, as opposed to calling once distribution is available
+fn is_admin_or_keeper_role(e: &Env, caller: &Address) -> bool {
+    *caller == storage::read_admin(e) || access_control::has_role(e, Role::Keeper, caller)
+}
+
 pub trait LendingYieldControllerTrait {
     /// Initialize the Lending Yield Controller contract
     ///
@@ -73,9 +105,180 @@ pub trait LendingYieldControllerTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn set_admin(e: &Env, new_admin: Address);
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Propose a new admin who takes over once they accept, as an alternative to setting it directly
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The proposed admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_admin(e: &Env, new_admin: Address);
+
+    /// (Proposed admin only) Accept a pending admin transfer
+    ///
+    /// ### Panics
+    /// If no admin transfer is pending
+    /// If the caller is not the proposed admin
+    fn accept_admin(e: &Env);
+
+    /// (Owner only) Cancel a pending admin transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no admin transfer is pending
+    fn cancel_admin_transfer(e: &Env);
+
+    /// Fetch the proposed admin, if an admin transfer is pending
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Grant a role to an account. Granting the admin role replaces the current admin
+    ///
+    /// ### Arguments
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner role
+    fn grant_role(e: &Env, role: Role, account: Address);
+
+    /// (Owner only) Revoke a role from an account
+    ///
+    /// ### Arguments
+    /// * `role` - The role to revoke
+    /// * `account` - The account losing the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner or admin role
+    fn revoke_role(e: &Env, role: Role, account: Address);
+
+    /// Give up a role held by `account`
+    ///
+    /// ### Arguments
+    /// * `account` - The account giving up the role
+    /// * `role` - The role to give up
+    ///
+    /// ### Panics
+    /// If `account` does not authorize the call
+    /// If `role` is the owner or admin role
+    fn renounce_role(e: &Env, account: Address, role: Role);
+
+    /// Check whether an account holds a role
+    ///
+    /// ### Arguments
+    /// * `role` - The role to check
+    /// * `account` - The account to check
+    fn has_role(e: &Env, role: Role, account: Address) -> bool;
+
+    /// (Pauser only) Pause an operation
+    ///
+    /// ### Arguments
+    /// * `caller` - An account holding the pauser role
+    /// * `flag` - The operation to pause
+    ///
+    /// ### Panics
+    /// If the caller does not hold the pauser role
+    fn pause(e: &Env, caller: Address, flag: PauseFlag);
+
+    /// (Owner only) Unpause an operation
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to unpause
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn unpause(e: &Env, flag: PauseFlag);
+
+    /// Check whether an operation is paused
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to check
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool;
     
+    /// (Admin only) Set the number of decimals of a collateral asset
+    ///
+    /// Deposits and withdrawals of the asset are scaled to cUSD's 7 decimals.
+    /// Assets without a configured value use the decimals reported by the token.
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the collateral asset
+    /// * `decimals` - The number of decimals of the asset (at most 18)
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `decimals` exceeds 18
+    fn set_asset_decimals(e: &Env, asset: Address, decimals: u32);
+
+    /// Fetch the number of decimals used to scale a collateral asset to cUSD
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the collateral asset
+    fn get_asset_decimals(e: &Env, asset: Address) -> u32;
+
+    /// (Admin only) Price a collateral asset through a SEP-40 oracle
+    ///
+    /// Deposits of the asset issue cUSD for the USD value of the collateral multiplied
+    /// by the collateral factor. Withdrawals burn cUSD using the same valuation.
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the collateral asset
+    /// * `oracle` - The address of the SEP-40 oracle quoting the asset in USD
+    /// * `collateral_factor_bps` - Share of the collateral value issued as cUSD, in basis points
+    /// * `max_price_age` - Maximum age of an oracle price in seconds
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `collateral_factor_bps` is zero or greater than 10000
+    fn set_asset_oracle(e: &Env, asset: Address, oracle: Address, collateral_factor_bps: u32, max_price_age: u64);
+
+    /// (Admin only) Remove oracle pricing from a collateral asset, valuing it at $1 again
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the collateral asset
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn remove_asset_oracle(e: &Env, asset: Address);
+
+    /// Fetch the oracle pricing configured for a collateral asset, if any
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the collateral asset
+    fn get_asset_oracle(e: &Env, asset: Address) -> Option<AssetOracleConfig>;
+
     /// Deposit collateral into a lending protocol through the yield controller
     ///
+    /// Issues cUSD for the deposit, scaled from the asset's decimals to cUSD's 7 decimals
+    /// and priced through the asset's oracle if one is configured.
+    /// cUSD is issued for the amount the adapter reports as deposited.
     /// Returns the actual amount deposited into the protocol
     ///
     /// ### Arguments
@@ -83,20 +286,29 @@ pub trait LendingYieldControllerTrait {
     /// * `user` - The address of the user depositing collateral
     /// * `asset` - The address of the asset being deposited
     /// * `amount` - The amount of the asset to deposit
+    /// * `min_out` - The minimum amount of cUSD to issue, if any
     ///
     /// ### Panics
     /// If the user does not authorize the transaction
     /// If the protocol is not registered in the adapter registry
+    /// If less than `min_out` cUSD would be issued
+    /// If the protocol is exit-only for the asset
+    /// If deposits or minting are paused
     fn deposit_collateral(
         e: &Env,
         protocol: Symbol,
         user: Address,
         asset: Address,
         amount: i128,
+        min_out: Option<i128>,
     ) -> i128;
     
     /// Withdraw collateral from a lending protocol through the yield controller
     ///
+    /// Burns cUSD for the withdrawal, scaled from the asset's decimals to cUSD's 7 decimals
+    /// and priced through the asset's oracle if one is configured.
+    /// cUSD is burned for the amount the adapter reports as withdrawn.
+    /// Positions in an exit-only protocol are paid out of the principal recovered from it.
     /// Returns the actual amount withdrawn from the protocol
     ///
     /// ### Arguments
@@ -104,18 +316,265 @@ pub trait LendingYieldControllerTrait {
     /// * `user` - The address of the user withdrawing collateral
     /// * `asset` - The address of the asset being withdrawn
     /// * `amount` - The amount of the asset to withdraw
+    /// * `max_burn` - The maximum amount of cUSD to burn, if any
+    /// * `allow_partial` - Withdraw only what the protocol's liquidity allows instead of failing
     ///
     /// ### Panics
     /// If the user does not authorize the transaction
-    /// If the user has insufficient deposited balance
+    /// If the user's position for the protocol and asset is smaller than `amount`
+    /// If more than `max_burn` cUSD would be burned
+    /// If withdrawals are paused
     fn withdraw_collateral(
         e: &Env,
         protocol: Symbol,
         user: Address,
         asset: Address,
         amount: i128,
+        max_burn: Option<i128>,
+        allow_partial: bool,
     ) -> i128;
     
+    /// Fetch the collateral a user has deposited into a protocol for an asset
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the collateral asset
+    fn get_user_position(e: &Env, user: Address, protocol: Symbol, asset: Address) -> i128;
+
+    /// Fetch every open position of a user across protocols and assets
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_positions(e: &Env, user: Address) -> Vec<UserPosition>;
+
+    /// Fetch the cUSD issued against a user's position, which withdrawals burn pro-rata
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the collateral asset
+    fn get_user_debt(e: &Env, user: Address, protocol: Symbol, asset: Address) -> i128;
+
+    /// Deposit collateral split across every protocol that supports the asset
+    ///
+    /// The split follows the asset's allocation config, or an equal split if none is set.
+    /// Returns the total amount deposited into the protocols
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user depositing collateral
+    /// * `asset` - The address of the asset being deposited
+    /// * `amount` - The amount of the asset to deposit
+    /// * `min_out` - The minimum total amount of cUSD to issue, if any
+    ///
+    /// ### Panics
+    /// If the user does not authorize the transaction
+    /// If no registered protocol supports the asset
+    /// If less than `min_out` cUSD would be issued
+    /// If deposits or minting are paused
+    fn deposit_collateral_auto(
+        e: &Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+        min_out: Option<i128>,
+    ) -> i128;
+
+    /// Withdraw collateral split across the user's positions in the asset
+    ///
+    /// The withdrawal is routed like a deposit of the same amount, capped by each position,
+    /// with any shortfall taken from the user's other positions.
+    /// Returns the total amount withdrawn from the protocols
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user withdrawing collateral
+    /// * `asset` - The address of the asset being withdrawn
+    /// * `amount` - The amount of the asset to withdraw
+    /// * `max_burn` - The maximum total amount of cUSD to burn, if any
+    ///
+    /// ### Panics
+    /// If the user does not authorize the transaction
+    /// If the user's positions in the asset add up to less than `amount`
+    /// If more than `max_burn` cUSD would be burned
+    /// If withdrawals are paused
+    fn withdraw_collateral_auto(
+        e: &Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+        max_burn: Option<i128>,
+    ) -> i128;
+
+    /// (Admin only) Set how deposits without a named protocol are split for an asset
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the asset
+    /// * `mode` - `Weighted` to split by `weights`, or `HighestApy` to use the protocol with the highest APY
+    /// * `weights` - The target weight of each protocol, used in `Weighted` mode
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `mode` is `Weighted` and the weights are all zero or name a protocol that does not support the asset
+    fn set_allocation(e: &Env, asset: Address, mode: AllocationMode, weights: Map<Symbol, u32>);
+
+    /// Fetch the allocation config for an asset, if one is set
+    fn get_allocation(e: &Env, asset: Address) -> Option<AllocationConfig>;
+
+    /// Queue a withdrawal to be filled once the protocol has liquidity
+    ///
+    /// Escrows the cUSD the withdrawal would burn and removes `amount` from the user's position.
+    /// Returns the ticket id
+    ///
+    /// ### Arguments
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `user` - The address of the user withdrawing collateral
+    /// * `asset` - The address of the asset being withdrawn
+    /// * `amount` - The amount of the asset to withdraw
+    ///
+    /// ### Panics
+    /// If the user does not authorize the transaction
+    /// If the user's position for the protocol and asset is smaller than `amount`
+    /// If the user does not hold enough cUSD to escrow
+    /// If withdrawals are paused
+    fn request_redemption(e: &Env, protocol: Symbol, user: Address, asset: Address, amount: i128) -> u64;
+
+    /// Cancel a queued withdrawal, restoring the user's position and returning the remaining escrowed cUSD
+    ///
+    /// ### Arguments
+    /// * `id` - The ticket id
+    ///
+    /// ### Panics
+    /// If the ticket does not exist
+    /// If the ticket's user does not authorize the transaction
+    fn cancel_redemption(e: &Env, id: u64);
+
+    /// Fill a protocol/asset pair's queued withdrawals first-in-first-out as liquidity allows.
+    /// Callable by anyone.
+    ///
+    /// Each pair has its own queue. Processing stops at the first ticket that cannot be filled in
+    /// full, which keeps its place at the head of the pair's queue. Returns the number of tickets filled
+    ///
+    /// ### Arguments
+    /// * `protocol` - The protocol the tickets were queued against
+    /// * `asset` - The asset the tickets withdraw
+    /// * `limit` - The maximum number of tickets to examine
+    ///
+    /// ### Panics
+    /// If withdrawals are paused
+    fn process_queue(e: &Env, protocol: Symbol, asset: Address, limit: u32) -> u32;
+
+    /// Fetch a queued withdrawal, if it is still pending
+    fn get_redemption(e: &Env, id: u64) -> Option<RedemptionTicket>;
+
+    /// Fetch the bounds of a protocol/asset pair's redemption queue
+    ///
+    /// ### Arguments
+    /// * `protocol` - The protocol the tickets were queued against
+    /// * `asset` - The asset the tickets withdraw
+    fn get_redemption_queue(e: &Env, protocol: Symbol, asset: Address) -> RedemptionQueue;
+
+    /// (Admin only) Move principal from one protocol to another
+    ///
+    /// cUSD supply is unchanged and users keep their positions in `from_protocol`;
+    /// their withdrawals are served from `to_protocol` until the moved principal is repaid.
+    /// Returns the amount deposited into `to_protocol`
+    ///
+    /// ### Arguments
+    /// * `from_protocol` - The symbol identifier of the protocol to withdraw from
+    /// * `to_protocol` - The symbol identifier of the protocol to deposit into
+    /// * `asset` - The address of the asset
+    /// * `amount` - The amount of principal to move
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If either protocol does not support the asset or has a pending harvest
+    /// If the protocols are the same or `amount` exceeds the principal held in `from_protocol`
+    /// If deposits are paused
+    fn rebalance(e: &Env, from_protocol: Symbol, to_protocol: Symbol, asset: Address, amount: i128) -> i128;
+
+    /// (Admin or owner) Pull the controller's whole balance of an asset out of a protocol
+    ///
+    /// The protocol is marked exit-only for the asset: it accepts no further deposits,
+    /// rebalances or recompounds, and `withdraw_collateral` pays users out of the recovered
+    /// principal held by the controller. Accrued yield is moved to the asset's idle balance.
+    /// Can be called again to recover what an illiquid pool could not pay out.
+    /// Returns the total amount recovered
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or owner address
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the asset
+    ///
+    /// ### Panics
+    /// If the caller is neither the admin nor the owner
+    /// If the protocol has a pending harvest for the asset
+    fn emergency_withdraw_all(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128;
+
+    /// Check whether a protocol was emergency withdrawn for an asset
+    ///
+    /// ### Arguments
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the asset
+    fn is_exit_only(e: &Env, protocol: Symbol, asset: Address) -> bool;
+
+    /// Fetch the principal recovered from an exit-only protocol that users have not yet withdrawn
+    ///
+    /// ### Arguments
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the asset
+    fn get_recovered_balance(e: &Env, protocol: Symbol, asset: Address) -> i128;
+
+    /// (Admin only) Cap the collateral held for an asset across all protocols
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the asset
+    /// * `cap` - The maximum amount of the asset, or `None` to remove the cap
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `cap` is negative
+    fn set_asset_cap(e: &Env, asset: Address, cap: Option<i128>);
+
+    /// Fetch the collateral cap for an asset, if one is set
+    fn get_asset_cap(e: &Env, asset: Address) -> Option<i128>;
+
+    /// Fetch how much more of an asset can be deposited across all protocols, or `None` if uncapped
+    fn get_asset_capacity(e: &Env, asset: Address) -> Option<i128>;
+
+    /// (Admin only) Cap the collateral held for an asset in a single protocol
+    ///
+    /// ### Arguments
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the asset
+    /// * `cap` - The maximum amount of the asset, or `None` to remove the cap
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `cap` is negative
+    fn set_protocol_cap(e: &Env, protocol: Symbol, asset: Address, cap: Option<i128>);
+
+    /// Fetch the collateral cap for a protocol and asset, if one is set
+    fn get_protocol_cap(e: &Env, protocol: Symbol, asset: Address) -> Option<i128>;
+
+    /// Fetch how much more of an asset can be deposited into a protocol, or `None` if uncapped
+    fn get_protocol_capacity(e: &Env, protocol: Symbol, asset: Address) -> Option<i128>;
+
+    /// (Admin only) Cap the total cUSD supply that deposits may issue up to
+    ///
+    /// ### Arguments
+    /// * `cap` - The maximum cUSD supply, or `None` to remove the cap
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `cap` is negative
+    fn set_cusd_supply_cap(e: &Env, cap: Option<i128>);
+
+    /// Fetch the cUSD supply cap, if one is set
+    fn get_cusd_supply_cap(e: &Env) -> Option<i128>;
+
+    /// Fetch how much more cUSD deposits can issue, or `None` if uncapped
+    fn get_cusd_capacity(e: &Env) -> Option<i128>;
+
     /// Fetch the accumulated yield for a specific protocol and asset
     ///
     /// ### Arguments
@@ -123,67 +582,121 @@ pub trait LendingYieldControllerTrait {
     /// * `asset` - The address of the asset to check yield for
     fn get_yield(e: &Env, protocol: Symbol, asset: Address) -> i128;
 
-    /// Claim accumulated yield for a specific protocol and asset, and distribute it
+    /// (Admin or keeper) Claim accumulated yield for a specific protocol and asset, and distribute it
     /// NOTE: This is a single-transaction version that may fail on budget limits.
     /// For large operations, use the 3-stage process: harvest_yield -> recompound_yield -> finalize_distribution
     ///
-    /// Returns the amount of yield claimed and distributed
+    /// Resumes from any pending harvest and runs the remaining stages. If distribution is not
+    /// yet available it stops after recompounding, leaving the harvest pending for a later call.
+    /// Anyone may call once distribution is available, receiving the keeper bounty on distribution.
+    /// Returns the last stage reached and the amount handled by it
     ///
     /// ### Arguments
+    /// * `caller` - The address driving the claim, either the admin or a keeper
     /// * `protocol` - The symbol identifier of the lending protocol
     /// * `asset` - The address of the asset to claim yield for
     ///
+    /// ### Panics
+    /// If the caller does not authorize the transaction
+    /// If the caller is not admin and distribution is not available
+    /// If the protocol is not registered in the adapter registry
+    /// If harvesting is paused
+    fn claim_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> ClaimYieldResult;
+
+    /// (Admin or keeper) Harvest yield across every registered protocol and asset
+    ///
+    /// Harvests and recompounds up to `limit` protocol/asset pairs per call, resuming from
+    /// where the previous call stopped. When the last pair is reached, the accumulated yield
+    /// is issued and distributed as a single cUSD distribution for the epoch, and a keeper
+    /// completing the walk receives the keeper bounty. Pairs with a staged harvest in
+    /// progress are skipped.
+    ///
+    /// ### Arguments
+    /// * `caller` - The address driving the harvest, either the admin or a keeper
+    /// * `limit` - The maximum number of protocol/asset pairs to harvest in this call
+    ///
+    /// ### Panics
+    /// If the caller does not authorize the transaction
+    /// If distribution is not available
+    /// If harvesting is paused
+    fn harvest_all(e: &Env, caller: Address, limit: u32) -> HarvestAllResult;
+
+    /// Fetch the progress of an unfinished `harvest_all` walk, if any
+    fn get_harvest_all_cursor(e: &Env) -> Option<HarvestAllCursor>;
 
     // =========================================================================
-    // Multi-stage yield claiming (Admin only)
+    // Multi-stage yield claiming (Admin and keeper role, or anyone once distribution is available)
     // Use these functions when claim_yield exceeds budget limits
     // =========================================================================
 
-    /// (Admin only) Stage 1: Harvest yield from the lending protocol
+    /// (Admin only) Set the bounty paid to keepers out of distributed yield
+    ///
+    /// ### Arguments
+    /// * `bounty_bps` - Share of the yield paid to the keeper, in basis points
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `bounty_bps` is greater than 10000
+    fn set_keeper_bounty(e: &Env, bounty_bps: u32);
+
+    /// Fetch the bounty paid to keepers out of distributed yield, in basis points
+    fn get_keeper_bounty(e: &Env) -> u32;
+
+    /// (Admin or keeper) Stage 1: Harvest yield from the lending protocol
     /// Withdraws accumulated yield from the protocol and stores it for later processing.
     ///
-    /// Returns the amount of yield harvested
+    /// Returns the amount of yield harvested. Without yield, the admin or a keeper role holder
+    /// advances the epoch with an empty distribution once it is due; other callers get 0.
     ///
     /// ### Arguments
+    /// * `caller` - The address driving the harvest, either the admin or a keeper
     /// * `protocol` - The symbol identifier of the lending protocol
     /// * `asset` - The address of the asset to harvest yield for
     ///
     /// ### Panics
-    /// If caller is not admin
+    /// If the caller does not authorize the transaction
+    /// If the caller is not admin and distribution is not available
     /// If no yield is available to harvest
     /// If a harvest is already in progress for this protocol/asset
-    fn harvest_yield(e: &Env, protocol: Symbol, asset: Address) -> i128;
+    /// If harvesting is paused
+    fn harvest_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128;
 
-    /// (Admin only) Stage 2: Recompound harvested yield back into the protocol
+    /// (Admin or keeper) Stage 2: Recompound harvested yield back into the protocol
     /// Re-deposits the harvested yield to continue earning interest.
     ///
     /// Returns the amount recompounded
     ///
     /// ### Arguments
+    /// * `caller` - The address driving the harvest, either the admin or a keeper
     /// * `protocol` - The symbol identifier of the lending protocol
     /// * `asset` - The address of the asset to recompound
     ///
     /// ### Panics
-    /// If caller is not admin
+    /// If the caller does not authorize the transaction
+    /// If the caller is not admin and distribution is not available
     /// If no pending harvest exists
     /// If harvest is not in correct state
-    fn recompound_yield(e: &Env, protocol: Symbol, asset: Address) -> i128;
+    /// If harvesting is paused
+    fn recompound_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128;
 
-    /// (Admin only) Stage 3: Finalize distribution of yield to members
-    /// Issues cUSD and distributes to treasury and members.
+    /// (Admin or keeper) Stage 3: Finalize distribution of yield to members
+    /// Issues cUSD and distributes to treasury and members. A keeper caller is first paid
+    /// the keeper bounty out of the yield.
     ///
     /// Returns the amount distributed
     ///
     /// ### Arguments
+    /// * `caller` - The address driving the harvest, either the admin or a keeper
     /// * `protocol` - The symbol identifier of the lending protocol
     /// * `asset` - The address of the asset to finalize distribution for
     ///
     /// ### Panics
-    /// If caller is not admin
+    /// If the caller does not authorize the transaction
     /// If no pending harvest exists
     /// If harvest is not in correct state (must be recompounded)
     /// If distribution is not available
-    fn finalize_distribution(e: &Env, protocol: Symbol, asset: Address) -> i128;
+    /// If distribution is paused
+    fn finalize_distribution(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128;
 
     /// Fetch the pending harvest state for a protocol/asset pair
     ///
@@ -194,6 +707,7 @@ pub trait LendingYieldControllerTrait {
 
     /// (Admin only) Cancel a pending harvest operation
     /// Use this to reset state if something goes wrong during multi-stage process.
+    /// Yield already harvested but not yet recompounded is moved to the asset's idle balance.
     ///
     /// ### Arguments
     /// * `protocol` - The symbol identifier of the lending protocol
@@ -203,6 +717,40 @@ pub trait LendingYieldControllerTrait {
     /// If caller is not admin
     fn cancel_harvest(e: &Env, protocol: Symbol, asset: Address);
 
+    /// Fetch the collateral held by the controller outside of any protocol for an asset
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the asset
+    fn get_idle_balance(e: &Env, asset: Address) -> i128;
+
+    /// (Admin only) Deposit idle collateral back into a lending protocol
+    ///
+    /// Returns the amount deposited into the protocol
+    ///
+    /// ### Arguments
+    /// * `protocol` - The symbol identifier of the lending protocol
+    /// * `asset` - The address of the asset
+    /// * `amount` - The amount of idle collateral to deposit
+    ///
+    /// ### Panics
+    /// If caller is not admin
+    /// If the idle balance of the asset is smaller than `amount`
+    /// If the asset is not supported by the protocol
+    /// If deposits are paused
+    fn redeposit_idle(e: &Env, protocol: Symbol, asset: Address, amount: i128) -> i128;
+
+    /// (Admin only) Transfer idle collateral out of the controller
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the asset
+    /// * `to` - The address receiving the collateral
+    /// * `amount` - The amount of idle collateral to transfer
+    ///
+    /// ### Panics
+    /// If caller is not admin
+    /// If the idle balance of the asset is smaller than `amount`
+    fn sweep_idle(e: &Env, asset: Address, to: Address, amount: i128);
+
     /// Claim emissions rewards from a specific protocol for an asset
     ///
     /// Returns the total amount of emissions claimed
@@ -210,6 +758,9 @@ pub trait LendingYieldControllerTrait {
     /// ### Arguments
     /// * `protocol` - The symbol identifier of the lending protocol
     /// * `asset` - The address of the asset for which to claim emissions
+    ///
+    /// ### Panics
+    /// If harvesting is paused
     fn claim_emissions(e: &Env, protocol: Symbol, asset: Address) -> i128;
 
     /// Fetch the accumulated emissions rewards for a specific protocol and asset
@@ -234,6 +785,17 @@ pub trait LendingYieldControllerTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
 }
 
 
@@ -259,6 +821,7 @@ impl LendingYieldControllerTrait for LendingYieldController {
         storage::set_cusd_manager(&e, cusd_manager);
         storage::write_admin(&e, admin); 
         storage::write_owner(&e, owner);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
     }
 
     fn deposit_collateral(
@@ -267,12 +830,14 @@ impl LendingYieldControllerTrait for LendingYieldController {
         user: Address,
         asset: Address,
         amount: i128,
+        min_out: Option<i128>,
     ) -> i128 {
         user.require_auth();
+        controls::require_not_paused(e, PauseFlag::Deposit);
         
-        let deposited = controls::process_deposit(&e, &protocol, user.clone(), asset.clone(), amount);
+        let (deposited, _) = controls::process_deposit(&e, &protocol, user.clone(), asset.clone(), amount, min_out);
         
-        LendingYieldControllerEvents::deposit_collateral(&e, user, asset, amount);
+        LendingYieldControllerEvents::deposit_collateral(&e, user, asset, deposited);
 
         deposited
     }
@@ -283,16 +848,189 @@ impl LendingYieldControllerTrait for LendingYieldController {
         user: Address,
         asset: Address,
         amount: i128,
+        max_burn: Option<i128>,
+        allow_partial: bool,
     ) -> i128 {
         user.require_auth();
+        controls::require_not_paused(e, PauseFlag::Withdraw);
         
-        let withdrawn = controls::process_withdraw_collateral(&e, &protocol, user.clone(), asset.clone(), amount);
+        let (withdrawn, _) = controls::process_withdraw_collateral(&e, &protocol, user.clone(), asset.clone(), amount, max_burn, allow_partial);
         
-        LendingYieldControllerEvents::withdraw_collateral(&e, user, asset, amount);
+        LendingYieldControllerEvents::withdraw_collateral(&e, user, asset, withdrawn);
         
         withdrawn
     }
 
+    fn get_user_position(e: &Env, user: Address, protocol: Symbol, asset: Address) -> i128 {
+        storage::read_user_position(e, &user, &protocol, &asset)
+    }
+
+    fn get_user_positions(e: &Env, user: Address) -> Vec<UserPosition> {
+        storage::read_user_positions(e, &user)
+    }
+
+    fn get_user_debt(e: &Env, user: Address, protocol: Symbol, asset: Address) -> i128 {
+        controls::read_position_debt(e, &user, &protocol, &asset)
+    }
+
+    fn deposit_collateral_auto(
+        e: &Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+        min_out: Option<i128>,
+    ) -> i128 {
+        user.require_auth();
+        controls::require_not_paused(e, PauseFlag::Deposit);
+
+        let (deposited, _) = controls::process_deposit_auto(e, user.clone(), asset.clone(), amount, min_out);
+
+        LendingYieldControllerEvents::deposit_collateral(e, user, asset, deposited);
+
+        deposited
+    }
+
+    fn withdraw_collateral_auto(
+        e: &Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+        max_burn: Option<i128>,
+    ) -> i128 {
+        user.require_auth();
+        controls::require_not_paused(e, PauseFlag::Withdraw);
+
+        let (withdrawn, _) = controls::process_withdraw_auto(e, user.clone(), asset.clone(), amount, max_burn);
+
+        LendingYieldControllerEvents::withdraw_collateral(e, user, asset, withdrawn);
+
+        withdrawn
+    }
+
+    fn set_allocation(e: &Env, asset: Address, mode: AllocationMode, weights: Map<Symbol, u32>) {
+        require_admin(e);
+
+        let config = controls::process_set_allocation(e, asset.clone(), mode, weights);
+
+        LendingYieldControllerEvents::set_allocation(e, asset, config);
+    }
+
+    fn get_allocation(e: &Env, asset: Address) -> Option<AllocationConfig> {
+        storage::read_allocation(e, &asset)
+    }
+
+    fn request_redemption(e: &Env, protocol: Symbol, user: Address, asset: Address, amount: i128) -> u64 {
+        user.require_auth();
+        controls::require_not_paused(e, PauseFlag::Withdraw);
+
+        let ticket = controls::process_request_redemption(e, protocol, user, asset, amount);
+        let id = ticket.id;
+
+        LendingYieldControllerEvents::request_redemption(e, ticket);
+
+        id
+    }
+
+    fn cancel_redemption(e: &Env, id: u64) {
+        let ticket = storage::read_redemption_ticket(e, id)
+            .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::RedemptionNotFound));
+        ticket.user.require_auth();
+
+        controls::process_cancel_redemption(e, &ticket);
+
+        LendingYieldControllerEvents::cancel_redemption(e, ticket);
+    }
+
+    fn process_queue(e: &Env, protocol: Symbol, asset: Address, limit: u32) -> u32 {
+        controls::require_not_paused(e, PauseFlag::Withdraw);
+        controls::process_queue(e, &protocol, &asset, limit)
+    }
+
+    fn get_redemption(e: &Env, id: u64) -> Option<RedemptionTicket> {
+        storage::read_redemption_ticket(e, id)
+    }
+
+    fn get_redemption_queue(e: &Env, protocol: Symbol, asset: Address) -> RedemptionQueue {
+        storage::read_redemption_queue(e, &protocol, &asset)
+    }
+
+    fn rebalance(e: &Env, from_protocol: Symbol, to_protocol: Symbol, asset: Address, amount: i128) -> i128 {
+        require_admin(e);
+        controls::require_not_paused(e, PauseFlag::Deposit);
+
+        let deposited = controls::process_rebalance(e, &from_protocol, &to_protocol, asset.clone(), amount);
+
+        LendingYieldControllerEvents::rebalance(e, from_protocol, to_protocol, asset, deposited);
+
+        deposited
+    }
+
+    fn emergency_withdraw_all(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128 {
+        require_admin_or_owner(e, &caller);
+
+        let (principal, yield_amount) = controls::process_emergency_withdraw_all(e, &protocol, asset.clone());
+
+        LendingYieldControllerEvents::emergency_withdraw_all(e, protocol, asset, principal, yield_amount);
+
+        principal + yield_amount
+    }
+
+    fn is_exit_only(e: &Env, protocol: Symbol, asset: Address) -> bool {
+        storage::is_exit_only(e, &protocol, &asset)
+    }
+
+    fn get_recovered_balance(e: &Env, protocol: Symbol, asset: Address) -> i128 {
+        storage::read_recovered_balance(e, &protocol, &asset)
+    }
+
+    fn set_asset_cap(e: &Env, asset: Address, cap: Option<i128>) {
+        require_admin(e);
+
+        controls::process_set_asset_cap(e, asset.clone(), cap);
+
+        LendingYieldControllerEvents::set_asset_cap(e, asset, cap);
+    }
+
+    fn get_asset_cap(e: &Env, asset: Address) -> Option<i128> {
+        storage::read_asset_cap(e, &asset)
+    }
+
+    fn get_asset_capacity(e: &Env, asset: Address) -> Option<i128> {
+        caps::asset_capacity(e, &asset)
+    }
+
+    fn set_protocol_cap(e: &Env, protocol: Symbol, asset: Address, cap: Option<i128>) {
+        require_admin(e);
+
+        controls::process_set_protocol_cap(e, protocol.clone(), asset.clone(), cap);
+
+        LendingYieldControllerEvents::set_protocol_cap(e, protocol, asset, cap);
+    }
+
+    fn get_protocol_cap(e: &Env, protocol: Symbol, asset: Address) -> Option<i128> {
+        storage::read_protocol_cap(e, &protocol, &asset)
+    }
+
+    fn get_protocol_capacity(e: &Env, protocol: Symbol, asset: Address) -> Option<i128> {
+        caps::protocol_capacity(e, &protocol, &asset)
+    }
+
+    fn set_cusd_supply_cap(e: &Env, cap: Option<i128>) {
+        require_admin(e);
+
+        controls::process_set_cusd_supply_cap(e, cap);
+
+        LendingYieldControllerEvents::set_cusd_supply_cap(e, cap);
+    }
+
+    fn get_cusd_supply_cap(e: &Env) -> Option<i128> {
+        storage::read_cusd_supply_cap(e)
+    }
+
+    fn get_cusd_capacity(e: &Env) -> Option<i128> {
+        caps::cusd_capacity(e)
+    }
+
     fn get_yield(e: &Env, protocol: Symbol, asset: Address) -> i128 {
         controls::read_yield(e, &protocol, asset)
     }
@@ -302,6 +1040,7 @@ impl LendingYieldControllerTrait for LendingYieldController {
     }
 
     fn claim_emissions(e: &Env, protocol: Symbol, asset: Address) -> i128 {
+        controls::require_not_paused(e, PauseFlag::Harvest);
         let claimed_total = controls::process_claim_emissions(e, &protocol, asset.clone());
 
         LendingYieldControllerEvents::claim_emissions(
@@ -350,6 +1089,104 @@ impl LendingYieldControllerTrait for LendingYieldController {
         LendingYieldControllerEvents::set_admin(e, new_admin);
     }
 
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn propose_admin(e: &Env, new_admin: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Admin, &new_admin);
+    }
+
+    fn accept_admin(e: &Env) {
+        let new_admin = access_control::accept_transfer(e, Role::Admin);
+        LendingYieldControllerEvents::set_admin(e, new_admin);
+    }
+
+    fn cancel_admin_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Admin);
+    }
+
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Admin)
+    }
+
+    fn grant_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        if role == Role::Owner {
+            panic_with_error!(e, AccessControlError::InvalidRole);
+        }
+        access_control::grant_role(e, role, &account);
+    }
+
+    fn revoke_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        access_control::revoke_role(e, role, &account);
+    }
+
+    fn renounce_role(e: &Env, account: Address, role: Role) {
+        access_control::renounce_role(e, role, &account);
+    }
+
+    fn has_role(e: &Env, role: Role, account: Address) -> bool {
+        access_control::has_role(e, role, &account)
+    }
+
+    fn pause(e: &Env, caller: Address, flag: PauseFlag) {
+        access_control::require_role(e, Role::Pauser, &caller);
+        storage::write_paused(e, flag, true);
+        LendingYieldControllerEvents::pause(e, flag);
+    }
+
+    fn unpause(e: &Env, flag: PauseFlag) {
+        require_owner(e);
+        storage::write_paused(e, flag, false);
+        LendingYieldControllerEvents::unpause(e, flag);
+    }
+
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool { storage::is_paused(e, flag) }
+
+    fn set_asset_decimals(e: &Env, asset: Address, decimals: u32) {
+        require_admin(e);
+        controls::process_set_asset_decimals(e, asset.clone(), decimals);
+        LendingYieldControllerEvents::set_asset_decimals(e, asset, decimals);
+    }
+
+    fn get_asset_decimals(e: &Env, asset: Address) -> u32 {
+        pricing::asset_decimals(e, &asset)
+    }
+
+    fn set_asset_oracle(e: &Env, asset: Address, oracle: Address, collateral_factor_bps: u32, max_price_age: u64) {
+        require_admin(e);
+        let config = controls::process_set_asset_oracle(e, asset.clone(), oracle, collateral_factor_bps, max_price_age);
+        LendingYieldControllerEvents::set_asset_oracle(e, asset, config);
+    }
+
+    fn remove_asset_oracle(e: &Env, asset: Address) {
+        require_admin(e);
+        storage::remove_asset_oracle(e, &asset);
+        LendingYieldControllerEvents::remove_asset_oracle(e, asset);
+    }
+
+    fn get_asset_oracle(e: &Env, asset: Address) -> Option<AssetOracleConfig> {
+        storage::read_asset_oracle(e, &asset)
+    }
+
     fn get_apy(e: &Env, protocol: Symbol, asset: Address) -> u32 {
         controls::read_apy(e, &protocol, asset)
     }
@@ -359,35 +1196,98 @@ impl LendingYieldControllerTrait for LendingYieldController {
         e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
+    fn migrate(e: &Env) -> u32 {
+        // Migrate before authorizing: an old layout may keep the owner where `require_owner` can't
+        // read it, and a failed authorization reverts the migration
+        let version = migrations::migrate(e);
+        require_owner(e);
+        version
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
+
+    fn claim_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> ClaimYieldResult {
+        let is_keeper = require_admin_or_keeper(e, &caller);
+        let may_advance_epoch = is_admin_or_keeper_role(e, &caller);
+        let keeper = if is_keeper { Some(caller) } else { None };
+        controls::require_not_paused(e, PauseFlag::Harvest);
+
+        let result = controls::process_claim_yield(e, &protocol, asset.clone(), keeper, may_advance_epoch);
+
+        if result.stage == HarvestState::Distributed {
+            LendingYieldControllerEvents::claim_yield(
+                e,
+                e.current_contract_address(),
+                asset,
+                result.amount,
+            );
+        }
+
+        result
+    }
+
+    fn harvest_all(e: &Env, caller: Address, limit: u32) -> HarvestAllResult {
+        let is_keeper = require_admin_or_keeper(e, &caller);
+        let keeper = if is_keeper { Some(caller.clone()) } else { None };
+        controls::require_not_paused(e, PauseFlag::Harvest);
+
+        let result = controls::process_harvest_all(e, limit, keeper);
+
+        LendingYieldControllerEvents::harvest_all(
+            e,
+            caller,
+            result.processed,
+            result.harvested,
+            result.distributed,
+        );
+
+        result
+    }
+
+    fn get_harvest_all_cursor(e: &Env) -> Option<HarvestAllCursor> {
+        storage::read_harvest_all_cursor(e)
+    }
+
     // =========================================================================
-    // Multi-stage yield claiming (Admin only)
+    // Multi-stage yield claiming (Admin and keeper role, or anyone once distribution is available)
     // =========================================================================
 
-    fn harvest_yield(e: &Env, protocol: Symbol, asset: Address) -> i128 {
+    fn set_keeper_bounty(e: &Env, bounty_bps: u32) {
         require_admin(e);
+        controls::process_set_keeper_bounty(e, bounty_bps);
+        LendingYieldControllerEvents::set_keeper_bounty(e, bounty_bps);
+    }
 
-        let harvested = controls::process_harvest_yield(e, &protocol, asset.clone());
-
-        harvested
+    fn get_keeper_bounty(e: &Env) -> u32 {
+        storage::read_keeper_bounty_bps(e)
     }
 
-    fn recompound_yield(e: &Env, protocol: Symbol, asset: Address) -> i128 {
-        require_admin(e);
+    fn harvest_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128 {
+        require_admin_or_keeper(e, &caller);
+        controls::require_not_paused(e, PauseFlag::Harvest);
 
-        let recompounded = controls::process_recompound_yield(e, &protocol, asset.clone());
+        controls::process_harvest_yield(e, &protocol, asset, is_admin_or_keeper_role(e, &caller))
+    }
+
+    fn recompound_yield(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128 {
+        require_admin_or_keeper(e, &caller);
+        controls::require_not_paused(e, PauseFlag::Harvest);
 
-        recompounded
+        controls::process_recompound_yield(e, &protocol, asset)
     }
 
-    fn finalize_distribution(e: &Env, protocol: Symbol, asset: Address) -> i128 {
-        require_admin(e);
+    fn finalize_distribution(e: &Env, caller: Address, protocol: Symbol, asset: Address) -> i128 {
+        let is_keeper = require_admin_or_keeper(e, &caller);
+        let keeper = if is_keeper { Some(caller) } else { None };
 
         let distributor = storage::distributor_client(e);
         if !distributor.is_distribution_available() {
             panic_with_error!(e, LendingYieldControllerError::YieldUnavailable);
         }
 
-        let distributed = controls::process_finalize_distribution(e, &protocol, asset.clone());
+        let distributed = controls::process_finalize_distribution(e, &protocol, asset.clone(), keeper);
 
         LendingYieldControllerEvents::claim_yield(
             e,
@@ -408,4 +1308,27 @@ impl LendingYieldControllerTrait for LendingYieldController {
 
         controls::process_cancel_harvest(e, &protocol, asset);
     }
+
+    fn get_idle_balance(e: &Env, asset: Address) -> i128 {
+        storage::read_idle_balance(e, &asset)
+    }
+
+    fn redeposit_idle(e: &Env, protocol: Symbol, asset: Address, amount: i128) -> i128 {
+        require_admin(e);
+        controls::require_not_paused(e, PauseFlag::Deposit);
+
+        let deposited = controls::process_redeposit_idle(e, &protocol, asset.clone(), amount);
+
+        LendingYieldControllerEvents::redeposit_idle(e, protocol, asset, deposited);
+
+        deposited
+    }
+
+    fn sweep_idle(e: &Env, asset: Address, to: Address, amount: i128) {
+        require_admin(e);
+
+        controls::process_sweep_idle(e, asset.clone(), to.clone(), amount);
+
+        LendingYieldControllerEvents::sweep_idle(e, asset, to, amount);
+    }
 }
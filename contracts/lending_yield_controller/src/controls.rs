@@ -1,14 +1,26 @@
-use soroban_sdk::{panic_with_error, vec, Address, Env, IntoVal, Symbol};
+use soroban_sdk::{panic_with_error, token::TokenClient, vec, Address, Env, IntoVal, Map, Symbol, Vec};
 use yield_adapter::lending_adapter::LendingAdapterClient;
 use crate::error::LendingYieldControllerError;
 use crate::events::LendingYieldControllerEvents;
-use crate::storage_types::{HarvestState, PendingHarvest};
+use crate::storage_types::{
+    AllocationConfig, AllocationMode, AssetOracleConfig, ClaimYieldResult, HarvestAllCursor, HarvestAllResult, HarvestState, PauseFlag, PendingHarvest,
+    RedemptionTicket,
+};
 use crate::utils;
-use crate::{storage, storage_types};
+use crate::{allocation, caps, pricing, storage, storage_types};
 
-pub fn process_deposit(e: &Env, protocol: &Symbol, user: Address, asset: Address, amount: i128) -> i128 { 
+pub fn process_deposit(
+    e: &Env,
+    protocol: &Symbol,
+    user: Address,
+    asset: Address,
+    amount: i128,
+    min_out: Option<i128>,
+) -> (i128, i128) {
     let registry_client = storage::adapter_registry_client(&e);
     let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), &protocol));
+    require_not_exit_only(e, protocol, &asset);
+    caps::check_deposit(e, protocol, &asset, amount);
     if let Some((id, fn_name, args)) = adapter.deposit_auth(&user, &asset, &amount) {
         utils::authenticate_contract(
             &e, 
@@ -35,12 +47,108 @@ pub fn process_deposit(e: &Env, protocol: &Symbol, user: Address, asset: Address
         &amount
     );
 
-    process_cusd_issue(e, user.clone(), amount);
+    // Issue against what the adapter actually deposited, not what was requested
+    let issued = pricing::cusd_to_issue(e, &asset, deposited);
+    if let Some(min_out) = min_out {
+        if issued < min_out {
+            panic_with_error!(e, LendingYieldControllerError::SlippageExceeded);
+        }
+    }
+    caps::check_issue(e, issued);
 
-    deposited
+    let debt = read_position_debt(e, &user, protocol, &asset);
+    storage::increase_user_position(e, &user, protocol, &asset, deposited);
+    storage::write_user_debt(e, &user, protocol, &asset, debt + issued);
+
+    process_cusd_issue(e, user.clone(), issued);
+
+    (deposited, issued)
+}
+
+/// Deposit collateral split across the protocols supporting the asset
+pub fn process_deposit_auto(
+    e: &Env,
+    user: Address,
+    asset: Address,
+    amount: i128,
+    min_out: Option<i128>,
+) -> (i128, i128) {
+    let mut deposited = 0;
+    let mut issued = 0;
+    for (protocol, part) in allocation::deposit_split(e, &asset, amount).iter() {
+        let (part_deposited, part_issued) = process_deposit(e, &protocol, user.clone(), asset.clone(), part, None);
+        deposited += part_deposited;
+        issued += part_issued;
+    }
+
+    if let Some(min_out) = min_out {
+        if issued < min_out {
+            panic_with_error!(e, LendingYieldControllerError::SlippageExceeded);
+        }
+    }
+
+    (deposited, issued)
+}
+
+/// Withdraw collateral split across the user's positions in the asset
+pub fn process_withdraw_auto(
+    e: &Env,
+    user: Address,
+    asset: Address,
+    amount: i128,
+    max_burn: Option<i128>,
+) -> (i128, i128) {
+    let mut withdrawn = 0;
+    let mut burned = 0;
+    for (protocol, part) in allocation::withdraw_split(e, &user, &asset, amount).iter() {
+        let (part_withdrawn, part_burned) =
+            process_withdraw_collateral(e, &protocol, user.clone(), asset.clone(), part, None, false);
+        withdrawn += part_withdrawn;
+        burned += part_burned;
+    }
+
+    if let Some(max_burn) = max_burn {
+        if burned > max_burn {
+            panic_with_error!(e, LendingYieldControllerError::SlippageExceeded);
+        }
+    }
+
+    (withdrawn, burned)
+}
+
+pub fn process_set_allocation(
+    e: &Env,
+    asset: Address,
+    mode: AllocationMode,
+    weights: Map<Symbol, u32>,
+) -> AllocationConfig {
+    if mode == AllocationMode::Weighted {
+        let registry_client = storage::adapter_registry_client(e);
+        let mut total_weight: u64 = 0;
+        for (protocol, weight) in weights.iter() {
+            if !registry_client.is_supported_asset(&storage_types::YIELD_TYPE.id(), &protocol, &asset) {
+                panic_with_error!(e, LendingYieldControllerError::InvalidAllocation);
+            }
+            total_weight += weight as u64;
+        }
+        if total_weight == 0 {
+            panic_with_error!(e, LendingYieldControllerError::InvalidAllocation);
+        }
+    }
+
+    let config = AllocationConfig { mode, weights };
+    storage::write_allocation(e, &asset, &config);
+    config
+}
+
+pub fn require_not_paused(e: &Env, flag: PauseFlag) {
+    if storage::is_paused(e, flag) {
+        panic_with_error!(e, LendingYieldControllerError::Paused);
+    }
 }
 
 fn process_cusd_issue(e: &Env, user: Address, amount: i128) {
+    require_not_paused(e, PauseFlag::Mint);
     let cusd_manager_client = storage::cusd_manager_client(&e);
     utils::authenticate_contract(
         &e, 
@@ -60,46 +168,523 @@ fn process_cusd_burn(e: &Env, user: Address, amount: i128) {
     cusd_manager_client.burn_cusd(&user, &amount);
 }
 
-pub fn process_withdraw_collateral(e: &Env, protocol: &Symbol, user: Address, asset: Address, amount: i128) -> i128 {
-    
+pub fn process_withdraw_collateral(
+    e: &Env,
+    protocol: &Symbol,
+    user: Address,
+    asset: Address,
+    amount: i128,
+    max_burn: Option<i128>,
+    allow_partial: bool,
+) -> (i128, i128) {
     let registry_client = storage::adapter_registry_client(&e);
-    
-    let adapter =
-        LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), &protocol));
+
+    // Panics if the protocol has no registered adapter
+    registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), protocol);
     
     let is_asset_supported =
         registry_client.is_supported_asset(&storage_types::YIELD_TYPE.id(), &protocol, &asset);
     if !is_asset_supported {
         panic_with_error!(e, LendingYieldControllerError::UnsupportedAsset);
     };
-    
-    if let Some((id, fn_name, args)) = adapter.withdraw_auth(&user, &asset, &amount) {
-        utils::authenticate_contract(
-            &e, 
-            id, 
-            fn_name,  
-            args,
-        );
+
+    // Collateral deposited before positions were recorded belongs to no position; like before
+    // the upgrade, any cUSD holder can withdraw it by burning cUSD for it
+    let position = storage::read_user_position(e, &user, protocol, &asset);
+    let legacy = storage::read_legacy_collateral(e, protocol, &asset);
+    if position + legacy < amount {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientPosition);
+    }
+    let debt = read_position_debt(e, &user, protocol, &asset);
+
+    let withdrawn = process_protocol_withdraw(e, protocol, &user, &asset, amount, allow_partial);
+    let from_position = withdrawn.min(position);
+    let from_legacy = withdrawn - from_position;
+
+    // Burn the share of the cUSD issued for the position that the withdrawn collateral backed,
+    // so a price move between deposit and withdrawal can't leave cUSD unbacked
+    let mut burned = 0;
+    if from_position > 0 {
+        burned = pricing::debt_share(e, debt, position, from_position);
+        release_position(e, &user, protocol, &asset, from_position, debt - burned);
+    }
+    if from_legacy > 0 {
+        storage::write_legacy_collateral(e, protocol, &asset, legacy - from_legacy);
+        burned += pricing::cusd_to_burn(e, &asset, from_legacy);
+    }
+    if let Some(max_burn) = max_burn {
+        if burned > max_burn {
+            panic_with_error!(e, LendingYieldControllerError::SlippageExceeded);
+        }
+    }
+
+    process_cusd_burn(e, user.clone(), burned);
+
+    (withdrawn, burned)
+}
+
+/// cUSD issued against a user's position. Positions opened before the issued cUSD was
+/// recorded are valued once at the current price.
+pub fn read_position_debt(e: &Env, user: &Address, protocol: &Symbol, asset: &Address) -> i128 {
+    if let Some(debt) = storage::read_user_debt(e, user, protocol, asset) {
+        return debt;
+    }
+    match storage::read_user_position(e, user, protocol, asset) {
+        0 => 0,
+        position => pricing::cusd_to_burn(e, asset, position),
+    }
+}
+
+/// Take `amount` of collateral out of a user's position, leaving `debt` cUSD issued against the rest
+fn release_position(e: &Env, user: &Address, protocol: &Symbol, asset: &Address, amount: i128, debt: i128) {
+    storage::decrease_user_position(e, user, protocol, asset, amount);
+    if storage::read_user_position(e, user, protocol, asset) > 0 {
+        storage::write_user_debt(e, user, protocol, asset, debt);
+    } else {
+        storage::remove_user_debt(e, user, protocol, asset);
+    }
+}
+
+fn authenticate_adapter_withdraw(e: &Env, adapter: &LendingAdapterClient, user: &Address, asset: &Address, amount: i128) {
+    if let Some((id, fn_name, args)) = adapter.withdraw_auth(user, asset, &amount) {
+        utils::authenticate_contract(e, id, fn_name, args);
     }
     utils::authenticate_contract(
-        &e, 
-        adapter.address.clone(), 
-        Symbol::new(&e, "withdraw"), 
+        e,
+        adapter.address.clone(),
+        Symbol::new(e, "withdraw"),
         vec![
             e,
-            (&user).into_val(e),
-            (&asset).into_val(e),
-            (&amount).into_val(e),
+            user.into_val(e),
+            asset.into_val(e),
+            amount.into_val(e),
         ]
     );
-    
-    let withdrawn = adapter.withdraw(&user, &asset, &amount);
-    
-    process_cusd_burn(e, user.clone(), amount);
+}
 
+/// Withdraw `amount` for a user from the adapter
+fn process_adapter_withdraw(e: &Env, adapter: &LendingAdapterClient, user: &Address, asset: &Address, amount: i128) -> i128 {
+    authenticate_adapter_withdraw(e, adapter, user, asset, amount);
+    adapter.withdraw(user, asset, &amount)
+}
+
+/// Withdraw `amount` for a user from the adapter, or `None` if the protocol cannot pay it out
+fn process_adapter_try_withdraw(e: &Env, adapter: &LendingAdapterClient, user: &Address, asset: &Address, amount: i128) -> Option<i128> {
+    authenticate_adapter_withdraw(e, adapter, user, asset, amount);
+    match adapter.try_withdraw(user, asset, &amount) {
+        Ok(Ok(withdrawn)) => Some(withdrawn),
+        _ => None,
+    }
+}
+
+/// Principal `holder` can pay out towards a withdrawal of `protocol`'s user principal.
+/// An exit-only holder pays from its recovered balance, which is shared with the protocols whose
+/// principal was rebalanced into it; a live holder pays out of its adapter up to the principal it holds.
+fn available_principal(e: &Env, protocol: &Symbol, holder: &Symbol, asset: &Address) -> i128 {
+    let surplus = storage::read_rebalanced_principal(e, holder, asset).max(0);
+    if storage::is_exit_only(e, holder, asset) {
+        let recovered = storage::read_recovered_balance(e, holder, asset);
+        return if holder == protocol { (recovered - surplus).max(0) } else { surplus.min(recovered) };
+    }
+    if holder == protocol {
+        return i128::MAX;
+    }
+    let adapter = LendingAdapterClient::new(
+        e,
+        &storage::adapter_registry_client(e).get_adapter(&storage_types::YIELD_TYPE.id(), holder),
+    );
+    let principal = adapter.get_balance(&e.current_contract_address(), asset) - adapter.get_yield(asset).max(0);
+    surplus.min(principal).max(0)
+}
+
+/// Plan a withdrawal of a protocol's user principal.
+/// Principal rebalanced out of the protocol is taken from the protocols now holding it, exit-only ones
+/// first, each up to what it actually holds. The rest comes from the protocol itself.
+fn plan_protocol_withdraw(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) -> Vec<(Symbol, i128)> {
+    let mut parts = Vec::new(e);
+    let mut remaining = amount;
+    let mut owed = amount.min(-storage::read_rebalanced_principal(e, protocol, asset)).max(0);
+
+    let others = allocation::supporting_protocols(e, asset);
+    for exit_only in [true, false] {
+        for other in others.iter() {
+            if owed == 0 {
+                break;
+            }
+            if other == *protocol || storage::is_exit_only(e, &other, asset) != exit_only {
+                continue;
+            }
+            let take = owed.min(available_principal(e, protocol, &other, asset));
+            if take > 0 {
+                parts.push_back((other, take));
+                owed -= take;
+                remaining -= take;
+            }
+        }
+    }
+
+    if remaining > 0 {
+        parts.push_back((protocol.clone(), remaining));
+    }
+    parts
+}
+
+/// Record that `amount` of a protocol's rebalanced principal was withdrawn from `other`
+fn settle_rebalanced_withdraw(e: &Env, protocol: &Symbol, other: &Symbol, asset: &Address, amount: i128) {
+    if other != protocol {
+        storage::adjust_rebalanced_principal(e, other, asset, -amount);
+        storage::adjust_rebalanced_principal(e, protocol, asset, amount);
+    }
+}
+
+/// Withdraw `amount` of a protocol's user principal for a user.
+/// With `allow_partial`, each adapter only pays out up to its available liquidity and each
+/// exit-only protocol up to its recovered balance.
+fn process_protocol_withdraw(e: &Env, protocol: &Symbol, user: &Address, asset: &Address, amount: i128, allow_partial: bool) -> i128 {
+    let registry_client = storage::adapter_registry_client(e);
+    let mut withdrawn = 0;
+    for (other, mut part) in plan_protocol_withdraw(e, protocol, asset, amount).iter() {
+        if storage::is_exit_only(e, &other, asset) {
+            part = process_recovered_withdraw(e, protocol, &other, user, asset, part, allow_partial);
+            withdrawn += part;
+        } else {
+            let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), &other));
+            if allow_partial {
+                part = part.min(adapter.max_withdrawable(asset));
+            }
+            if part > 0 {
+                withdrawn += process_adapter_withdraw(e, &adapter, user, asset, part);
+            }
+        }
+        if part > 0 {
+            settle_rebalanced_withdraw(e, protocol, &other, asset, part);
+        }
+    }
     withdrawn
 }
 
+/// Pay out `amount` of a protocol's user principal from the balance recovered from `holder`'s exit-only adapter.
+/// With `allow_partial`, only up to the share of the recovered balance available to `protocol` is paid out.
+fn process_recovered_withdraw(
+    e: &Env,
+    protocol: &Symbol,
+    holder: &Symbol,
+    user: &Address,
+    asset: &Address,
+    mut amount: i128,
+    allow_partial: bool,
+) -> i128 {
+    let available = available_principal(e, protocol, holder, asset);
+    if allow_partial {
+        amount = amount.min(available);
+    } else if available < amount {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientRecoveredBalance);
+    }
+    if amount <= 0 {
+        return 0;
+    }
+
+    storage::decrease_recovered_balance(e, holder, asset, amount);
+    TokenClient::new(e, asset).transfer(&e.current_contract_address(), user, &amount);
+    amount
+}
+
+fn require_not_exit_only(e: &Env, protocol: &Symbol, asset: &Address) {
+    if storage::is_exit_only(e, protocol, asset) {
+        panic_with_error!(e, LendingYieldControllerError::ExitOnly);
+    }
+}
+
+/// Pull the controller's whole balance out of an adapter and mark it exit-only for the asset.
+/// Recovered principal is held for users to withdraw against; accrued yield goes to the idle balance.
+/// Returns the recovered principal and yield.
+pub fn process_emergency_withdraw_all(e: &Env, protocol: &Symbol, asset: Address) -> (i128, i128) {
+    let registry_client = storage::adapter_registry_client(e);
+    let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), protocol));
+    if storage::has_pending_harvest(e, protocol, &asset) {
+        panic_with_error!(e, LendingYieldControllerError::HarvestAlreadyInProgress);
+    }
+
+    // Mark the pair first so nothing is deposited back into the adapter
+    storage::set_exit_only(e, protocol, &asset);
+
+    let controller = e.current_contract_address();
+    let balance = adapter.get_balance(&controller, &asset);
+    let principal = balance - adapter.get_yield(&asset).max(0);
+
+    // A pool short on liquidity pays out what it can; the rest can be recovered by calling again
+    let amount = balance.min(adapter.max_withdrawable(&asset));
+    if amount <= 0 {
+        return (0, 0);
+    }
+
+    let withdrawn = process_adapter_withdraw(e, &adapter, &controller, &asset, amount);
+    let recovered_principal = withdrawn.min(principal).max(0);
+    let recovered_yield = withdrawn - recovered_principal;
+
+    storage::increase_recovered_balance(e, protocol, &asset, recovered_principal);
+    if recovered_yield > 0 {
+        storage::increase_idle_balance(e, &asset, recovered_yield);
+    }
+
+    // Whatever is left in the adapter is principal; keep it from being reported as yield
+    let epoch = storage::distributor_client(e).get_current_epoch();
+    process_set_epoch_principal(e, &adapter, &asset, epoch, adapter.get_balance(&controller, &asset));
+
+    (recovered_principal, recovered_yield)
+}
+
+/// Escrow cUSD from a user and queue a withdrawal of their position
+pub fn process_request_redemption(
+    e: &Env,
+    protocol: Symbol,
+    user: Address,
+    asset: Address,
+    amount: i128,
+) -> RedemptionTicket {
+    if amount <= 0 {
+        panic_with_error!(e, LendingYieldControllerError::NegativeAmountError);
+    }
+    let registry_client = storage::adapter_registry_client(e);
+    if !registry_client.is_supported_asset(&storage_types::YIELD_TYPE.id(), &protocol, &asset) {
+        panic_with_error!(e, LendingYieldControllerError::UnsupportedAsset);
+    }
+    // Exit-only positions are paid out from the recovered balance by `withdraw_collateral`
+    require_not_exit_only(e, &protocol, &asset);
+    let position = storage::read_user_position(e, &user, &protocol, &asset);
+    if position < amount {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientPosition);
+    }
+
+    // The queued amount and its share of the issued cUSD leave the position so they cannot also
+    // be withdrawn directly
+    let debt = read_position_debt(e, &user, &protocol, &asset);
+    let escrow = pricing::debt_share(e, debt, position, amount);
+    release_position(e, &user, &protocol, &asset, amount, debt - escrow);
+
+    cusd_token_client(e).transfer(&user, &e.current_contract_address(), &escrow);
+
+    let id = storage::next_redemption_id(e);
+    let ticket = RedemptionTicket { id, user, protocol, asset, amount, escrow };
+    storage::write_redemption_ticket(e, &ticket);
+    enqueue_redemption(e, &ticket.protocol, &ticket.asset, id);
+
+    ticket
+}
+
+/// Append a ticket to the back of its protocol/asset pair's queue
+fn enqueue_redemption(e: &Env, protocol: &Symbol, asset: &Address, id: u64) {
+    let mut queue = storage::read_redemption_queue(e, protocol, asset);
+    storage::write_redemption_slot(e, protocol, asset, queue.tail, id);
+    queue.tail += 1;
+    storage::write_redemption_queue(e, protocol, asset, &queue);
+}
+
+/// Cancel a queued redemption, restoring the user's position and returning their escrowed cUSD
+pub fn process_cancel_redemption(e: &Env, ticket: &RedemptionTicket) {
+    let debt = read_position_debt(e, &ticket.user, &ticket.protocol, &ticket.asset);
+    storage::increase_user_position(e, &ticket.user, &ticket.protocol, &ticket.asset, ticket.amount);
+    storage::write_user_debt(e, &ticket.user, &ticket.protocol, &ticket.asset, debt + ticket.escrow);
+    cusd_token_client(e).transfer(&e.current_contract_address(), &ticket.user, &ticket.escrow);
+    storage::remove_redemption_ticket(e, ticket.id);
+}
+
+/// Fill a protocol/asset pair's queued redemptions first-in-first-out, examining at most `limit` tickets.
+/// Stops at the first ticket the protocol cannot pay out in full; it stays at the head of its queue.
+/// Each pair has its own queue, so an illiquid pool only holds up redemptions from that pool.
+pub fn process_queue(e: &Env, protocol: &Symbol, asset: &Address, limit: u32) -> u32 {
+    let mut queue = storage::read_redemption_queue(e, protocol, asset);
+    let mut filled = 0;
+    let mut examined = 0;
+    while queue.head < queue.tail && examined < limit {
+        examined += 1;
+        let ticket = storage::read_redemption_slot(e, protocol, asset, queue.head)
+            .and_then(|id| storage::read_redemption_ticket(e, id));
+        if let Some(mut ticket) = ticket {
+            if !process_fill_redemption(e, &mut ticket) {
+                break;
+            }
+            filled += 1;
+        }
+        // Filled or cancelled
+        storage::remove_redemption_slot(e, protocol, asset, queue.head);
+        queue.head += 1;
+    }
+    storage::write_redemption_queue(e, protocol, asset, &queue);
+    filled
+}
+
+/// Pay out as much of a ticket as the protocols allow, burning escrowed cUSD for what was withdrawn.
+/// Returns whether the ticket was filled in full.
+fn process_fill_redemption(e: &Env, ticket: &mut RedemptionTicket) -> bool {
+    let registry_client = storage::adapter_registry_client(e);
+    let amount = ticket.amount;
+    let mut withdrawn = 0;
+    for (other, part) in plan_protocol_withdraw(e, &ticket.protocol, &ticket.asset, ticket.amount).iter() {
+        // Exit-only protocols pay what they recovered; live ones pay the whole part or nothing
+        let (paid, part_withdrawn) = if storage::is_exit_only(e, &other, &ticket.asset) {
+            let paid = process_recovered_withdraw(e, &ticket.protocol, &other, &ticket.user, &ticket.asset, part, true);
+            (paid, paid)
+        } else {
+            let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), &other));
+            match process_adapter_try_withdraw(e, &adapter, &ticket.user, &ticket.asset, part) {
+                Some(part_withdrawn) => (part, part_withdrawn),
+                None => (0, 0),
+            }
+        };
+        if paid > 0 {
+            settle_rebalanced_withdraw(e, &ticket.protocol, &other, &ticket.asset, paid);
+            withdrawn += part_withdrawn;
+            ticket.amount -= paid;
+        }
+        if paid < part {
+            break;
+        }
+    }
+
+    if ticket.amount < amount {
+        // The escrow is the cUSD issued for the queued collateral; burn it as the collateral leaves
+        let burned = pricing::debt_share(e, ticket.escrow, amount, amount - ticket.amount);
+        process_cusd_burn_escrow(e, burned);
+        ticket.escrow -= burned;
+        LendingYieldControllerEvents::fill_redemption(e, ticket.id, ticket.user.clone(), withdrawn, burned);
+    }
+
+    if ticket.amount > 0 {
+        storage::write_redemption_ticket(e, ticket);
+        return false;
+    }
+
+    storage::remove_redemption_ticket(e, ticket.id);
+    true
+}
+
+fn cusd_token_client(e: &Env) -> TokenClient {
+    TokenClient::new(e, &storage::cusd_manager_client(e).get_cusd_id())
+}
+
+/// Burn cUSD held in escrow by the controller
+fn process_cusd_burn_escrow(e: &Env, amount: i128) {
+    let cusd_manager_client = storage::cusd_manager_client(e);
+    let controller = e.current_contract_address();
+    utils::authenticate_contract(
+        e,
+        cusd_manager_client.get_cusd_id(),
+        Symbol::new(e, "burn"),
+        vec![
+            e,
+            controller.into_val(e),
+            amount.into_val(e),
+        ],
+    );
+    cusd_manager_client.burn_cusd(&controller, &amount);
+}
+
+/// Move principal from one protocol to another without touching cUSD supply.
+/// Each adapter's epoch principal is reset around its accrued yield so neither records the move as yield.
+pub fn process_rebalance(e: &Env, from_protocol: &Symbol, to_protocol: &Symbol, asset: Address, amount: i128) -> i128 {
+    if from_protocol == to_protocol || amount <= 0 {
+        panic_with_error!(e, LendingYieldControllerError::InvalidRebalance);
+    }
+
+    let registry_client = storage::adapter_registry_client(e);
+    for protocol in [from_protocol, to_protocol] {
+        if !registry_client.is_supported_asset(&storage_types::YIELD_TYPE.id(), protocol, &asset) {
+            panic_with_error!(e, LendingYieldControllerError::UnsupportedAsset);
+        }
+        if storage::has_pending_harvest(e, protocol, &asset) {
+            panic_with_error!(e, LendingYieldControllerError::HarvestAlreadyInProgress);
+        }
+        require_not_exit_only(e, protocol, &asset);
+    }
+
+    let from_adapter =
+        LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), from_protocol));
+    let to_adapter =
+        LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), to_protocol));
+
+    let controller = e.current_contract_address();
+    let from_yield = from_adapter.get_yield(&asset);
+    let to_yield = to_adapter.get_yield(&asset);
+
+    // Only principal may be moved; accrued yield stays behind for the next harvest
+    if amount > from_adapter.get_balance(&controller, &asset) - from_yield {
+        panic_with_error!(e, LendingYieldControllerError::InvalidRebalance);
+    }
+
+    caps::check_protocol_deposit(e, to_protocol, &asset, amount);
+
+    let withdrawn = process_adapter_withdraw(e, &from_adapter, &controller, &asset, amount);
+    let deposited = process_deposit_for_claim(e, to_adapter.address.clone(), asset.clone(), withdrawn);
+
+    storage::adjust_rebalanced_principal(e, from_protocol, &asset, -withdrawn);
+    storage::adjust_rebalanced_principal(e, to_protocol, &asset, deposited);
+
+    let epoch = storage::distributor_client(e).get_current_epoch();
+    let from_principal = from_adapter.get_balance(&controller, &asset) - from_yield;
+    process_set_epoch_principal(e, &from_adapter, &asset, epoch, from_principal);
+    let to_principal = to_adapter.get_balance(&controller, &asset) - to_yield;
+    process_set_epoch_principal(e, &to_adapter, &asset, epoch, to_principal);
+
+    deposited
+}
+
+fn require_valid_cap(e: &Env, cap: Option<i128>) {
+    if let Some(cap) = cap {
+        if cap < 0 {
+            panic_with_error!(e, LendingYieldControllerError::InvalidCap);
+        }
+    }
+}
+
+pub fn process_set_asset_cap(e: &Env, asset: Address, cap: Option<i128>) {
+    require_valid_cap(e, cap);
+    storage::write_asset_cap(e, &asset, cap);
+}
+
+pub fn process_set_protocol_cap(e: &Env, protocol: Symbol, asset: Address, cap: Option<i128>) {
+    require_valid_cap(e, cap);
+    storage::write_protocol_cap(e, &protocol, &asset, cap);
+}
+
+pub fn process_set_cusd_supply_cap(e: &Env, cap: Option<i128>) {
+    require_valid_cap(e, cap);
+    storage::write_cusd_supply_cap(e, cap);
+}
+
+pub fn process_set_asset_decimals(e: &Env, asset: Address, decimals: u32) {
+    if decimals > storage_types::MAX_ASSET_DECIMALS {
+        panic_with_error!(e, LendingYieldControllerError::InvalidDecimals);
+    }
+    storage::write_asset_decimals(e, &asset, decimals);
+}
+
+pub fn process_set_asset_oracle(
+    e: &Env,
+    asset: Address,
+    oracle: Address,
+    collateral_factor_bps: u32,
+    max_price_age: u64,
+) -> AssetOracleConfig {
+    if collateral_factor_bps == 0 || collateral_factor_bps > storage_types::MAX_BPS {
+        panic_with_error!(e, LendingYieldControllerError::InvalidCollateralFactor);
+    }
+    let config = AssetOracleConfig {
+        oracle,
+        collateral_factor_bps,
+        max_price_age,
+    };
+    storage::write_asset_oracle(e, &asset, &config);
+    config
+}
+
+pub fn process_set_keeper_bounty(e: &Env, bounty_bps: u32) {
+    if bounty_bps > storage_types::MAX_BPS {
+        panic_with_error!(e, LendingYieldControllerError::InvalidKeeperBounty);
+    }
+    storage::write_keeper_bounty_bps(e, bounty_bps);
+}
+
 pub fn process_distribute_cusd_yield(e: &Env, asset: Address, amount: i128) {
     let distributor = storage::distributor_client(e);
     utils::authenticate_contract(
@@ -243,11 +828,15 @@ fn process_deposit_for_claim(e: &Env, protocol_id: Address, asset: Address, yiel
 
 /// Stage 1: Harvest yield from the lending protocol
 /// Withdraws accumulated yield and stores it for later processing.
-pub fn process_harvest_yield(e: &Env, protocol: &Symbol, asset: Address) -> i128 {
+/// A pair without yield advances the epoch only when `may_advance_epoch` is set, i.e. for the admin or a
+/// keeper role holder; otherwise anyone could close every epoch with an empty distribution.
+pub fn process_harvest_yield(e: &Env, protocol: &Symbol, asset: Address, may_advance_epoch: bool) -> i128 {
     // Check no pending harvest exists
     if storage::has_pending_harvest(e, protocol, &asset) {
         panic_with_error!(e, LendingYieldControllerError::HarvestAlreadyInProgress);
     }
+    // Yield left in an exit-only adapter is recovered by emergency withdrawal, not recompounded
+    require_not_exit_only(e, protocol, &asset);
 
     let registry_client = storage::adapter_registry_client(e);
     let adapter_address = registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), protocol);
@@ -257,31 +846,12 @@ pub fn process_harvest_yield(e: &Env, protocol: &Symbol, asset: Address) -> i128
     if yield_amount <= 0 {
         // No yield available — advance epoch if distribution is due, so the system doesn't stall
         let distributor = storage::distributor_client(e);
-        if distributor.is_distribution_available() {
+        if may_advance_epoch && distributor.is_distribution_available() {
             // Advance epoch with 0-distribution
-            utils::authenticate_contract(
-                e,
-                distributor.address.clone(),
-                Symbol::new(e, "advance_epoch"),
-                vec![e],
-            );
-            distributor.advance_epoch();
+            process_advance_epoch(e);
 
             // Update adapter epoch principal for next epoch (balance unchanged, reset counters)
-            let new_principal = adapter.get_balance(&e.current_contract_address(), &asset);
-            let next_epoch = distributor.get_current_epoch();
-            utils::authenticate_contract(
-                e,
-                adapter.address.clone(),
-                Symbol::new(e, "update_epoch_principal"),
-                vec![
-                    e,
-                    (&asset).into_val(e),
-                    (&next_epoch).into_val(e),
-                    (&new_principal).into_val(e),
-                ],
-            );
-            adapter.update_epoch_principal(&asset, &next_epoch, &new_principal);
+            process_update_epoch_principal(e, &adapter, &asset, distributor.get_current_epoch());
 
             LendingYieldControllerEvents::harvest_yield(e, protocol.clone(), asset, 0);
         }
@@ -339,8 +909,8 @@ pub fn process_recompound_yield(e: &Env, protocol: &Symbol, asset: Address) -> i
 }
 
 /// Stage 3: Finalize distribution of yield to members
-/// Issues cUSD and distributes to treasury and members.
-pub fn process_finalize_distribution(e: &Env, protocol: &Symbol, asset: Address) -> i128 {
+/// Issues cUSD, pays the keeper bounty if driven by a keeper, and distributes the rest to treasury and members.
+pub fn process_finalize_distribution(e: &Env, protocol: &Symbol, asset: Address, keeper: Option<Address>) -> i128 {
     // Get pending harvest
     let pending = storage::get_pending_harvest(e, protocol, &asset)
         .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::NoPendingHarvest));
@@ -351,54 +921,213 @@ pub fn process_finalize_distribution(e: &Env, protocol: &Symbol, asset: Address)
     }
 
     let distributor = storage::distributor_client(e);
-    let cusd_manager = storage::cusd_manager_client(e);
     let registry_client = storage::adapter_registry_client(e);
     let adapter_address = registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), protocol);
     let adapter = LendingAdapterClient::new(e, &adapter_address);
 
-    let yield_amount = pending.amount;
+    // The balance AFTER re-depositing yield (done in stage 2) is the principal for the next epoch
+    let next_epoch = distributor.get_current_epoch() + 1;
+
+    // Yield is harvested in asset units; distribution happens in cUSD units
+    let yield_amount = process_issue_and_distribute(e, pricing::cusd_to_issue(e, &asset, pending.amount), keeper);
+
+    // Update the epoch principal for the next epoch after successful distribution
+    process_update_epoch_principal(e, &adapter, &asset, next_epoch);
+
+    // Remove pending harvest - operation complete
+    storage::remove_pending_harvest(e, protocol, &asset);
+
+    LendingYieldControllerEvents::finalize_distribution(e, protocol.clone(), asset, yield_amount);
+
+    yield_amount
+}
+
+/// Issue harvested yield as cUSD, pay the keeper bounty if driven by a keeper, and
+/// distribute the rest to treasury and members. Returns the amount distributed.
+fn process_issue_and_distribute(e: &Env, amount: i128, keeper: Option<Address>) -> i128 {
+    require_not_paused(e, PauseFlag::Distribution);
+    let mut yield_amount = amount;
+
+    // Pay the keeper bounty before the treasury/member split
+    if let Some(keeper) = keeper {
+        let bounty = yield_amount * storage::read_keeper_bounty_bps(e) as i128 / storage_types::MAX_BPS as i128;
+        if bounty > 0 {
+            process_cusd_issue(e, keeper.clone(), bounty);
+            yield_amount -= bounty;
+            LendingYieldControllerEvents::keeper_bounty(e, keeper, bounty);
+        }
+    }
 
     // Issue cUSD for the yield amount
+    let distributor = storage::distributor_client(e);
     process_cusd_issue(e, distributor.address.clone(), yield_amount);
 
     // Distribute the cUSD yield
-    process_distribute_cusd_yield(e, cusd_manager.get_cusd_id(), yield_amount);
+    process_distribute_cusd_yield(e, storage::cusd_manager_client(e).get_cusd_id(), yield_amount);
 
-    // Get the current balance AFTER re-depositing yield (done in stage 2)
-    // This should be the principal for the next epoch
-    let new_principal = adapter.get_balance(&e.current_contract_address(), &asset);
+    yield_amount
+}
+
+/// Advance the distributor to the next epoch with a 0-distribution
+fn process_advance_epoch(e: &Env) {
+    let distributor = storage::distributor_client(e);
+    utils::authenticate_contract(
+        e,
+        distributor.address.clone(),
+        Symbol::new(e, "advance_epoch"),
+        vec![e],
+    );
+    distributor.advance_epoch();
+}
 
-    // Update epoch principal for the next epoch after successful distribution
-    let current_epoch = distributor.get_current_epoch();
-    let next_epoch = current_epoch + 1;
+/// Reset the adapter's epoch principal for an asset to its current balance
+fn process_update_epoch_principal(e: &Env, adapter: &LendingAdapterClient, asset: &Address, epoch: u64) {
+    let principal = adapter.get_balance(&e.current_contract_address(), asset);
+    process_set_epoch_principal(e, adapter, asset, epoch, principal);
+}
 
-    // Update the epoch principal for the next epoch
+fn process_set_epoch_principal(e: &Env, adapter: &LendingAdapterClient, asset: &Address, epoch: u64, principal: i128) {
     utils::authenticate_contract(
         e,
         adapter.address.clone(),
         Symbol::new(e, "update_epoch_principal"),
         vec![
             e,
-            (&asset).into_val(e),
-            (&next_epoch).into_val(e),
-            (&new_principal).into_val(e),
+            asset.into_val(e),
+            epoch.into_val(e),
+            principal.into_val(e),
         ],
     );
-    adapter.update_epoch_principal(&asset, &next_epoch, &new_principal);
+    adapter.update_epoch_principal(asset, &epoch, &principal);
+}
 
-    // Remove pending harvest - operation complete
-    storage::remove_pending_harvest(e, protocol, &asset);
+/// Run the harvest stages in a single call, resuming from any pending harvest.
+/// Stops after recompounding if distribution is not yet available.
+pub fn process_claim_yield(
+    e: &Env,
+    protocol: &Symbol,
+    asset: Address,
+    keeper: Option<Address>,
+    may_advance_epoch: bool,
+) -> ClaimYieldResult {
+    let (mut state, mut amount) = match storage::get_pending_harvest(e, protocol, &asset) {
+        Some(pending) => (pending.state, pending.amount),
+        None => (HarvestState::None, 0),
+    };
 
-    LendingYieldControllerEvents::finalize_distribution(e, protocol.clone(), asset, yield_amount);
+    if state == HarvestState::None {
+        amount = process_harvest_yield(e, protocol, asset.clone(), may_advance_epoch);
+        if amount <= 0 {
+            return ClaimYieldResult { stage: HarvestState::None, amount: 0 };
+        }
+        state = HarvestState::Harvested;
+    }
 
-    yield_amount
+    if state == HarvestState::Harvested {
+        amount = process_recompound_yield(e, protocol, asset.clone());
+    }
+
+    if !storage::distributor_client(e).is_distribution_available() {
+        return ClaimYieldResult { stage: HarvestState::Recompounded, amount };
+    }
+
+    let distributed = process_finalize_distribution(e, protocol, asset, keeper);
+
+    ClaimYieldResult { stage: HarvestState::Distributed, amount: distributed }
+}
+
+/// Harvest and recompound up to `limit` registered protocol/asset pairs, resuming from the
+/// stored cursor. Once the last pair is reached, the accumulated yield is issued and
+/// distributed as a single cUSD distribution for the epoch.
+/// Pairs with a staged harvest in progress are skipped and left to the staged flow;
+/// exit-only pairs are skipped entirely.
+pub fn process_harvest_all(e: &Env, limit: u32, keeper: Option<Address>) -> HarvestAllResult {
+    let distributor = storage::distributor_client(e);
+    if !distributor.is_distribution_available() {
+        panic_with_error!(e, LendingYieldControllerError::YieldUnavailable);
+    }
+
+    let registry_client = storage::adapter_registry_client(e);
+    let mut cursor = storage::read_harvest_all_cursor(e)
+        .unwrap_or(HarvestAllCursor { visited: Vec::new(e), amount: 0 });
+    let next_epoch = distributor.get_current_epoch() + 1;
+
+    let mut processed: u32 = 0;
+    let mut harvested: i128 = 0;
+    let mut unfinished = false;
+    'walk: for (protocol, assets) in registry_client.get_protocols_with_assets(&storage_types::YIELD_TYPE.id()).iter() {
+        let mut adapter = None;
+        for asset in assets.iter() {
+            let pair = (protocol.clone(), asset.clone());
+            if cursor.visited.contains(&pair) {
+                continue;
+            }
+            if processed >= limit {
+                unfinished = true;
+                break 'walk;
+            }
+            if !storage::has_pending_harvest(e, &protocol, &asset) && !storage::is_exit_only(e, &protocol, &asset) {
+                let adapter = adapter.get_or_insert_with(|| {
+                    LendingAdapterClient::new(e, &registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), &protocol))
+                });
+                harvested += process_harvest_pair(e, &protocol, adapter, &asset, next_epoch);
+            }
+            cursor.visited.push_back(pair);
+            processed += 1;
+        }
+    }
+
+    cursor.amount += harvested;
+
+    if unfinished {
+        storage::write_harvest_all_cursor(e, &cursor);
+        return HarvestAllResult { processed, harvested, complete: false, distributed: 0 };
+    }
+
+    storage::remove_harvest_all_cursor(e);
+
+    let distributed = if cursor.amount > 0 {
+        process_issue_and_distribute(e, cursor.amount, keeper)
+    } else {
+        process_advance_epoch(e);
+        0
+    };
+
+    HarvestAllResult { processed, harvested, complete: true, distributed }
+}
+
+/// Harvest and recompound the yield of a single pair, then reset its epoch principal.
+/// Returns the cUSD value of the recompounded yield.
+fn process_harvest_pair(e: &Env, protocol: &Symbol, adapter: &LendingAdapterClient, asset: &Address, next_epoch: u64) -> i128 {
+    let mut value = 0;
+
+    let yield_amount = adapter.get_yield(asset);
+    if yield_amount > 0 {
+        authenticate_for_claim_yield(e, &adapter.address, asset.clone(), yield_amount);
+        let claimed = adapter.claim_yield(asset, &yield_amount);
+        LendingYieldControllerEvents::harvest_yield(e, protocol.clone(), asset.clone(), claimed);
+
+        let deposited = process_deposit_for_claim(e, adapter.address.clone(), asset.clone(), claimed);
+        LendingYieldControllerEvents::recompound_yield(e, protocol.clone(), asset.clone(), deposited);
+
+        value = pricing::cusd_to_issue(e, asset, deposited);
+    }
+
+    process_update_epoch_principal(e, adapter, asset, next_epoch);
+
+    value
 }
 
 /// Cancel a pending harvest operation
+/// Yield already withdrawn from the protocol is moved to the asset's idle balance.
 pub fn process_cancel_harvest(e: &Env, protocol: &Symbol, asset: Address) {
     // Verify a pending harvest exists
-    if !storage::has_pending_harvest(e, protocol, &asset) {
-        panic_with_error!(e, LendingYieldControllerError::NoPendingHarvest);
+    let pending = storage::get_pending_harvest(e, protocol, &asset)
+        .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::NoPendingHarvest));
+
+    // Harvested yield is held by the controller until recompounded; keep it accounted for
+    if pending.state == HarvestState::Harvested {
+        storage::increase_idle_balance(e, &asset, pending.amount);
     }
 
     // Remove the pending harvest
@@ -406,3 +1135,32 @@ pub fn process_cancel_harvest(e: &Env, protocol: &Symbol, asset: Address) {
 
     LendingYieldControllerEvents::cancel_harvest(e, protocol.clone(), asset);
 }
+
+/// Deposit idle collateral held by the controller back into a lending protocol
+pub fn process_redeposit_idle(e: &Env, protocol: &Symbol, asset: Address, amount: i128) -> i128 {
+    if storage::read_idle_balance(e, &asset) < amount {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientIdleBalance);
+    }
+
+    let registry_client = storage::adapter_registry_client(e);
+    if !registry_client.is_supported_asset(&storage_types::YIELD_TYPE.id(), protocol, &asset) {
+        panic_with_error!(e, LendingYieldControllerError::UnsupportedAsset);
+    }
+    require_not_exit_only(e, protocol, &asset);
+    let adapter_address = registry_client.get_adapter(&storage_types::YIELD_TYPE.id(), protocol);
+
+    let deposited = process_deposit_for_claim(e, adapter_address, asset.clone(), amount);
+    storage::decrease_idle_balance(e, &asset, amount);
+
+    deposited
+}
+
+/// Transfer idle collateral held by the controller out to a recipient
+pub fn process_sweep_idle(e: &Env, asset: Address, to: Address, amount: i128) {
+    if storage::read_idle_balance(e, &asset) < amount {
+        panic_with_error!(e, LendingYieldControllerError::InsufficientIdleBalance);
+    }
+
+    storage::decrease_idle_balance(e, &asset, amount);
+    TokenClient::new(e, &asset).transfer(&e.current_contract_address(), &to, &amount);
+}
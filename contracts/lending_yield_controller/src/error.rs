@@ -26,4 +26,60 @@ pub enum LendingYieldControllerError {
     InvalidHarvestState = 1004,
     /// No yield available to harvest
     NoYieldToHarvest = 1005,
+
+    // Position errors
+    /// The user's position for this protocol/asset is smaller than the requested amount
+    InsufficientPosition = 1006,
+
+    // Asset configuration errors
+    /// Asset decimals exceed the supported maximum
+    InvalidDecimals = 1007,
+    /// Collateral factor must be between 1 and 10000 basis points
+    InvalidCollateralFactor = 1008,
+
+    // Oracle errors
+    /// The oracle has no usable price for the asset
+    PriceUnavailable = 1009,
+    /// The oracle price is older than the configured maximum age
+    StalePrice = 1010,
+
+    // Slippage errors
+    /// The cUSD issued or burned is outside the caller's bound
+    SlippageExceeded = 1011,
+
+    // Keeper errors
+    /// Keeper bounty must not exceed 10000 basis points
+    InvalidKeeperBounty = 1012,
+
+    // Idle balance errors
+    /// The idle balance of the asset is smaller than the requested amount
+    InsufficientIdleBalance = 1013,
+
+    // Allocation errors
+    /// Weighted allocation needs positive weights on protocols supporting the asset
+    InvalidAllocation = 1014,
+
+    // Rebalance errors
+    /// Rebalance must move a positive amount of principal between two different protocols
+    InvalidRebalance = 1015,
+
+    // Cap errors
+    /// The deposit would exceed an asset, protocol or cUSD supply cap
+    DepositCapExceeded = 1016,
+    /// Caps cannot be negative
+    InvalidCap = 1017,
+
+    // Redemption queue errors
+    /// No queued redemption exists with this ticket id
+    RedemptionNotFound = 1018,
+
+    // Emergency exit errors
+    /// The adapter was emergency withdrawn and only accepts exits for this asset
+    ExitOnly = 1019,
+    /// The balance recovered from the adapter is smaller than the requested amount
+    InsufficientRecoveredBalance = 1020,
+
+    // Pause errors
+    /// The operation is paused
+    Paused = 1021,
 }
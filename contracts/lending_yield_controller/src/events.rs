@@ -1,4 +1,5 @@
 use soroban_sdk::{Address, Env, Symbol};
+use crate::storage_types::{AllocationConfig, AssetOracleConfig, PauseFlag, RedemptionTicket};
 pub struct LendingYieldControllerEvents {}
 
 impl LendingYieldControllerEvents {
@@ -42,6 +43,81 @@ impl LendingYieldControllerEvents {
         e.events().publish(topics,  new_admin.clone());
     }
 
+    pub fn set_asset_decimals(e: &Env, asset: Address, decimals: u32) {
+        let topics = (Symbol::new(e, "set_asset_decimals"), asset);
+        e.events().publish(topics, decimals);
+    }
+
+    pub fn set_asset_oracle(e: &Env, asset: Address, config: AssetOracleConfig) {
+        let topics = (Symbol::new(e, "set_asset_oracle"), asset);
+        e.events().publish(topics, config);
+    }
+
+    pub fn remove_asset_oracle(e: &Env, asset: Address) {
+        let topics = (Symbol::new(e, "remove_asset_oracle"), asset);
+        e.events().publish(topics, ());
+    }
+
+    pub fn set_keeper_bounty(e: &Env, bounty_bps: u32) {
+        let topics = (Symbol::new(e, "set_keeper_bounty"),);
+        e.events().publish(topics, bounty_bps);
+    }
+
+    pub fn keeper_bounty(e: &Env, keeper: Address, amount: i128) {
+        let topics = (Symbol::new(e, "keeper_bounty"), keeper);
+        e.events().publish(topics, amount);
+    }
+
+    pub fn set_asset_cap(e: &Env, asset: Address, cap: Option<i128>) {
+        let topics = (Symbol::new(e, "set_asset_cap"), asset);
+        e.events().publish(topics, cap);
+    }
+
+    pub fn set_protocol_cap(e: &Env, protocol: Symbol, asset: Address, cap: Option<i128>) {
+        let topics = (Symbol::new(e, "set_protocol_cap"), protocol, asset);
+        e.events().publish(topics, cap);
+    }
+
+    pub fn set_cusd_supply_cap(e: &Env, cap: Option<i128>) {
+        let topics = (Symbol::new(e, "set_cusd_supply_cap"),);
+        e.events().publish(topics, cap);
+    }
+
+    pub fn request_redemption(e: &Env, ticket: RedemptionTicket) {
+        let topics = (Symbol::new(e, "request_redemption"), ticket.user.clone(), ticket.id);
+        e.events().publish(topics, ticket);
+    }
+
+    pub fn fill_redemption(e: &Env, id: u64, user: Address, withdrawn: i128, burned: i128) {
+        let topics = (Symbol::new(e, "fill_redemption"), user, id);
+        e.events().publish(topics, (withdrawn, burned));
+    }
+
+    pub fn cancel_redemption(e: &Env, ticket: RedemptionTicket) {
+        let topics = (Symbol::new(e, "cancel_redemption"), ticket.user.clone(), ticket.id);
+        e.events().publish(topics, ticket);
+    }
+
+    pub fn rebalance(e: &Env, from_protocol: Symbol, to_protocol: Symbol, asset: Address, amount: i128) {
+        let topics = (Symbol::new(e, "rebalance"), from_protocol, to_protocol);
+        e.events().publish(topics, (asset, amount));
+    }
+
+    pub fn emergency_withdraw_all(e: &Env, protocol: Symbol, asset: Address, principal: i128, yield_amount: i128) {
+        let topics = (Symbol::new(e, "emergency_withdraw_all"), protocol, asset);
+        e.events().publish(topics, (principal, yield_amount));
+    }
+
+    pub fn set_allocation(e: &Env, asset: Address, config: AllocationConfig) {
+        let topics = (Symbol::new(e, "set_allocation"), asset);
+        e.events().publish(topics, config);
+    }
+
+    pub fn harvest_all(e: &Env, caller: Address, processed: u32, harvested: i128, distributed: i128) {
+        let topics = (Symbol::new(e, "harvest_all"), caller);
+        e.events().publish(topics, (processed, harvested, distributed));
+    }
+
     pub fn harvest_yield(e: &Env, protocol: Symbol, asset: Address, amount: i128) {
         let topics = (Symbol::new(e, "harvest_yield"), protocol);
         e.events().publish(topics, (asset, amount));
@@ -61,4 +137,24 @@ impl LendingYieldControllerEvents {
         let topics = (Symbol::new(e, "cancel_harvest"), protocol);
         e.events().publish(topics, asset);
     }
+
+    pub fn redeposit_idle(e: &Env, protocol: Symbol, asset: Address, amount: i128) {
+        let topics = (Symbol::new(e, "redeposit_idle"), protocol);
+        e.events().publish(topics, (asset, amount));
+    }
+
+    pub fn sweep_idle(e: &Env, asset: Address, to: Address, amount: i128) {
+        let topics = (Symbol::new(e, "sweep_idle"), asset);
+        e.events().publish(topics, (to, amount));
+    }
+
+    pub fn pause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "pause"),);
+        e.events().publish(topics, flag);
+    }
+
+    pub fn unpause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "unpause"),);
+        e.events().publish(topics, flag);
+    }
 }
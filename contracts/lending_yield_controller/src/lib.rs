@@ -17,11 +17,15 @@ pub mod yield_distributor {
     );
 }
 
+mod allocation;
+mod caps;
+mod migrations;
 mod storage;
 mod storage_types;
 mod contract;
 mod events;
 mod error;
 mod controls;
+mod pricing;
 mod test;
 mod utils;
\ No newline at end of file
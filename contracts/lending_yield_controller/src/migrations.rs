@@ -0,0 +1,54 @@
+use soroban_sdk::{contracttype, Address, Env};
+use yield_adapter::lending_adapter::LendingAdapterClient;
+use crate::storage;
+use crate::storage_types::YIELD_TYPE;
+use access_control::role::Role;
+
+/// Schema version written by this build
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+/// Contracts deployed before versioning hold their data in the version 1 layout
+const UNVERSIONED: u32 = 1;
+
+/// Version 1 instance keys that version 2 moved elsewhere
+#[derive(Clone)]
+#[contracttype]
+pub(crate) enum LegacyDataKey {
+    Owner,
+    Admin,
+}
+
+/// Bring stored data up to `SCHEMA_VERSION` and return the resulting version
+pub fn migrate(e: &Env) -> u32 {
+    versioning::migrate(e, UNVERSIONED, SCHEMA_VERSION, step)
+}
+
+pub fn version(e: &Env) -> u32 {
+    versioning::read_version(e, UNVERSIONED)
+}
+
+fn step(e: &Env, from: u32) {
+    if from == 1 {
+        v1_to_v2(e);
+    }
+}
+
+/// Version 2 keeps the owner and admin in the shared access control storage and records the
+/// collateral of each user. Version 1 pooled every deposit under the controller without recording
+/// who made it, so that principal is kept as legacy collateral that positions don't cover.
+fn v1_to_v2(e: &Env) {
+    for (legacy, role) in [(LegacyDataKey::Owner, Role::Owner), (LegacyDataKey::Admin, Role::Admin)] {
+        if let Some(holder) = e.storage().instance().get::<_, Address>(&legacy) {
+            access_control::grant_role(e, role, &holder);
+            e.storage().instance().remove(&legacy);
+        }
+    }
+
+    let registry_client = storage::adapter_registry_client(e);
+    for (protocol, assets) in registry_client.get_protocols_with_assets(&YIELD_TYPE.id()).iter() {
+        let adapter = LendingAdapterClient::new(e, &registry_client.get_adapter(&YIELD_TYPE.id(), &protocol));
+        for asset in assets.iter() {
+            let principal = adapter.get_balance(&e.current_contract_address(), &asset) - adapter.get_yield(&asset);
+            storage::write_legacy_collateral(e, &protocol, &asset, principal);
+        }
+    }
+}
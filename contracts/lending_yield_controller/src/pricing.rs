@@ -0,0 +1,95 @@
+use soroban_sdk::{panic_with_error, token::TokenClient, Address, Env};
+use yield_adapter::price_oracle::{Asset, PriceOracleClient};
+use crate::error::LendingYieldControllerError;
+use crate::storage;
+use crate::storage_types::{AssetOracleConfig, CUSD_DECIMALS, MAX_BPS};
+
+/// Fetch the decimals of a collateral asset, preferring the admin-configured value
+/// and falling back to the decimals reported by the token contract
+pub fn asset_decimals(e: &Env, asset: &Address) -> u32 {
+    match storage::read_asset_decimals(e, asset) {
+        Some(decimals) => decimals,
+        None => TokenClient::new(e, asset).decimals(),
+    }
+}
+
+/// Amount of cUSD to issue for `amount` of collateral, rounded down
+///
+/// Assets with an oracle configured are valued at the oracle price less the
+/// collateral factor haircut; all other assets are valued at $1.
+pub fn cusd_to_issue(e: &Env, asset: &Address, amount: i128) -> i128 {
+    collateral_value(e, asset, amount, false)
+}
+
+/// Share of a position's cUSD `debt` released with `amount` of its `position` collateral,
+/// rounded up so the collateral left behind never backs more cUSD than was issued for it
+pub fn debt_share(e: &Env, debt: i128, position: i128, amount: i128) -> i128 {
+    if amount >= position {
+        return debt;
+    }
+    mul_div(e, debt, amount, position, true)
+}
+
+/// Amount of cUSD to burn when releasing `amount` of collateral at the current price, rounded up
+/// so that a withdrawal never burns less cUSD than the collateral backs
+pub fn cusd_to_burn(e: &Env, asset: &Address, amount: i128) -> i128 {
+    collateral_value(e, asset, amount, true)
+}
+
+fn collateral_value(e: &Env, asset: &Address, amount: i128, round_up: bool) -> i128 {
+    let scaled = scale(e, amount, asset_decimals(e, asset), CUSD_DECIMALS, round_up);
+
+    match storage::read_asset_oracle(e, asset) {
+        Some(config) => {
+            let (price, price_decimals) = read_price(e, asset, &config);
+            let value = mul_div(e, scaled, price, 10i128.pow(price_decimals), round_up);
+            mul_div(e, value, config.collateral_factor_bps as i128, MAX_BPS as i128, round_up)
+        }
+        None => scaled,
+    }
+}
+
+fn read_price(e: &Env, asset: &Address, config: &AssetOracleConfig) -> (i128, u32) {
+    let oracle = PriceOracleClient::new(e, &config.oracle);
+    let price_data = oracle
+        .lastprice(&Asset::Stellar(asset.clone()))
+        .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::PriceUnavailable));
+
+    if price_data.price <= 0 {
+        panic_with_error!(e, LendingYieldControllerError::PriceUnavailable);
+    }
+
+    let now = e.ledger().timestamp();
+    if price_data.timestamp > now || now - price_data.timestamp > config.max_price_age {
+        panic_with_error!(e, LendingYieldControllerError::StalePrice);
+    }
+
+    (price_data.price, oracle.decimals())
+}
+
+fn mul_div(e: &Env, a: i128, b: i128, denominator: i128, round_up: bool) -> i128 {
+    let product = a
+        .checked_mul(b)
+        .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::OverflowError));
+    let result = product / denominator;
+    if round_up && product % denominator > 0 {
+        result + 1
+    } else {
+        result
+    }
+}
+
+fn scale(e: &Env, amount: i128, from_decimals: u32, to_decimals: u32, round_up: bool) -> i128 {
+    if from_decimals == to_decimals {
+        return amount;
+    }
+
+    if from_decimals < to_decimals {
+        let factor = 10i128.pow(to_decimals - from_decimals);
+        return amount
+            .checked_mul(factor)
+            .unwrap_or_else(|| panic_with_error!(e, LendingYieldControllerError::OverflowError));
+    }
+
+    mul_div(e, amount, 1, 10i128.pow(from_decimals - to_decimals), round_up)
+}
@@ -1,9 +1,19 @@
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{Address, Env, Symbol, Vec};
+use access_control::role::Role;
 use crate::storage_types::{
     DataKey,
+    AllocationConfig,
+    AssetOracleConfig,
+    HarvestAllCursor,
+    PauseFlag,
     PendingHarvest,
+    RedemptionQueue,
+    RedemptionTicket,
+    UserPosition,
     INSTANCE_BUMP_AMOUNT,
     INSTANCE_LIFETIME_THRESHOLD,
+    PERSISTENT_BUMP_AMOUNT,
+    PERSISTENT_LIFETIME_THRESHOLD,
 };
 use crate::cusd_manager::Client as CUSDManagerClient;
 use crate::yield_adapter_registry::Client as YieldAdapterRegistryClient;
@@ -20,6 +30,11 @@ pub fn extend_instance(e: &Env) {
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 }
+pub fn extend_persistent(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
 fn read_address(e: &Env, key: &DataKey) -> Address {
     extend_instance(e);
     e.storage().instance().get(key).unwrap()  
@@ -28,10 +43,15 @@ fn write_address(e: &Env, key: &DataKey, address: &Address) {
     extend_instance(e);    
     e.storage().instance().set(key, address); 
 }
-pub fn read_admin(e: &Env) -> Address { read_address(e, &DataKey::Admin)}
-pub fn read_owner(e: &Env) -> Address { read_address(e, &DataKey::Owner)}
-pub fn write_admin(e: &Env, new_admin: Address) { write_address(e, &DataKey::Admin, &new_admin);}
-pub fn write_owner(e: &Env, new_owner: Address) { write_address(e, &DataKey::Owner, &new_owner);}
+pub fn read_admin(e: &Env) -> Address { read_role_holder(e, Role::Admin) }
+pub fn read_owner(e: &Env) -> Address { read_role_holder(e, Role::Owner) }
+pub fn write_admin(e: &Env, new_admin: Address) { access_control::grant_role(e, Role::Admin, &new_admin); }
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+
+fn read_role_holder(e: &Env, role: Role) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, role).unwrap()
+}
 pub fn get_cusd_manager(e: &Env) -> Address { read_address(e, &DataKey::CUSDManager) }
 pub fn get_adapter_registry(e: &Env) -> Address { read_address(e, &DataKey::AdapterRegistry) }
 pub fn get_yield_distributor(e: &Env) -> Address { read_address(e, &DataKey::YieldDistributor) }
@@ -75,3 +95,386 @@ pub fn has_pending_harvest(e: &Env, protocol: &Symbol, asset: &Address) -> bool
     extend_instance(e);
     e.storage().instance().has(&DataKey::PendingHarvest(protocol.clone(), asset.clone()))
 }
+
+pub fn read_asset_decimals(e: &Env, asset: &Address) -> Option<u32> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::AssetDecimals(asset.clone()))
+}
+
+pub fn write_asset_decimals(e: &Env, asset: &Address, decimals: u32) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::AssetDecimals(asset.clone()), &decimals);
+}
+
+pub fn read_asset_oracle(e: &Env, asset: &Address) -> Option<AssetOracleConfig> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::AssetOracle(asset.clone()))
+}
+
+pub fn write_asset_oracle(e: &Env, asset: &Address, config: &AssetOracleConfig) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::AssetOracle(asset.clone()), config);
+}
+
+pub fn remove_asset_oracle(e: &Env, asset: &Address) {
+    extend_instance(e);
+    e.storage().instance().remove(&DataKey::AssetOracle(asset.clone()));
+}
+
+pub fn read_keeper_bounty_bps(e: &Env) -> u32 {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::KeeperBountyBps).unwrap_or(0)
+}
+
+pub fn write_keeper_bounty_bps(e: &Env, bounty_bps: u32) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::KeeperBountyBps, &bounty_bps);
+}
+
+pub fn read_harvest_all_cursor(e: &Env) -> Option<HarvestAllCursor> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::HarvestAllCursor)
+}
+
+pub fn write_harvest_all_cursor(e: &Env, cursor: &HarvestAllCursor) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::HarvestAllCursor, cursor);
+}
+
+pub fn remove_harvest_all_cursor(e: &Env) {
+    extend_instance(e);
+    e.storage().instance().remove(&DataKey::HarvestAllCursor);
+}
+
+pub fn read_allocation(e: &Env, asset: &Address) -> Option<AllocationConfig> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::Allocation(asset.clone()))
+}
+
+pub fn write_allocation(e: &Env, asset: &Address, config: &AllocationConfig) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::Allocation(asset.clone()), config);
+}
+
+fn write_cap(e: &Env, key: &DataKey, cap: Option<i128>) {
+    extend_instance(e);
+    match cap {
+        Some(cap) => e.storage().instance().set(key, &cap),
+        None => e.storage().instance().remove(key),
+    }
+}
+
+pub fn read_asset_cap(e: &Env, asset: &Address) -> Option<i128> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::AssetCap(asset.clone()))
+}
+
+pub fn write_asset_cap(e: &Env, asset: &Address, cap: Option<i128>) {
+    write_cap(e, &DataKey::AssetCap(asset.clone()), cap);
+}
+
+pub fn read_protocol_cap(e: &Env, protocol: &Symbol, asset: &Address) -> Option<i128> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::ProtocolCap(protocol.clone(), asset.clone()))
+}
+
+pub fn write_protocol_cap(e: &Env, protocol: &Symbol, asset: &Address, cap: Option<i128>) {
+    write_cap(e, &DataKey::ProtocolCap(protocol.clone(), asset.clone()), cap);
+}
+
+pub fn read_cusd_supply_cap(e: &Env) -> Option<i128> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::CUSDSupplyCap)
+}
+
+pub fn write_cusd_supply_cap(e: &Env, cap: Option<i128>) {
+    write_cap(e, &DataKey::CUSDSupplyCap, cap);
+}
+
+// Idle balance storage functions
+
+pub fn read_idle_balance(e: &Env, asset: &Address) -> i128 {
+    let key = DataKey::IdleBalance(asset.clone());
+    match e.storage().persistent().get(&key) {
+        Some(balance) => {
+            extend_persistent(e, &key);
+            balance
+        }
+        None => 0,
+    }
+}
+
+pub fn increase_idle_balance(e: &Env, asset: &Address, amount: i128) {
+    let key = DataKey::IdleBalance(asset.clone());
+    let balance = read_idle_balance(e, asset) + amount;
+    e.storage().persistent().set(&key, &balance);
+    extend_persistent(e, &key);
+}
+
+pub fn decrease_idle_balance(e: &Env, asset: &Address, amount: i128) {
+    let key = DataKey::IdleBalance(asset.clone());
+    let balance = read_idle_balance(e, asset) - amount;
+    if balance <= 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &balance);
+        extend_persistent(e, &key);
+    }
+}
+
+// Rebalanced principal storage functions
+
+pub fn read_rebalanced_principal(e: &Env, protocol: &Symbol, asset: &Address) -> i128 {
+    let key = DataKey::RebalancedPrincipal(protocol.clone(), asset.clone());
+    match e.storage().persistent().get(&key) {
+        Some(amount) => {
+            extend_persistent(e, &key);
+            amount
+        }
+        None => 0,
+    }
+}
+
+pub fn adjust_rebalanced_principal(e: &Env, protocol: &Symbol, asset: &Address, delta: i128) {
+    let key = DataKey::RebalancedPrincipal(protocol.clone(), asset.clone());
+    let amount = read_rebalanced_principal(e, protocol, asset) + delta;
+    if amount == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &amount);
+        extend_persistent(e, &key);
+    }
+}
+
+// Emergency exit storage functions
+
+pub fn is_exit_only(e: &Env, protocol: &Symbol, asset: &Address) -> bool {
+    extend_instance(e);
+    e.storage().instance().has(&DataKey::ExitOnly(protocol.clone(), asset.clone()))
+}
+
+pub fn set_exit_only(e: &Env, protocol: &Symbol, asset: &Address) {
+    e.storage().instance().set(&DataKey::ExitOnly(protocol.clone(), asset.clone()), &true);
+    extend_instance(e);
+}
+
+pub fn read_recovered_balance(e: &Env, protocol: &Symbol, asset: &Address) -> i128 {
+    let key = DataKey::RecoveredBalance(protocol.clone(), asset.clone());
+    match e.storage().persistent().get(&key) {
+        Some(balance) => {
+            extend_persistent(e, &key);
+            balance
+        }
+        None => 0,
+    }
+}
+
+pub fn increase_recovered_balance(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) {
+    let key = DataKey::RecoveredBalance(protocol.clone(), asset.clone());
+    let balance = read_recovered_balance(e, protocol, asset) + amount;
+    e.storage().persistent().set(&key, &balance);
+    extend_persistent(e, &key);
+}
+
+pub fn decrease_recovered_balance(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) {
+    let key = DataKey::RecoveredBalance(protocol.clone(), asset.clone());
+    let balance = read_recovered_balance(e, protocol, asset) - amount;
+    if balance <= 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &balance);
+        extend_persistent(e, &key);
+    }
+}
+
+pub fn read_legacy_collateral(e: &Env, protocol: &Symbol, asset: &Address) -> i128 {
+    let key = DataKey::LegacyCollateral(protocol.clone(), asset.clone());
+    match e.storage().persistent().get(&key) {
+        Some(amount) => {
+            extend_persistent(e, &key);
+            amount
+        }
+        None => 0,
+    }
+}
+
+pub fn write_legacy_collateral(e: &Env, protocol: &Symbol, asset: &Address, amount: i128) {
+    let key = DataKey::LegacyCollateral(protocol.clone(), asset.clone());
+    if amount <= 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &amount);
+        extend_persistent(e, &key);
+    }
+}
+
+// Redemption queue storage functions
+
+/// Issue the next redemption ticket id
+pub fn next_redemption_id(e: &Env) -> u64 {
+    extend_instance(e);
+    let id: u64 = e.storage().instance().get(&DataKey::NextRedemptionId).unwrap_or(0);
+    e.storage().instance().set(&DataKey::NextRedemptionId, &(id + 1));
+    id
+}
+
+pub fn read_redemption_queue(e: &Env, protocol: &Symbol, asset: &Address) -> RedemptionQueue {
+    extend_instance(e);
+    e.storage()
+        .instance()
+        .get(&DataKey::RedemptionQueue(protocol.clone(), asset.clone()))
+        .unwrap_or_default()
+}
+
+pub fn write_redemption_queue(e: &Env, protocol: &Symbol, asset: &Address, queue: &RedemptionQueue) {
+    extend_instance(e);
+    e.storage()
+        .instance()
+        .set(&DataKey::RedemptionQueue(protocol.clone(), asset.clone()), queue);
+}
+
+pub fn read_redemption_slot(e: &Env, protocol: &Symbol, asset: &Address, slot: u64) -> Option<u64> {
+    let key = DataKey::RedemptionSlot(protocol.clone(), asset.clone(), slot);
+    let id = e.storage().persistent().get(&key);
+    if id.is_some() {
+        extend_persistent(e, &key);
+    }
+    id
+}
+
+pub fn write_redemption_slot(e: &Env, protocol: &Symbol, asset: &Address, slot: u64, id: u64) {
+    let key = DataKey::RedemptionSlot(protocol.clone(), asset.clone(), slot);
+    e.storage().persistent().set(&key, &id);
+    extend_persistent(e, &key);
+}
+
+pub fn remove_redemption_slot(e: &Env, protocol: &Symbol, asset: &Address, slot: u64) {
+    e.storage()
+        .persistent()
+        .remove(&DataKey::RedemptionSlot(protocol.clone(), asset.clone(), slot));
+}
+
+pub fn read_redemption_ticket(e: &Env, id: u64) -> Option<RedemptionTicket> {
+    let key = DataKey::RedemptionTicket(id);
+    let ticket = e.storage().persistent().get(&key);
+    if ticket.is_some() {
+        extend_persistent(e, &key);
+    }
+    ticket
+}
+
+pub fn write_redemption_ticket(e: &Env, ticket: &RedemptionTicket) {
+    let key = DataKey::RedemptionTicket(ticket.id);
+    e.storage().persistent().set(&key, ticket);
+    extend_persistent(e, &key);
+}
+
+pub fn remove_redemption_ticket(e: &Env, id: u64) {
+    e.storage().persistent().remove(&DataKey::RedemptionTicket(id));
+}
+
+// User position storage functions
+pub fn read_user_position(e: &Env, user: &Address, protocol: &Symbol, asset: &Address) -> i128 {
+    let key = DataKey::UserPosition(user.clone(), protocol.clone(), asset.clone());
+    match e.storage().persistent().get(&key) {
+        Some(amount) => {
+            extend_persistent(e, &key);
+            amount
+        },
+        None => 0,
+    }
+}
+
+fn read_user_position_keys(e: &Env, user: &Address) -> Vec<(Symbol, Address)> {
+    let key = DataKey::UserPositions(user.clone());
+    match e.storage().persistent().get(&key) {
+        Some(existing) => {
+            extend_persistent(e, &key);
+            existing
+        },
+        None => Vec::new(e),
+    }
+}
+
+fn write_user_position_keys(e: &Env, user: &Address, keys: &Vec<(Symbol, Address)>) {
+    let key = DataKey::UserPositions(user.clone());
+    if keys.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, keys);
+        extend_persistent(e, &key);
+    }
+}
+
+pub fn increase_user_position(e: &Env, user: &Address, protocol: &Symbol, asset: &Address, amount: i128) {
+    let key = DataKey::UserPosition(user.clone(), protocol.clone(), asset.clone());
+    let current = read_user_position(e, user, protocol, asset);
+    if current == 0 {
+        let mut keys = read_user_position_keys(e, user);
+        keys.push_back((protocol.clone(), asset.clone()));
+        write_user_position_keys(e, user, &keys);
+    }
+    e.storage().persistent().set(&key, &(current + amount));
+    extend_persistent(e, &key);
+}
+
+pub fn decrease_user_position(e: &Env, user: &Address, protocol: &Symbol, asset: &Address, amount: i128) {
+    let key = DataKey::UserPosition(user.clone(), protocol.clone(), asset.clone());
+    let remaining = read_user_position(e, user, protocol, asset) - amount;
+    if remaining > 0 {
+        e.storage().persistent().set(&key, &remaining);
+        extend_persistent(e, &key);
+        return;
+    }
+
+    e.storage().persistent().remove(&key);
+    let mut keys = read_user_position_keys(e, user);
+    if let Some(index) = keys.first_index_of((protocol.clone(), asset.clone())) {
+        keys.remove(index);
+        write_user_position_keys(e, user, &keys);
+    }
+}
+
+pub fn read_user_debt(e: &Env, user: &Address, protocol: &Symbol, asset: &Address) -> Option<i128> {
+    let key = DataKey::UserDebt(user.clone(), protocol.clone(), asset.clone());
+    let debt = e.storage().persistent().get(&key);
+    if debt.is_some() {
+        extend_persistent(e, &key);
+    }
+    debt
+}
+
+pub fn write_user_debt(e: &Env, user: &Address, protocol: &Symbol, asset: &Address, debt: i128) {
+    let key = DataKey::UserDebt(user.clone(), protocol.clone(), asset.clone());
+    e.storage().persistent().set(&key, &debt);
+    extend_persistent(e, &key);
+}
+
+pub fn remove_user_debt(e: &Env, user: &Address, protocol: &Symbol, asset: &Address) {
+    e.storage().persistent().remove(&DataKey::UserDebt(user.clone(), protocol.clone(), asset.clone()));
+}
+
+pub fn read_user_positions(e: &Env, user: &Address) -> Vec<UserPosition> {
+    let mut positions = Vec::new(e);
+    for (protocol, asset) in read_user_position_keys(e, user).iter() {
+        let amount = read_user_position(e, user, &protocol, &asset);
+        positions.push_back(UserPosition { protocol, asset, amount });
+    }
+    positions
+}
+
+// Pause storage functions
+
+pub fn is_paused(e: &Env, flag: PauseFlag) -> bool {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::Paused(flag)).unwrap_or(false)
+}
+
+pub fn write_paused(e: &Env, flag: PauseFlag, paused: bool) {
+    if paused {
+        e.storage().instance().set(&DataKey::Paused(flag), &true);
+    } else {
+        e.storage().instance().remove(&DataKey::Paused(flag));
+    }
+    extend_instance(e);
+}
@@ -1,10 +1,15 @@
-use ::soroban_sdk::{contracttype, Address, Symbol};
+use ::soroban_sdk::{contracttype, Address, Map, Symbol, Vec};
 use yield_adapter::contract_types::SupportedYieldType;
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
 pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
 pub(crate) const YIELD_TYPE: SupportedYieldType = SupportedYieldType::Lending;
+pub(crate) const CUSD_DECIMALS: u32 = 7;
+pub(crate) const MAX_ASSET_DECIMALS: u32 = 18;
+pub(crate) const MAX_BPS: u32 = 10_000;
 
 /// State of a pending harvest operation
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -17,6 +22,8 @@ pub enum HarvestState {
     Harvested = 1,
     /// Yield has been recompounded (re-deposited to protocol)
     Recompounded = 2,
+    /// Yield has been distributed; reported by `claim_yield` but never stored
+    Distributed = 3,
 }
 
 /// Pending harvest data stored between multi-stage operations
@@ -29,14 +36,162 @@ pub struct PendingHarvest {
     pub state: HarvestState,
 }
 
+/// Outcome of a single-call `claim_yield`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ClaimYieldResult {
+    /// Last harvest stage completed by the call
+    pub stage: HarvestState,
+    /// Amount handled by that stage: collateral for Harvested/Recompounded, cUSD for Distributed
+    pub amount: i128,
+}
+
+/// How deposits without a named protocol are split across protocols
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum AllocationMode {
+    /// Split proportionally to the configured target weights
+    Weighted,
+    /// Deposit entirely into the protocol with the highest APY
+    HighestApy,
+}
+
+/// Allocation of an asset across the protocols that support it
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AllocationConfig {
+    pub mode: AllocationMode,
+    /// Target weight of each protocol, used in `Weighted` mode
+    pub weights: Map<Symbol, u32>,
+}
+
+/// A queued withdrawal waiting for protocol liquidity
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct RedemptionTicket {
+    pub id: u64,
+    pub user: Address,
+    pub protocol: Symbol,
+    pub asset: Address,
+    /// Collateral still to be withdrawn
+    pub amount: i128,
+    /// cUSD held by the controller until the withdrawal is filled or cancelled
+    pub escrow: i128,
+}
+
+/// Bounds of a protocol/asset pair's redemption queue; slots `head..tail` may still be pending
+#[derive(Clone, Debug, Default, PartialEq)]
+#[contracttype]
+pub struct RedemptionQueue {
+    pub head: u64,
+    pub tail: u64,
+}
+
+/// Progress of a `harvest_all` walk across registered protocol/asset pairs
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct HarvestAllCursor {
+    /// Protocol/asset pairs already harvested in this walk. Keyed by pair rather than list position
+    /// so pairs registered or removed between calls are neither skipped nor harvested twice
+    pub visited: Vec<(Symbol, Address)>,
+    /// cUSD value of the yield harvested so far in this walk
+    pub amount: i128,
+}
+
+/// Outcome of a `harvest_all` call
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct HarvestAllResult {
+    /// Protocol/asset pairs visited by this call
+    pub processed: u32,
+    /// cUSD value of the yield harvested by this call
+    pub harvested: i128,
+    /// Whether the walk reached the last pair and distributed the accumulated yield
+    pub complete: bool,
+    /// cUSD distributed to treasury and members when the walk completed
+    pub distributed: i128,
+}
+
+/// Collateral a user has deposited into a protocol/asset pair through the controller
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct UserPosition {
+    pub protocol: Symbol,
+    pub asset: Address,
+    pub amount: i128,
+}
+
+/// Oracle pricing applied to a non-USD collateral asset
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AssetOracleConfig {
+    /// SEP-40 oracle quoting the asset in USD
+    pub oracle: Address,
+    /// Share of the collateral value issued as cUSD, in basis points
+    pub collateral_factor_bps: u32,
+    /// Maximum age of an oracle price in seconds
+    pub max_price_age: u64,
+}
+
+/// Operations that can be paused independently during an incident
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PauseFlag {
+    Deposit,
+    Withdraw,
+    Harvest,
+    Distribution,
+    Mint,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    Owner,
-    Admin,
     CUSDManager,
     AdapterRegistry,
     YieldDistributor,
     /// Pending harvest for a specific protocol/asset pair
     PendingHarvest(Symbol, Address),
+    /// Collateral deposited by a user into a protocol/asset pair
+    UserPosition(Address, Symbol, Address),
+    /// Protocol/asset pairs a user currently holds a position in
+    UserPositions(Address),
+    /// cUSD issued against a user's position in a protocol/asset pair
+    UserDebt(Address, Symbol, Address),
+    /// Admin-configured decimals of a collateral asset
+    AssetDecimals(Address),
+    /// Oracle pricing of a collateral asset
+    AssetOracle(Address),
+    /// Share of distributed yield paid to keepers, in basis points
+    KeeperBountyBps,
+    /// Progress of the current `harvest_all` walk
+    HarvestAllCursor,
+    /// Collateral held by the controller outside of any protocol, per asset
+    IdleBalance(Address),
+    /// Allocation of an asset across protocols for deposits without a named protocol
+    Allocation(Address),
+    /// Net principal moved into (positive) or out of (negative) a protocol for an asset by rebalancing
+    RebalancedPrincipal(Symbol, Address),
+    /// Maximum collateral held for an asset across all protocols
+    AssetCap(Address),
+    /// Maximum collateral held for an asset in a single protocol
+    ProtocolCap(Symbol, Address),
+    /// Maximum cUSD supply the controller will issue up to
+    CUSDSupplyCap,
+    /// Id the next redemption ticket is issued under
+    NextRedemptionId,
+    /// Bounds of the redemption queue of a protocol/asset pair
+    RedemptionQueue(Symbol, Address),
+    /// Ticket id queued at a slot of a protocol/asset pair's redemption queue
+    RedemptionSlot(Symbol, Address, u64),
+    /// A queued redemption by ticket id
+    RedemptionTicket(u64),
+    /// Set once a protocol's adapter was emergency withdrawn for an asset
+    ExitOnly(Symbol, Address),
+    /// Principal recovered from an exit-only adapter and held by the controller
+    RecoveredBalance(Symbol, Address),
+    /// Collateral deposited into a protocol/asset pair before user positions were recorded
+    LegacyCollateral(Symbol, Address),
+    /// Set while an operation is paused
+    Paused(PauseFlag),
 }
\ No newline at end of file
@@ -3,11 +3,13 @@
 use soroban_sdk::{
     testutils::{Address as _, Events, Ledger},
     token::{StellarAssetClient, TokenClient},
-    vec, Address, Env, IntoVal, Symbol,
+    contract, contractimpl, map, vec, Address, Env, IntoVal, Symbol, Val, Vec,
 };
 
+use access_control::role::Role;
 use crate::{
     contract::{LendingYieldController, LendingYieldControllerClient},
+    migrations::LegacyDataKey,
 };
 
 use cusd_manager::contract::{CUSDManager, CUSDManagerClient};
@@ -16,6 +18,63 @@ use yield_adapter_registry::contract::{
 };
 use yield_distributor::contract::{YieldDistributor, YieldDistributorClient};
 use yield_adapter::contract_types::{SupportedAdapter, SupportedYieldType};
+use blend_capital_adapter::{
+    contract::{BlendCapitalAdapter, BlendCapitalAdapterClient},
+    mocks::blend_pool_mock::{PoolContract, PoolContractClient},
+    mocks::price_oracle_mock::{PriceOracleMock, PriceOracleMockClient},
+};
+use yield_adapter::price_oracle::Asset;
+
+/// Adapter that keeps 1% of every deposit and withdrawal
+#[contract]
+struct FeeTakingAdapter;
+
+#[contractimpl]
+impl FeeTakingAdapter {
+    pub fn deposit_auth(_e: Env, _user: Address, _asset: Address, _amount: i128) -> Option<(Address, Symbol, Vec<Val>)> {
+        None
+    }
+
+    pub fn deposit(_e: Env, _user: Address, _asset: Address, amount: i128) -> i128 {
+        amount * 99 / 100
+    }
+
+    pub fn withdraw_auth(_e: Env, _user: Address, _asset: Address, _amount: i128) -> Option<(Address, Symbol, Vec<Val>)> {
+        None
+    }
+
+    pub fn withdraw(_e: Env, _user: Address, _asset: Address, amount: i128) -> i128 {
+        amount * 99 / 100
+    }
+}
+
+mod fixed_apy_adapter {
+    use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Val, Vec};
+
+    /// Adapter that accepts deposits in full and reports a fixed APY
+    #[contract]
+    pub struct FixedApyAdapter;
+
+    #[contractimpl]
+    impl FixedApyAdapter {
+        pub fn __constructor(e: Env, apy: u32) {
+            e.storage().instance().set(&Symbol::new(&e, "apy"), &apy);
+        }
+
+        pub fn deposit_auth(_e: Env, _user: Address, _asset: Address, _amount: i128) -> Option<(Address, Symbol, Vec<Val>)> {
+            None
+        }
+
+        pub fn deposit(_e: Env, _user: Address, _asset: Address, amount: i128) -> i128 {
+            amount
+        }
+
+        pub fn get_apy(e: Env, _asset: Address) -> u32 {
+            e.storage().instance().get(&Symbol::new(&e, "apy")).unwrap()
+        }
+    }
+}
+use fixed_apy_adapter::FixedApyAdapter;
 
 struct TestFixture {
     env: Env,
@@ -164,6 +223,64 @@ impl TestFixture {
         self.env.mock_all_auths();
         self.cusd_client().approve(user, &self.controller.address, &amount, &1000000);
     }
+
+    /// Registers a Blend adapter backed by the mock pool and enables USDC on it
+    fn setup_blend_adapter(&self) -> (Address, PoolContractClient<'static>) {
+        self.setup_blend_adapter_as(&SupportedAdapter::BlendCapital.id())
+    }
+
+    /// Registers a Blend adapter backed by its own mock pool under `protocol` and enables USDC on it
+    fn setup_blend_adapter_as(&self, protocol: &Symbol) -> (Address, PoolContractClient<'static>) {
+        self.setup_blend_adapter_for(protocol, &self.usdc_token_id)
+    }
+
+    /// Registers a Blend adapter backed by its own mock pool under `protocol` and enables `asset` on it
+    fn setup_blend_adapter_for(&self, protocol: &Symbol, asset: &Address) -> (Address, PoolContractClient<'static>) {
+        self.env.mock_all_auths();
+        let pool_id = self.env.register(PoolContract, ());
+        let pool = PoolContractClient::new(&self.env, &pool_id);
+        pool.init(asset);
+
+        let blend_token_id = self.env.register_stellar_asset_contract_v2(self.token_admin.clone()).address();
+        let adapter_id = self.env.register(
+            BlendCapitalAdapter,
+            (self.controller.address.clone(), pool_id, blend_token_id),
+        );
+        self.adapter_registry.register_adapter(&SupportedYieldType::Lending.id(), protocol, &adapter_id);
+        self.adapter_registry.add_support_for_asset(&SupportedYieldType::Lending.id(), protocol, asset);
+
+        (adapter_id, pool)
+    }
+
+    /// Registers an adapter that keeps 1% of every deposit and withdrawal and enables USDC on it
+    fn setup_fee_taking_adapter(&self) -> Symbol {
+        self.env.mock_all_auths();
+        let protocol = Symbol::new(&self.env, "FEE_LA");
+        let adapter_id = self.env.register(FeeTakingAdapter, ());
+        self.adapter_registry.register_adapter(&SupportedYieldType::Lending.id(), &protocol, &adapter_id);
+        self.adapter_registry.add_support_for_asset(&SupportedYieldType::Lending.id(), &protocol, &self.usdc_token_id);
+        protocol
+    }
+
+    /// Registers an adapter reporting a fixed APY and enables USDC on it
+    fn setup_fixed_apy_adapter(&self, name: &str, apy: u32) -> Symbol {
+        self.env.mock_all_auths();
+        let protocol = Symbol::new(&self.env, name);
+        let adapter_id = self.env.register(FixedApyAdapter, (apy,));
+        self.adapter_registry.register_adapter(&SupportedYieldType::Lending.id(), &protocol, &adapter_id);
+        self.adapter_registry.add_support_for_asset(&SupportedYieldType::Lending.id(), &protocol, &self.usdc_token_id);
+        protocol
+    }
+
+    /// Prices USDC through a mock 14 decimal oracle at `price` with the given collateral factor
+    fn setup_usdc_oracle(&self, price: i128, collateral_factor_bps: u32) -> PriceOracleMockClient<'static> {
+        self.env.mock_all_auths();
+        let oracle_id = self.env.register(PriceOracleMock, (14u32,));
+        let oracle = PriceOracleMockClient::new(&self.env, &oracle_id);
+        oracle.set_price(&Asset::Stellar(self.usdc_token_id.clone()), &price, &self.env.ledger().timestamp());
+        self.controller.set_asset_oracle(&self.usdc_token_id, &oracle_id, &collateral_factor_bps, &3600);
+        oracle
+    }
 }
 
 #[test]
@@ -206,6 +323,7 @@ fn test_deposit_unsupported_asset() {
         &fixture.user1,
         &unsupported_token_id,
         &1000_0000000,
+        &None,
     );
 }
 
@@ -223,6 +341,8 @@ fn test_withdraw_unsupported_asset() {
         &fixture.user1,
         &unsupported_token_id,
         &1000_0000000,
+        &None,
+        &false,
     );
 }
 
@@ -251,6 +371,7 @@ fn test_deposit_unauthorized() {
         &fixture.user1,
         &fixture.usdc_token_id,
         &1000_0000000,
+        &None,
     );
 }
 
@@ -279,6 +400,8 @@ fn test_withdraw_unauthorized() {
         &fixture.user1,
         &fixture.usdc_token_id,
         &1000_0000000,
+        &None,
+        &false,
     );
 }
 
@@ -623,7 +746,10 @@ fn test_upgrade_function_exists() {
 // Multi-stage yield claiming tests
 // ============================================================================
 
-use crate::storage_types::HarvestState;
+use crate::storage_types::{
+    AllocationConfig, AllocationMode, ClaimYieldResult, HarvestAllCursor, HarvestAllResult, HarvestState, PauseFlag,
+    DataKey, RedemptionQueue,
+};
 
 #[test]
 fn test_get_pending_harvest_none() {
@@ -659,7 +785,7 @@ fn test_harvest_yield_unauthorized() {
     fixture.env.mock_auths(&[]);
 
     let protocol = SupportedAdapter::BlendCapital.id();
-    fixture.controller.harvest_yield(&protocol, &fixture.usdc_token_id);
+    fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -671,7 +797,7 @@ fn test_recompound_yield_unauthorized() {
     fixture.env.mock_auths(&[]);
 
     let protocol = SupportedAdapter::BlendCapital.id();
-    fixture.controller.recompound_yield(&protocol, &fixture.usdc_token_id);
+    fixture.controller.recompound_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -683,7 +809,7 @@ fn test_finalize_distribution_unauthorized() {
     fixture.env.mock_auths(&[]);
 
     let protocol = SupportedAdapter::BlendCapital.id();
-    fixture.controller.finalize_distribution(&protocol, &fixture.usdc_token_id);
+    fixture.controller.finalize_distribution(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -721,7 +847,7 @@ fn test_recompound_yield_no_pending_harvest() {
     let protocol = SupportedAdapter::BlendCapital.id();
 
     // Should panic with NoPendingHarvest (1002)
-    fixture.controller.recompound_yield(&protocol, &fixture.usdc_token_id);
+    fixture.controller.recompound_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -751,7 +877,7 @@ fn test_finalize_distribution_no_pending_harvest() {
     let protocol = SupportedAdapter::BlendCapital.id();
 
     // Should panic with NoPendingHarvest (1002)
-    fixture.controller.finalize_distribution(&protocol, &fixture.usdc_token_id);
+    fixture.controller.finalize_distribution(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -777,7 +903,7 @@ fn test_harvest_yield_no_adapter() {
     let protocol = SupportedAdapter::BlendCapital.id();
 
     // Should panic with InvalidYieldAdapter (1100) because no adapter is registered
-    fixture.controller.harvest_yield(&protocol, &fixture.usdc_token_id);
+    fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
 }
 
 #[test]
@@ -806,6 +932,7 @@ fn test_pending_harvest_state_enum() {
     assert_eq!(HarvestState::None as u32, 0);
     assert_eq!(HarvestState::Harvested as u32, 1);
     assert_eq!(HarvestState::Recompounded as u32, 2);
+    assert_eq!(HarvestState::Distributed as u32, 3);
 }
 
 #[test]
@@ -822,29 +949,1914 @@ fn test_multi_stage_event_names() {
 #[test]
 fn test_claim_yield_returns_zero_when_no_yield() {
     let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
 
     fixture.env.mock_all_auths();
-
-    // Register a dummy adapter
-    let dummy_adapter = Address::generate(&fixture.env);
-    fixture.adapter_registry.register_adapter(
-        &SupportedYieldType::Lending.id(),
-        &SupportedAdapter::BlendCapital.id(),
-        &dummy_adapter,
-    );
-    fixture.adapter_registry.add_support_for_asset(
-        &SupportedYieldType::Lending.id(),
-        &SupportedAdapter::BlendCapital.id(),
-        &fixture.usdc_token_id,
-    );
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
 
     // Set timestamp to make distribution available
     let current_time = fixture.env.ledger().timestamp();
     fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
 
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::None, amount: 0 });
+    assert!(fixture.controller.get_pending_harvest(&protocol, &fixture.usdc_token_id).is_none());
+}
+
+#[test]
+fn test_claim_yield_runs_all_stages() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    let member = Address::generate(&fixture.env);
+    fixture.yield_distributor.add_member(&fixture.admin, &member);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::Distributed, amount: 50_0000000 });
+    assert!(fixture.controller.get_pending_harvest(&protocol, &fixture.usdc_token_id).is_none());
+
+    let treasury = fixture.yield_distributor.get_treasury();
+    assert_eq!(fixture.yield_distributor.get_unclaimed(&treasury), 5_0000000);
+    assert_eq!(fixture.yield_distributor.get_unclaimed(&member), 45_0000000);
+    fixture.yield_distributor.claim(&member);
+    assert_eq!(fixture.cusd_client().balance(&member), 45_0000000);
+}
+
+#[test]
+fn test_claim_yield_stops_before_distribution_and_resumes() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    // Close out the current period so the next distribution is not yet available
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+    fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result.stage, HarvestState::Recompounded);
+    assert!(result.amount > 0);
+    let pending = fixture.controller.get_pending_harvest(&protocol, &fixture.usdc_token_id).unwrap();
+    assert_eq!(pending.state, HarvestState::Recompounded);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let resumed = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(resumed, ClaimYieldResult { stage: HarvestState::Distributed, amount: pending.amount });
+    assert!(fixture.controller.get_pending_harvest(&protocol, &fixture.usdc_token_id).is_none());
+}
+
+#[test]
+fn test_claim_yield_resumes_from_harvested() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let harvested = fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::Distributed, amount: harvested });
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_claim_yield_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.claim_yield(&fixture.admin, &SupportedAdapter::BlendCapital.id(), &fixture.usdc_token_id);
+}
+
+// ============================================================================
+// User position tests
+// ============================================================================
+
+#[test]
+fn test_deposit_records_user_position() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user2, &fixture.usdc_token_id, &200_0000000, &None);
+
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        1500_0000000
+    );
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user2, &protocol, &fixture.usdc_token_id),
+        200_0000000
+    );
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1500_0000000);
+
+    let positions = fixture.controller.get_user_positions(&fixture.user1);
+    assert_eq!(positions.len(), 1);
+    let position = positions.get(0).unwrap();
+    assert_eq!(position.protocol, protocol);
+    assert_eq!(position.asset, fixture.usdc_token_id);
+    assert_eq!(position.amount, 1500_0000000);
+}
+
+#[test]
+fn test_get_user_position_none() {
+    let fixture = TestFixture::create();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        0
+    );
+    assert_eq!(fixture.controller.get_user_positions(&fixture.user1).len(), 0);
+}
+
+#[test]
+fn test_withdraw_reduces_and_closes_user_position() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &400_0000000, &None, &false);
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        600_0000000
+    );
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 600_0000000);
+
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &600_0000000, &None, &false);
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        0
+    );
+    assert_eq!(fixture.controller.get_user_positions(&fixture.user1).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1006)")]
+fn test_withdraw_exceeding_user_position() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user2, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    // user2 cannot withdraw against user1's collateral even with enough cUSD
+    fixture.cusd_client().transfer(&fixture.user1, &fixture.user2, &500_0000000);
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user2, &fixture.usdc_token_id, &1500_0000000, &None, &false);
+}
+
+#[test]
+fn test_asset_decimals_default_to_token() {
+    let fixture = TestFixture::create();
+    assert_eq!(fixture.controller.get_asset_decimals(&fixture.usdc_token_id), 7);
+}
+
+#[test]
+fn test_set_asset_decimals() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.set_asset_decimals(&fixture.usdc_token_id, &6);
+    assert_eq!(fixture.controller.get_asset_decimals(&fixture.usdc_token_id), 6);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1007)")]
+fn test_set_asset_decimals_too_large() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.set_asset_decimals(&fixture.usdc_token_id, &19);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_asset_decimals_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.set_asset_decimals(&fixture.usdc_token_id, &6);
+}
+
+#[test]
+fn test_deposit_and_withdraw_scale_fewer_decimals() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_asset_decimals(&fixture.usdc_token_id, &6);
+
+    // 1000 units of a 6 decimal asset issue 1000 cUSD
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_000000, &None);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1000_0000000);
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        1000_000000
+    );
+
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &400_000000, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 600_0000000);
+}
+
+#[test]
+fn test_deposit_and_withdraw_round_in_favor_of_protocol() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_asset_decimals(&fixture.usdc_token_id, &9);
+
+    // 1.23456789 cUSD worth of collateral issues 1.2345678 cUSD (rounded down)
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1_234567899, &None);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1_2345678);
+
+    // Withdrawing 0.000000001 burns a full cUSD stroop (rounded up)
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1_2345677);
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        1_234567898
+    );
+}
+
+#[test]
+fn test_set_and_remove_asset_oracle() {
+    let fixture = TestFixture::create();
+    let oracle = fixture.setup_usdc_oracle(1_00000000000000, 9000);
+
+    let config = fixture.controller.get_asset_oracle(&fixture.usdc_token_id).unwrap();
+    assert_eq!(config.oracle, oracle.address);
+    assert_eq!(config.collateral_factor_bps, 9000);
+    assert_eq!(config.max_price_age, 3600);
+
+    fixture.controller.remove_asset_oracle(&fixture.usdc_token_id);
+    assert_eq!(fixture.controller.get_asset_oracle(&fixture.usdc_token_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1008)")]
+fn test_set_asset_oracle_invalid_collateral_factor() {
+    let fixture = TestFixture::create();
+    fixture.setup_usdc_oracle(1_00000000000000, 10001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_asset_oracle_unauthorized() {
+    let fixture = TestFixture::create();
+    let oracle = Address::generate(&fixture.env);
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.set_asset_oracle(&fixture.usdc_token_id, &oracle, &9000, &3600);
+}
+
+#[test]
+fn test_oracle_priced_deposit_and_withdraw() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    // Collateral worth $0.10 with an 80% collateral factor
+    fixture.setup_usdc_oracle(10000000000000, 8000);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 80_0000000);
+    assert_eq!(
+        fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id),
+        1000_0000000
+    );
+
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 40_0000000);
+}
+
+#[test]
+fn test_oracle_priced_withdraw_rounds_up() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.setup_usdc_oracle(10000000000000, 8000);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    // 1 stroop of collateral is worth less than 1 stroop of cUSD but still burns one
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 80_0000000 - 1);
+}
+
+#[test]
+fn test_withdraw_after_price_move_burns_issued_cusd() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    // Collateral worth $1.00 with an 80% collateral factor issues 800 cUSD
+    let oracle = fixture.setup_usdc_oracle(1_00000000000000, 8000);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    assert_eq!(fixture.controller.get_user_debt(&fixture.user1, &protocol, &fixture.usdc_token_id), 800_0000000);
+
+    // The price halves; half the collateral still releases half the cUSD issued for it
+    let timestamp = fixture.env.ledger().timestamp();
+    oracle.set_price(&Asset::Stellar(fixture.usdc_token_id.clone()), &50000000000000, &timestamp);
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 400_0000000);
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), 400_0000000);
+    assert_eq!(fixture.controller.get_user_debt(&fixture.user1, &protocol, &fixture.usdc_token_id), 400_0000000);
+
+    // The price doubles; withdrawing the rest burns exactly the cUSD left, so none is left unbacked
+    oracle.set_price(&Asset::Stellar(fixture.usdc_token_id.clone()), &2_00000000000000, &timestamp);
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None, &false);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 0);
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), 0);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_user_debt(&fixture.user1, &protocol, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1010)")]
+fn test_oracle_priced_deposit_stale_price() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
     let protocol = SupportedAdapter::BlendCapital.id();
 
-    // claim_yield should return 0 when there's no yield
-    // Note: This will fail without a proper mock adapter that returns 0 yield
-    // The test documents expected behavior
-}
\ No newline at end of file
+    fixture.setup_usdc_oracle(10000000000000, 8000);
+    fixture.env.ledger().with_mut(|li| li.timestamp += 3601);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1009)")]
+fn test_oracle_priced_deposit_price_unavailable() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    let oracle_id = fixture.env.register(PriceOracleMock, (14u32,));
+    fixture.controller.set_asset_oracle(&fixture.usdc_token_id, &oracle_id, &8000, &3600);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+}
+
+#[test]
+fn test_deposit_issues_for_adapter_reported_amount() {
+    let fixture = TestFixture::create();
+    let protocol = fixture.setup_fee_taking_adapter();
+
+    let deposited = fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    assert_eq!(deposited, 990_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 990_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id), 990_0000000);
+}
+
+#[test]
+fn test_deposit_within_min_out() {
+    let fixture = TestFixture::create();
+    let protocol = fixture.setup_fee_taking_adapter();
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &Some(990_0000000));
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 990_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1011)")]
+fn test_deposit_below_min_out() {
+    let fixture = TestFixture::create();
+    let protocol = fixture.setup_fee_taking_adapter();
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &Some(1000_0000000));
+}
+
+#[test]
+fn test_withdraw_burns_for_adapter_reported_amount() {
+    let fixture = TestFixture::create();
+    let protocol = fixture.setup_fee_taking_adapter();
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    let withdrawn = fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &Some(495_0000000), &false);
+    assert_eq!(withdrawn, 495_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 495_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id), 495_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1011)")]
+fn test_withdraw_above_max_burn() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &Some(499_0000000), &false);
+}
+
+// ============================================================================
+// Keeper tests
+// ============================================================================
+
+#[test]
+fn test_set_keeper_bounty() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    assert_eq!(fixture.controller.get_keeper_bounty(), 0);
+    fixture.controller.set_keeper_bounty(&500);
+    assert_eq!(fixture.controller.get_keeper_bounty(), 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1012)")]
+fn test_set_keeper_bounty_too_large() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.set_keeper_bounty(&10001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_keeper_bounty_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.set_keeper_bounty(&500);
+}
+
+#[test]
+fn test_keeper_claim_yield_pays_bounty() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+    let keeper = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_keeper_bounty(&500);
+    let member = Address::generate(&fixture.env);
+    fixture.yield_distributor.add_member(&fixture.admin, &member);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let result = fixture.controller.claim_yield(&keeper, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::Distributed, amount: 47_5000000 });
+
+    // 5% bounty is paid before the 10% treasury share
+    assert_eq!(fixture.cusd_client().balance(&keeper), 2_5000000);
+    let treasury = fixture.yield_distributor.get_treasury();
+    assert_eq!(fixture.yield_distributor.get_unclaimed(&treasury), 4_7500000);
+    assert_eq!(fixture.yield_distributor.get_unclaimed(&member), 42_7500000);
+    fixture.yield_distributor.claim(&member);
+    assert_eq!(fixture.cusd_client().balance(&member), 42_7500000);
+}
+
+#[test]
+fn test_keeper_drives_staged_harvest() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+    let keeper = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_keeper_bounty(&1000);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    fixture.controller.harvest_yield(&keeper, &protocol, &fixture.usdc_token_id);
+    fixture.controller.recompound_yield(&keeper, &protocol, &fixture.usdc_token_id);
+    let distributed = fixture.controller.finalize_distribution(&keeper, &protocol, &fixture.usdc_token_id);
+
+    assert_eq!(distributed, 45_0000000);
+    assert_eq!(fixture.cusd_client().balance(&keeper), 5_0000000);
+}
+
+#[test]
+fn test_admin_claim_yield_pays_no_bounty() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_keeper_bounty(&500);
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result.amount, 50_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.admin), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1001)")]
+fn test_keeper_harvest_before_distribution_available() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+    let keeper = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    // Close out the current period so the next distribution is not yet available
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+    fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    fixture.controller.harvest_yield(&keeper, &protocol, &fixture.usdc_token_id);
+}
+
+#[test]
+fn test_keeper_role_harvests_before_distribution_available() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+    let keeper = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.grant_role(&Role::Keeper, &keeper);
+    assert!(fixture.controller.has_role(&Role::Keeper, &keeper));
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+    fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+
+    // The keeper role stages the next harvest without waiting for the period to close
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    let harvested = fixture.controller.harvest_yield(&keeper, &protocol, &fixture.usdc_token_id);
+    assert_eq!(harvested, 50_0000000);
+
+    // Revoking the role puts the keeper back behind the distribution period
+    fixture.controller.revoke_role(&Role::Keeper, &keeper);
+    assert!(fixture.controller.try_recompound_yield(&keeper, &protocol, &fixture.usdc_token_id).is_err());
+}
+
+// ============================================================================
+// Harvest-all tests
+// ============================================================================
+
+/// Two Blend protocols with 1000 USDC deposited each, earning 50 and 20 USDC of yield
+fn setup_two_protocols_with_yield(fixture: &TestFixture) -> (Symbol, Symbol) {
+    let blend = SupportedAdapter::BlendCapital.id();
+    let other = Symbol::new(&fixture.env, "BC_LA2");
+    let (_, blend_pool) = fixture.setup_blend_adapter();
+    let (_, other_pool) = fixture.setup_blend_adapter_as(&other);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    other_pool.add_yield(&fixture.usdc_token_id, &20_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    (blend, other)
+}
+
+#[test]
+fn test_harvest_all_distributes_once() {
+    let fixture = TestFixture::create();
+    let (blend, other) = setup_two_protocols_with_yield(&fixture);
+    let epoch = fixture.yield_distributor.get_current_epoch();
+
+    let result = fixture.controller.harvest_all(&fixture.admin, &10);
+    assert_eq!(
+        result,
+        HarvestAllResult { processed: 2, harvested: 70_0000000, complete: true, distributed: 70_0000000 }
+    );
+    assert_eq!(fixture.controller.get_harvest_all_cursor(), None);
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch + 1);
+
+    let treasury = fixture.yield_distributor.get_treasury();
+    assert_eq!(fixture.yield_distributor.get_unclaimed(&treasury), 70_0000000);
+
+    // Both pairs start the next epoch with no outstanding yield
+    assert_eq!(fixture.controller.get_yield(&blend, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_yield(&other, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+fn test_harvest_all_resumes_from_cursor() {
+    let fixture = TestFixture::create();
+    let (blend, _) = setup_two_protocols_with_yield(&fixture);
+    let epoch = fixture.yield_distributor.get_current_epoch();
+
+    let first = fixture.controller.harvest_all(&fixture.admin, &1);
+    assert_eq!(first.processed, 1);
+    assert!(!first.complete);
+    assert_eq!(first.distributed, 0);
+    assert_eq!(
+        fixture.controller.get_harvest_all_cursor(),
+        Some(HarvestAllCursor { visited: vec![&fixture.env, (blend.clone(), fixture.usdc_token_id.clone())], amount: first.harvested })
+    );
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch);
+
+    let second = fixture.controller.harvest_all(&fixture.admin, &1);
+    assert_eq!(second.processed, 1);
+    assert!(second.complete);
+    assert_eq!(first.harvested + second.harvested, 70_0000000);
+    assert_eq!(second.distributed, 70_0000000);
+    assert_eq!(fixture.controller.get_harvest_all_cursor(), None);
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch + 1);
+}
+
+#[test]
+fn test_harvest_all_cursor_survives_pair_changes() {
+    let fixture = TestFixture::create();
+    let (blend, other) = setup_two_protocols_with_yield(&fixture);
+    let third = Symbol::new(&fixture.env, "BC_LA3");
+
+    // The first batch harvests BC_LA
+    let first = fixture.controller.harvest_all(&fixture.admin, &1);
+    assert_eq!(first.harvested, 50_0000000);
+
+    // A pair registered ahead of the walk's position and the removal of the harvested pair
+    // would shift a positional cursor
+    let (_, third_pool) = fixture.setup_blend_adapter_as(&third);
+    fixture.controller.deposit_collateral(&third, &fixture.user1, &fixture.usdc_token_id, &100_0000000, &None);
+    third_pool.add_yield(&fixture.usdc_token_id, &5_0000000);
+    fixture.adapter_registry.remove_support_for_asset(&SupportedYieldType::Lending.id(), &blend, &fixture.usdc_token_id);
+    let third_yield = fixture.controller.get_yield(&third, &fixture.usdc_token_id);
+    assert!(third_yield > 0);
+
+    // BC_LA2 and BC_LA3 are each harvested once
+    let second = fixture.controller.harvest_all(&fixture.admin, &10);
+    assert_eq!(second.processed, 2);
+    assert!(second.complete);
+    assert_eq!(second.harvested, 20_0000000 + third_yield);
+    assert_eq!(second.distributed, 70_0000000 + third_yield);
+    assert_eq!(fixture.controller.get_yield(&other, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_yield(&third, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+fn test_harvest_all_skips_pending_harvest() {
+    let fixture = TestFixture::create();
+    let (blend, _) = setup_two_protocols_with_yield(&fixture);
+
+    fixture.controller.harvest_yield(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    let result = fixture.controller.harvest_all(&fixture.admin, &10);
+    assert_eq!(result.processed, 2);
+    assert_eq!(result.distributed, 20_0000000);
+    assert!(fixture.controller.get_pending_harvest(&blend, &fixture.usdc_token_id).is_some());
+}
+
+#[test]
+fn test_harvest_all_without_yield_advances_epoch() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+    let epoch = fixture.yield_distributor.get_current_epoch();
+
+    let result = fixture.controller.harvest_all(&fixture.admin, &10);
+    assert_eq!(result, HarvestAllResult { processed: 1, harvested: 0, complete: true, distributed: 0 });
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch + 1);
+}
+
+#[test]
+fn test_harvest_without_yield_by_anyone_keeps_epoch() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+    let caller = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+    let epoch = fixture.yield_distributor.get_current_epoch();
+
+    // Without the keeper role a zero-yield pair can't close the epoch for every other pair
+    assert_eq!(fixture.controller.harvest_yield(&caller, &protocol, &fixture.usdc_token_id), 0);
+    let result = fixture.controller.claim_yield(&caller, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::None, amount: 0 });
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch);
+    assert!(fixture.yield_distributor.is_distribution_available());
+
+    // A keeper role holder still advances it
+    let keeper = Address::generate(&fixture.env);
+    fixture.controller.grant_role(&Role::Keeper, &keeper);
+    fixture.controller.harvest_yield(&keeper, &protocol, &fixture.usdc_token_id);
+    assert_eq!(fixture.yield_distributor.get_current_epoch(), epoch + 1);
+}
+
+#[test]
+fn test_keeper_harvest_all_pays_bounty() {
+    let fixture = TestFixture::create();
+    setup_two_protocols_with_yield(&fixture);
+    let keeper = Address::generate(&fixture.env);
+    fixture.controller.set_keeper_bounty(&1000);
+
+    let result = fixture.controller.harvest_all(&keeper, &10);
+    assert_eq!(result.distributed, 63_0000000);
+    assert_eq!(fixture.cusd_client().balance(&keeper), 7_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1001)")]
+fn test_harvest_all_before_distribution_available() {
+    let fixture = TestFixture::create();
+    setup_two_protocols_with_yield(&fixture);
+
+    fixture.controller.harvest_all(&fixture.admin, &10);
+    fixture.controller.harvest_all(&fixture.admin, &10);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_harvest_all_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.harvest_all(&fixture.admin, &10);
+}
+
+// ============================================================================
+// Idle balance tests
+// ============================================================================
+
+/// Harvests 50 USDC of yield from Blend and cancels before it is recompounded
+fn setup_cancelled_harvest(fixture: &TestFixture) -> Symbol {
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    fixture.controller.cancel_harvest(&protocol, &fixture.usdc_token_id);
+
+    protocol
+}
+
+#[test]
+fn test_cancel_harvested_moves_yield_to_idle() {
+    let fixture = TestFixture::create();
+    let protocol = setup_cancelled_harvest(&fixture);
+
+    assert!(fixture.controller.get_pending_harvest(&protocol, &fixture.usdc_token_id).is_none());
+    assert_eq!(fixture.controller.get_idle_balance(&fixture.usdc_token_id), 50_0000000);
+}
+
+#[test]
+fn test_cancel_recompounded_leaves_idle_balance() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    fixture.controller.recompound_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    fixture.controller.cancel_harvest(&protocol, &fixture.usdc_token_id);
+
+    assert_eq!(fixture.controller.get_idle_balance(&fixture.usdc_token_id), 0);
+}
+
+#[test]
+fn test_redeposit_idle() {
+    let fixture = TestFixture::create();
+    let protocol = setup_cancelled_harvest(&fixture);
+
+    let deposited = fixture.controller.redeposit_idle(&protocol, &fixture.usdc_token_id, &30_0000000);
+    assert_eq!(deposited, 30_0000000);
+    assert_eq!(fixture.controller.get_idle_balance(&fixture.usdc_token_id), 20_0000000);
+
+    fixture.controller.redeposit_idle(&protocol, &fixture.usdc_token_id, &20_0000000);
+    assert_eq!(fixture.controller.get_idle_balance(&fixture.usdc_token_id), 0);
+
+    // The cancelled yield is back in the protocol and can be distributed by a new claim
+    let available = fixture.controller.get_yield(&protocol, &fixture.usdc_token_id);
+    assert!(available > 0);
+    let result = fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(result, ClaimYieldResult { stage: HarvestState::Distributed, amount: available });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1013)")]
+fn test_redeposit_idle_exceeds_balance() {
+    let fixture = TestFixture::create();
+    let protocol = setup_cancelled_harvest(&fixture);
+
+    fixture.controller.redeposit_idle(&protocol, &fixture.usdc_token_id, &50_0000001);
+}
+
+#[test]
+fn test_sweep_idle() {
+    let fixture = TestFixture::create();
+    setup_cancelled_harvest(&fixture);
+    let recipient = Address::generate(&fixture.env);
+
+    // The mock pool does not move tokens, so fund the controller with the harvested yield
+    StellarAssetClient::new(&fixture.env, &fixture.usdc_token_id).mint(&fixture.controller.address, &50_0000000);
+
+    fixture.controller.sweep_idle(&fixture.usdc_token_id, &recipient, &50_0000000);
+    assert_eq!(fixture.usdc_client().balance(&recipient), 50_0000000);
+    assert_eq!(fixture.controller.get_idle_balance(&fixture.usdc_token_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1013)")]
+fn test_sweep_idle_exceeds_balance() {
+    let fixture = TestFixture::create();
+    setup_cancelled_harvest(&fixture);
+    let recipient = Address::generate(&fixture.env);
+
+    fixture.controller.sweep_idle(&fixture.usdc_token_id, &recipient, &50_0000001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_sweep_idle_unauthorized() {
+    let fixture = TestFixture::create();
+    setup_cancelled_harvest(&fixture);
+    let recipient = Address::generate(&fixture.env);
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.sweep_idle(&fixture.usdc_token_id, &recipient, &10_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_redeposit_idle_unauthorized() {
+    let fixture = TestFixture::create();
+    let protocol = setup_cancelled_harvest(&fixture);
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.redeposit_idle(&protocol, &fixture.usdc_token_id, &10_0000000);
+}
+
+// ============================================================================
+// Allocation Tests
+// ============================================================================
+
+/// Registers Blend as BC_LA and a second Blend pool as BC_LA2, both supporting USDC
+fn setup_two_protocols(fixture: &TestFixture) -> (Symbol, PoolContractClient<'static>, Symbol, PoolContractClient<'static>) {
+    let blend = SupportedAdapter::BlendCapital.id();
+    let other = Symbol::new(&fixture.env, "BC_LA2");
+    let (_, blend_pool) = fixture.setup_blend_adapter();
+    let (_, other_pool) = fixture.setup_blend_adapter_as(&other);
+    (blend, blend_pool, other, other_pool)
+}
+
+#[test]
+fn test_deposit_auto_splits_equally_without_allocation() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    let deposited = fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    assert_eq!(deposited, 1000_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 500_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 500_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1000_0000000);
+}
+
+#[test]
+fn test_deposit_auto_follows_weights() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    let weights = map![&fixture.env, (blend.clone(), 3u32), (other.clone(), 1u32)];
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::Weighted, &weights);
+    assert_eq!(
+        fixture.controller.get_allocation(&fixture.usdc_token_id),
+        Some(AllocationConfig { mode: AllocationMode::Weighted, weights })
+    );
+
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 750_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 250_0000000);
+}
+
+#[test]
+fn test_deposit_auto_gives_remainder_to_largest_weight() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    let weights = map![&fixture.env, (blend.clone(), 1u32), (other.clone(), 2u32)];
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::Weighted, &weights);
+
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &100, &None);
+
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 33);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 67);
+}
+
+#[test]
+fn test_deposit_auto_highest_apy() {
+    let fixture = TestFixture::create();
+    let low = fixture.setup_fixed_apy_adapter("LOW_LA", 300);
+    let high = fixture.setup_fixed_apy_adapter("HIGH_LA", 500);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::HighestApy, &map![&fixture.env]);
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &low, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &high, &fixture.usdc_token_id), 1000_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1011)")]
+fn test_deposit_auto_checks_total_min_out() {
+    let fixture = TestFixture::create();
+    setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &Some(1000_0000001));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1000)")]
+fn test_deposit_auto_unsupported_asset() {
+    let fixture = TestFixture::create();
+    setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.cusd_token_id, &1000_0000000, &None);
+}
+
+#[test]
+fn test_withdraw_auto_follows_split() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let withdrawn = fixture.controller.withdraw_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &400_0000000, &None);
+
+    assert_eq!(withdrawn, 400_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 300_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 300_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 600_0000000);
+}
+
+#[test]
+fn test_withdraw_auto_spills_into_other_positions() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &900_0000000, &None);
+    fixture.controller.deposit_collateral(&other, &fixture.user1, &fixture.usdc_token_id, &100_0000000, &None);
+
+    // The equal split asks 300 of BC_LA2, which only holds 100
+    fixture.controller.withdraw_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &600_0000000, &None);
+
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 400_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1006)")]
+fn test_withdraw_auto_insufficient_position() {
+    let fixture = TestFixture::create();
+    setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.withdraw_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000001, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1014)")]
+fn test_set_allocation_rejects_zero_weights() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    let weights = map![&fixture.env, (blend, 0u32), (other, 0u32)];
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::Weighted, &weights);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1014)")]
+fn test_set_allocation_rejects_unsupported_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    let weights = map![&fixture.env, (blend, 1u32), (Symbol::new(&fixture.env, "NOPE"), 1u32)];
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::Weighted, &weights);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_allocation_unauthorized() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+    fixture.env.mock_auths(&[]);
+
+    let weights = map![&fixture.env, (blend, 1u32)];
+    fixture.controller.set_allocation(&fixture.usdc_token_id, &AllocationMode::Weighted, &weights);
+}
+
+// ============================================================================
+// Rebalance Tests
+// ============================================================================
+
+/// Balance the controller holds in a protocol's adapter
+fn adapter_balance(fixture: &TestFixture, protocol: &Symbol) -> i128 {
+    let adapter_id = fixture.adapter_registry.get_adapter(&SupportedYieldType::Lending.id(), protocol);
+    BlendCapitalAdapterClient::new(&fixture.env, &adapter_id).get_balance(&fixture.controller.address, &fixture.usdc_token_id)
+}
+
+#[test]
+fn test_rebalance_moves_principal() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let supply = fixture.cusd_manager.cusd_total_supply();
+
+    let moved = fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &600_0000000);
+
+    assert_eq!(moved, 600_0000000);
+    assert_eq!(adapter_balance(&fixture, &blend), 400_0000000);
+    assert_eq!(adapter_balance(&fixture, &other), 600_0000000);
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), supply);
+    assert_eq!(fixture.controller.get_yield(&blend, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_yield(&other, &fixture.usdc_token_id), 0);
+    // Users keep their positions in the source protocol
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 1000_0000000);
+}
+
+#[test]
+fn test_rebalance_keeps_accrued_yield() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    let accrued = fixture.controller.get_yield(&blend, &fixture.usdc_token_id);
+
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &500_0000000);
+
+    assert_eq!(fixture.controller.get_yield(&blend, &fixture.usdc_token_id), accrued);
+    assert_eq!(fixture.controller.get_yield(&other, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+fn test_withdraw_after_rebalance() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &200_0000000, &None);
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &1000_0000000);
+
+    // All of BC_LA's principal now sits in BC_LA2
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &false);
+
+    assert_eq!(withdrawn, 1000_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 0);
+    assert_eq!(adapter_balance(&fixture, &other), 200_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 0);
+
+    fixture.controller.withdraw_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &200_0000000, &None, &false);
+    assert_eq!(adapter_balance(&fixture, &other), 0);
+}
+
+#[test]
+fn test_withdraw_after_rebalance_into_exit_only_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, other_pool) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &200_0000000, &None);
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &600_0000000);
+    other_pool.set_liquidity(&fixture.usdc_token_id, &Some(800_0000000));
+
+    // BC_LA2's recovered balance holds its own users' principal and the 600 moved in from BC_LA
+    let recovered = fixture.controller.emergency_withdraw_all(&fixture.admin, &other, &fixture.usdc_token_id);
+    assert_eq!(recovered, 800_0000000);
+    // The mock pool does not move tokens, so fund the controller with the recovered principal
+    StellarAssetClient::new(&fixture.env, &fixture.usdc_token_id).mint(&fixture.controller.address, &800_0000000);
+
+    // BC_LA2's users can only draw their own share of it
+    assert!(fixture.controller
+        .try_withdraw_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &200_0000001, &None, &false)
+        .is_err());
+    let withdrawn = fixture.controller.withdraw_collateral(&other, &fixture.user2, &fixture.usdc_token_id, &200_0000000, &None, &false);
+    assert_eq!(withdrawn, 200_0000000);
+    assert_eq!(fixture.controller.get_recovered_balance(&other, &fixture.usdc_token_id), 600_0000000);
+
+    // BC_LA's rebalanced principal is paid from the recovered balance, the rest from its own adapter
+    let usdc_before = fixture.usdc_client().balance(&fixture.user1);
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &false);
+    assert_eq!(withdrawn, 1000_0000000);
+    assert_eq!(fixture.usdc_client().balance(&fixture.user1), usdc_before + 600_0000000);
+    assert_eq!(fixture.controller.get_recovered_balance(&other, &fixture.usdc_token_id), 0);
+    assert_eq!(adapter_balance(&fixture, &blend), 0);
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), 0);
+}
+
+#[test]
+fn test_withdraw_after_rebalance_out_of_exit_only_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &600_0000000);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(400_0000000));
+    let recovered = fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+    assert_eq!(recovered, 400_0000000);
+    StellarAssetClient::new(&fixture.env, &fixture.usdc_token_id).mint(&fixture.controller.address, &400_0000000);
+
+    // The principal moved out before the exit is still in BC_LA2, not in the recovered balance
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &false);
+    assert_eq!(withdrawn, 1000_0000000);
+    assert_eq!(adapter_balance(&fixture, &other), 0);
+    assert_eq!(fixture.controller.get_recovered_balance(&blend, &fixture.usdc_token_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1015)")]
+fn test_rebalance_cannot_move_yield() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &1000_0000001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1015)")]
+fn test_rebalance_same_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    fixture.controller.rebalance(&blend, &blend, &fixture.usdc_token_id, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1003)")]
+fn test_rebalance_blocked_by_pending_harvest() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    fixture.controller.harvest_yield(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_rebalance_unauthorized() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &100_0000000);
+}
+
+// ============================================================================
+// Deposit Cap Tests
+// ============================================================================
+
+#[test]
+fn test_protocol_cap_capacity() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    assert_eq!(fixture.controller.get_protocol_capacity(&blend, &fixture.usdc_token_id), None);
+
+    fixture.controller.set_protocol_cap(&blend, &fixture.usdc_token_id, &Some(1000_0000000));
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &600_0000000, &None);
+
+    assert_eq!(fixture.controller.get_protocol_cap(&blend, &fixture.usdc_token_id), Some(1000_0000000));
+    assert_eq!(fixture.controller.get_protocol_capacity(&blend, &fixture.usdc_token_id), Some(400_0000000));
+    assert_eq!(fixture.controller.get_protocol_capacity(&other, &fixture.usdc_token_id), None);
+
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000, &None);
+    assert_eq!(fixture.controller.get_protocol_capacity(&blend, &fixture.usdc_token_id), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1016)")]
+fn test_protocol_cap_exceeded() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_protocol_cap(&blend, &fixture.usdc_token_id, &Some(1000_0000000));
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &600_0000000, &None);
+    fixture.controller.deposit_collateral(&blend, &fixture.user2, &fixture.usdc_token_id, &400_0000001, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1016)")]
+fn test_asset_cap_spans_protocols() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_asset_cap(&fixture.usdc_token_id, &Some(1000_0000000));
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &700_0000000, &None);
+    assert_eq!(fixture.controller.get_asset_capacity(&fixture.usdc_token_id), Some(300_0000000));
+
+    fixture.controller.deposit_collateral(&other, &fixture.user1, &fixture.usdc_token_id, &300_0000001, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1016)")]
+fn test_cusd_supply_cap_exceeded() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_cusd_supply_cap(&Some(500_0000000));
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None);
+    assert_eq!(fixture.controller.get_cusd_capacity(), Some(0));
+
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1, &None);
+}
+
+#[test]
+fn test_removing_cap_allows_deposits() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.set_cusd_supply_cap(&Some(0));
+    fixture.controller.set_cusd_supply_cap(&None);
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None);
+
+    assert_eq!(fixture.controller.get_cusd_supply_cap(), None);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 500_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1016)")]
+fn test_rebalance_respects_protocol_cap() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.set_protocol_cap(&other, &fixture.usdc_token_id, &Some(500_0000000));
+
+    fixture.controller.rebalance(&blend, &other, &fixture.usdc_token_id, &600_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1017)")]
+fn test_negative_cap() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.set_asset_cap(&fixture.usdc_token_id, &Some(-1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_cap_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.set_cusd_supply_cap(&Some(1000_0000000));
+}
+
+// ============================================================================
+// Redemption Queue Tests
+// ============================================================================
+
+#[test]
+fn test_request_redemption_escrows_cusd() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let id = fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000);
+
+    assert_eq!(id, 0);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 600_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.controller.address), 400_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 600_0000000);
+    let ticket = fixture.controller.get_redemption(&id).unwrap();
+    assert_eq!(ticket.amount, 400_0000000);
+    assert_eq!(ticket.escrow, 400_0000000);
+}
+
+#[test]
+fn test_process_queue_waits_for_liquidity() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(0));
+    let id = fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000);
+    let supply = fixture.cusd_manager.cusd_total_supply();
+
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &10), 0);
+    assert!(fixture.controller.get_redemption(&id).is_some());
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), supply);
+
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &None);
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &10), 1);
+
+    assert_eq!(fixture.controller.get_redemption(&id), None);
+    assert_eq!(fixture.cusd_manager.cusd_total_supply(), supply - 400_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.controller.address), 0);
+    assert_eq!(adapter_balance(&fixture, &blend), 600_0000000);
+    assert_eq!(fixture.controller.get_redemption_queue(&blend, &fixture.usdc_token_id).head, 1);
+}
+
+#[test]
+fn test_process_queue_is_first_in_first_out() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&blend, &fixture.user2, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &500_0000000);
+    fixture.controller.request_redemption(&blend, &fixture.user2, &fixture.usdc_token_id, &200_0000000);
+
+    // The second ticket would fit, but it cannot jump the queue
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &10), 0);
+    assert_eq!(fixture.controller.get_redemption_queue(&blend, &fixture.usdc_token_id).head, 0);
+
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(700_0000000));
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &1), 1);
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &1), 1);
+    assert_eq!(fixture.controller.get_redemption_queue(&blend, &fixture.usdc_token_id), RedemptionQueue { head: 2, tail: 2 });
+}
+
+#[test]
+fn test_process_queue_is_per_protocol_and_asset() {
+    let fixture = TestFixture::create();
+    let blend = SupportedAdapter::BlendCapital.id();
+    let (_, blend_pool) = fixture.setup_blend_adapter();
+    let other = Symbol::new(&fixture.env, "BC_LA2");
+    let eurc = fixture.env.register_stellar_asset_contract_v2(fixture.token_admin.clone()).address();
+    let (_, eurc_pool) = fixture.setup_blend_adapter_for(&other, &eurc);
+
+    fixture.env.mock_all_auths();
+    StellarAssetClient::new(&fixture.env, &eurc).mint(&fixture.user2, &1000_0000000);
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&other, &fixture.user2, &eurc, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(0));
+    eurc_pool.set_liquidity(&eurc, &Some(0));
+
+    // The USDC ticket is queued first and cannot be filled
+    let stuck = fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &500_0000000);
+    let eurc_ticket = fixture.controller.request_redemption(&other, &fixture.user2, &eurc, &300_0000000);
+    eurc_pool.set_liquidity(&eurc, &None);
+
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &10), 0);
+    assert_eq!(fixture.controller.process_queue(&other, &eurc, &10), 1);
+
+    assert_eq!(fixture.controller.get_redemption(&eurc_ticket), None);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user2, &other, &eurc), 700_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.controller.address), 500_0000000);
+    assert!(fixture.controller.get_redemption(&stuck).is_some());
+    assert_eq!(fixture.controller.get_redemption_queue(&blend, &fixture.usdc_token_id), RedemptionQueue { head: 0, tail: 1 });
+    assert_eq!(fixture.controller.get_redemption_queue(&other, &eurc), RedemptionQueue { head: 1, tail: 1 });
+}
+
+#[test]
+fn test_cancel_redemption_returns_escrow() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.deposit_collateral(&blend, &fixture.user2, &fixture.usdc_token_id, &1000_0000000, &None);
+    let cancelled = fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000);
+    let kept = fixture.controller.request_redemption(&blend, &fixture.user2, &fixture.usdc_token_id, &100_0000000);
+
+    fixture.controller.cancel_redemption(&cancelled);
+
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1000_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 1000_0000000);
+    assert_eq!(fixture.controller.get_redemption(&cancelled), None);
+
+    // The cancelled ticket is skipped
+    assert_eq!(fixture.controller.process_queue(&blend, &fixture.usdc_token_id, &10), 1);
+    assert_eq!(fixture.controller.get_redemption(&kept), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1018)")]
+fn test_cancel_redemption_not_found() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.cancel_redemption(&7);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_cancel_redemption_unauthorized() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    let id = fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000);
+    fixture.env.mock_auths(&[]);
+
+    fixture.controller.cancel_redemption(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1006)")]
+fn test_request_redemption_insufficient_position() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.request_redemption(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000001);
+}
+
+// ============================================================================
+// Partial Withdrawal Tests
+// ============================================================================
+
+#[test]
+fn test_partial_withdraw_limited_by_liquidity() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &true);
+
+    assert_eq!(withdrawn, 300_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 700_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 700_0000000);
+}
+
+#[test]
+fn test_partial_withdraw_without_liquidity() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(0));
+
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &true);
+
+    assert_eq!(withdrawn, 0);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1000_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 1000_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(WasmVm, InvalidAction)")]
+fn test_full_withdraw_fails_without_liquidity() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+
+    fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None, &false);
+}
+
+// ============================================================================
+// Emergency Exit Tests
+// ============================================================================
+
+#[test]
+fn test_emergency_withdraw_all() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(1000_0000000));
+
+    let recovered = fixture.controller.emergency_withdraw_all(&fixture.owner, &blend, &fixture.usdc_token_id);
+
+    assert_eq!(recovered, 1000_0000000);
+    assert!(fixture.controller.is_exit_only(&blend, &fixture.usdc_token_id));
+    assert_eq!(fixture.controller.get_recovered_balance(&blend, &fixture.usdc_token_id), 1000_0000000);
+    assert_eq!(adapter_balance(&fixture, &blend), 0);
+    // Positions and cUSD are untouched until users withdraw
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 1000_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 1000_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_emergency_withdraw_all_unauthorized() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.emergency_withdraw_all(&fixture.user1, &blend, &fixture.usdc_token_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1019)")]
+fn test_deposit_into_exit_only_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    fixture.controller.deposit_collateral(&blend, &fixture.user2, &fixture.usdc_token_id, &100_0000000, &None);
+}
+
+#[test]
+fn test_deposit_auto_skips_exit_only_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, other, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+    fixture.controller.deposit_collateral_auto(&fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 0);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &other, &fixture.usdc_token_id), 1000_0000000);
+}
+
+#[test]
+fn test_withdraw_from_exit_only_protocol_pays_recovered_balance() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(1000_0000000));
+    fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    // The mock pool does not move tokens, so fund the controller with the recovered principal
+    StellarAssetClient::new(&fixture.env, &fixture.usdc_token_id).mint(&fixture.controller.address, &1000_0000000);
+    let usdc_before = fixture.usdc_client().balance(&fixture.user1);
+
+    let withdrawn = fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &400_0000000, &None, &false);
+
+    assert_eq!(withdrawn, 400_0000000);
+    assert_eq!(fixture.usdc_client().balance(&fixture.user1), usdc_before + 400_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), 600_0000000);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &blend, &fixture.usdc_token_id), 600_0000000);
+    assert_eq!(fixture.controller.get_recovered_balance(&blend, &fixture.usdc_token_id), 600_0000000);
+}
+
+#[test]
+fn test_emergency_withdraw_all_limited_by_liquidity() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+
+    let recovered = fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+    assert_eq!(recovered, 300_0000000);
+    assert_eq!(adapter_balance(&fixture, &blend), 700_0000000);
+
+    // Calling again recovers the rest once the pool has liquidity
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(700_0000000));
+    let recovered = fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+    assert_eq!(recovered, 700_0000000);
+    assert_eq!(fixture.controller.get_recovered_balance(&blend, &fixture.usdc_token_id), 1000_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1020)")]
+fn test_withdraw_exceeds_recovered_balance() {
+    let fixture = TestFixture::create();
+    let (blend, blend_pool, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    blend_pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+    fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    fixture.controller.withdraw_collateral(&blend, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1019)")]
+fn test_harvest_exit_only_protocol() {
+    let fixture = TestFixture::create();
+    let (blend, _, _, _) = setup_two_protocols(&fixture);
+
+    fixture.env.mock_all_auths();
+    fixture.controller.emergency_withdraw_all(&fixture.admin, &blend, &fixture.usdc_token_id);
+
+    fixture.controller.harvest_yield(&fixture.admin, &blend, &fixture.usdc_token_id);
+}
+
+// ============================================================================
+// Pause Tests
+// ============================================================================
+
+#[test]
+fn test_pause_and_unpause() {
+    let fixture = TestFixture::create();
+    let pauser = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.controller.grant_role(&Role::Pauser, &pauser);
+    assert!(fixture.controller.has_role(&Role::Pauser, &pauser));
+
+    fixture.controller.pause(&pauser, &PauseFlag::Deposit);
+    assert!(fixture.controller.is_paused(&PauseFlag::Deposit));
+    assert!(!fixture.controller.is_paused(&PauseFlag::Withdraw));
+
+    fixture.controller.unpause(&PauseFlag::Deposit);
+    assert!(!fixture.controller.is_paused(&PauseFlag::Deposit));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1021)")]
+fn test_deposit_paused() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Deposit);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1021)")]
+fn test_deposit_mint_paused() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Mint);
+
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1021)")]
+fn test_withdraw_paused() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Withdraw);
+
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &500_0000000, &None, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1021)")]
+fn test_claim_yield_harvest_paused() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Harvest);
+
+    fixture.controller.claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+}
+
+#[test]
+fn test_claim_yield_distribution_paused_keeps_harvest_staged() {
+    let fixture = TestFixture::create();
+    let (_, pool) = fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+    pool.add_yield(&fixture.usdc_token_id, &50_0000000);
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Distribution);
+
+    let current_time = fixture.env.ledger().timestamp();
+    fixture.env.ledger().set_timestamp(current_time + 86400 + 10);
+
+    let result = fixture.controller.try_claim_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert!(result.is_err());
+
+    // Harvesting stages still run; only the cUSD distribution waits for the unpause
+    fixture.controller.harvest_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    fixture.controller.recompound_yield(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert!(fixture.controller.try_finalize_distribution(&fixture.admin, &protocol, &fixture.usdc_token_id).is_err());
+
+    fixture.controller.unpause(&PauseFlag::Distribution);
+    let distributed = fixture.controller.finalize_distribution(&fixture.admin, &protocol, &fixture.usdc_token_id);
+    assert_eq!(distributed, 50_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1400)")]
+fn test_pause_without_pauser_role() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Deposit);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_unpause_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+    fixture.controller.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.controller.pause(&fixture.admin, &PauseFlag::Deposit);
+
+    fixture.env.mock_auths(&[]);
+    fixture.controller.unpause(&PauseFlag::Deposit);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.controller.propose_owner(&new_owner);
+    assert_eq!(fixture.controller.get_pending_owner(), Some(new_owner.clone()));
+    assert!(fixture.controller.has_role(&Role::Owner, &fixture.owner));
+
+    fixture.controller.accept_owner();
+    assert!(fixture.controller.has_role(&Role::Owner, &new_owner));
+    assert!(!fixture.controller.has_role(&Role::Owner, &fixture.owner));
+}
+
+/// Lay the contract's storage out as a version 1 deployment: owner and admin under the legacy
+/// keys and no schema version
+fn simulate_v1_storage(env: &Env, contract: &Address) -> (Address, Address) {
+    let legacy_owner = Address::generate(env);
+    let legacy_admin = Address::generate(env);
+    env.as_contract(contract, || {
+        env.storage().instance().set(&LegacyDataKey::Owner, &legacy_owner);
+        env.storage().instance().set(&LegacyDataKey::Admin, &legacy_admin);
+        versioning::write_version(env, 1);
+    });
+    (legacy_owner, legacy_admin)
+}
+
+#[test]
+fn test_withdraw_collateral_deposited_before_positions() {
+    let fixture = TestFixture::create();
+    fixture.setup_blend_adapter();
+    let protocol = SupportedAdapter::BlendCapital.id();
+
+    fixture.env.mock_all_auths();
+    fixture.controller.deposit_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &1000_0000000, &None);
+
+    // Version 1 pooled the deposit under the controller without recording a position for it
+    fixture.env.as_contract(&fixture.controller.address, || {
+        let storage = fixture.env.storage().persistent();
+        storage.remove(&DataKey::UserPosition(fixture.user1.clone(), protocol.clone(), fixture.usdc_token_id.clone()));
+        storage.remove(&DataKey::UserPositions(fixture.user1.clone()));
+        storage.remove(&DataKey::UserDebt(fixture.user1.clone(), protocol.clone(), fixture.usdc_token_id.clone()));
+    });
+    simulate_v1_storage(&fixture.env, &fixture.controller.address);
+    fixture.controller.migrate();
+    assert_eq!(fixture.controller.get_user_position(&fixture.user1, &protocol, &fixture.usdc_token_id), 0);
+
+    // The depositor still withdraws by burning cUSD for the collateral
+    let cusd_before = fixture.cusd_client().balance(&fixture.user1);
+    let withdrawn = fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &400_0000000, &None, &false);
+    assert_eq!(withdrawn, 400_0000000);
+    assert_eq!(fixture.cusd_client().balance(&fixture.user1), cusd_before - 400_0000000);
+
+    // Positions opened after the upgrade don't dip into the legacy collateral
+    fixture.controller.deposit_collateral(&protocol, &fixture.user2, &fixture.usdc_token_id, &100_0000000, &None);
+    let result = fixture.controller.try_withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &700_0000000, &None, &false);
+    assert!(result.is_err());
+    fixture.controller.withdraw_collateral(&protocol, &fixture.user1, &fixture.usdc_token_id, &600_0000000, &None, &false);
+    assert_eq!(fixture.controller.get_user_position(&fixture.user2, &protocol, &fixture.usdc_token_id), 100_0000000);
+}
+
+#[test]
+fn test_version_after_deploy() {
+    let fixture = TestFixture::create();
+
+    assert_eq!(fixture.controller.version(), 2);
+}
+
+#[test]
+fn test_migrate_v1_to_latest() {
+    let fixture = TestFixture::create();
+    let (legacy_owner, legacy_admin) = simulate_v1_storage(&fixture.env, &fixture.controller.address);
+    assert_eq!(fixture.controller.version(), 1);
+
+    fixture.env.mock_all_auths();
+    assert_eq!(fixture.controller.migrate(), 2);
+
+    assert_eq!(fixture.controller.version(), 2);
+    assert!(fixture.controller.has_role(&Role::Owner, &legacy_owner));
+    assert!(fixture.controller.has_role(&Role::Admin, &legacy_admin));
+    fixture.env.as_contract(&fixture.controller.address, || {
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Owner));
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Admin));
+    });
+
+    // Migrating again is a no-op
+    assert_eq!(fixture.controller.migrate(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_migrate_unauthorized() {
+    let fixture = TestFixture::create();
+    simulate_v1_storage(&fixture.env, &fixture.controller.address);
+
+    fixture.env.mock_auths(&[]);
+    fixture.controller.migrate();
+}
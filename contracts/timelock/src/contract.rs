@@ -0,0 +1,379 @@
+use soroban_sdk::{contract, contractimpl, contractmeta, panic_with_error, Address, BytesN, Env, IntoVal, Symbol, Val, Vec};
+use access_control::{error::AccessControlError, role::Role};
+use crate::error::TimelockError;
+use crate::events::TimelockEvents;
+use crate::storage_types::{Operation, GRACE_PERIOD};
+use crate::storage;
+use crate::migrations;
+
+contractmeta!(
+    key = "Description",
+    val = "Timelock for Coopstable admin and upgrade actions"
+);
+
+fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
+
+/// Calls that change the timelock's own delays or code must themselves wait out a delay
+fn require_self(e: &Env) { e.current_contract_address().require_auth(); }
+
+/// Selectors the timelock runs on itself from `execute`
+const SELF_CALLS: [&str; 3] = ["set_min_delay", "set_default_delay", "upgrade"];
+
+fn is_self_call(e: &Env, fn_name: &Symbol) -> bool {
+    SELF_CALLS.iter().any(|name| *fn_name == Symbol::new(e, name))
+}
+
+/// Run a queued call on the timelock itself. The host rejects a contract calling back into
+/// itself, so these are dispatched here instead of through `invoke_contract`
+fn execute_self(e: &Env, fn_name: &Symbol, args: Vec<Val>) -> Val {
+    if *fn_name == Symbol::new(e, "set_min_delay") {
+        apply_min_delay(e, args.get_unchecked(0).into_val(e), args.get_unchecked(1).into_val(e));
+    } else if *fn_name == Symbol::new(e, "set_default_delay") {
+        apply_default_delay(e, args.get_unchecked(0).into_val(e));
+    } else if *fn_name == Symbol::new(e, "upgrade") {
+        let new_wasm_hash: BytesN<32> = args.get_unchecked(0).into_val(e);
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    } else {
+        panic_with_error!(e, TimelockError::UnsupportedSelfCall);
+    }
+    ().into_val(e)
+}
+
+fn apply_min_delay(e: &Env, fn_name: Symbol, delay: u64) {
+    storage::write_min_delay(e, &fn_name, delay);
+    TimelockEvents::set_min_delay(e, fn_name, delay);
+}
+
+fn apply_default_delay(e: &Env, delay: u64) {
+    storage::write_default_delay(e, delay);
+    TimelockEvents::set_default_delay(e, delay);
+}
+
+pub trait TimelockTrait {
+    /// Initialize the Timelock contract
+    ///
+    /// ### Arguments
+    /// * `owner` - The address of the contract owner (sets delays and manages roles)
+    /// * `admin` - The address of the admin (queues and executes operations)
+    /// * `guardian` - The address allowed to cancel queued operations in an emergency
+    /// * `default_delay` - The minimum delay, in seconds, for selectors without their own delay
+    fn __constructor(e: Env, owner: Address, admin: Address, guardian: Address, default_delay: u64);
+
+    /// (Admin only) Queue a contract call to run once `eta` is reached
+    ///
+    /// Returns the id of the queued operation
+    ///
+    /// ### Arguments
+    /// * `target` - The contract to call
+    /// * `fn_name` - The function selector to call on the target
+    /// * `args` - The call arguments
+    /// * `eta` - The earliest timestamp the call can be executed
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If `eta` is sooner than the minimum delay for `fn_name`
+    /// If `target` is the timelock and `fn_name` is not one of its queueable calls
+    fn queue(e: &Env, target: Address, fn_name: Symbol, args: Vec<Val>, eta: u64) -> u64;
+
+    /// Execute a queued operation once its ETA is reached. Anyone can execute; the call itself
+    /// is authorized by the timelock.
+    ///
+    /// Returns the result of the call
+    ///
+    /// ### Arguments
+    /// * `id` - The operation id
+    ///
+    /// ### Panics
+    /// If the operation does not exist
+    /// If the ETA has not been reached
+    /// If the grace period after the ETA has passed
+    fn execute(e: &Env, id: u64) -> Val;
+
+    /// (Admin or guardian) Cancel a queued operation
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or an account holding the guardian role
+    /// * `id` - The operation id
+    ///
+    /// ### Panics
+    /// If the caller is neither the admin nor a guardian
+    /// If the operation does not exist
+    fn cancel(e: &Env, caller: Address, id: u64);
+
+    /// Fetch a queued operation, if it has not been executed or cancelled
+    ///
+    /// ### Arguments
+    /// * `id` - The operation id
+    fn get_operation(e: &Env, id: u64) -> Option<Operation>;
+
+    /// (Timelock only) Set the minimum delay for one function selector. Queue it on the timelock
+    /// itself so the change waits out the current delay
+    ///
+    /// ### Arguments
+    /// * `fn_name` - The function selector
+    /// * `delay` - The minimum delay in seconds
+    ///
+    /// ### Panics
+    /// If not called through `execute`
+    fn set_min_delay(e: &Env, fn_name: Symbol, delay: u64);
+
+    /// Fetch the minimum delay for a function selector, falling back to the default delay
+    ///
+    /// ### Arguments
+    /// * `fn_name` - The function selector
+    fn get_min_delay(e: &Env, fn_name: Symbol) -> u64;
+
+    /// (Timelock only) Set the minimum delay for selectors without their own delay. Queue it on the
+    /// timelock itself so the change waits out the current delay
+    ///
+    /// ### Arguments
+    /// * `delay` - The minimum delay in seconds
+    ///
+    /// ### Panics
+    /// If not called through `execute`
+    fn set_default_delay(e: &Env, delay: u64);
+
+    /// Fetch the minimum delay for selectors without their own delay
+    fn get_default_delay(e: &Env) -> u64;
+
+    /// (Owner only) Grant a role to an account. Granting the admin role replaces the current admin
+    ///
+    /// ### Arguments
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner role
+    fn grant_role(e: &Env, role: Role, account: Address);
+
+    /// (Owner only) Revoke a role from an account
+    ///
+    /// ### Arguments
+    /// * `role` - The role to revoke
+    /// * `account` - The account losing the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner or admin role
+    fn revoke_role(e: &Env, role: Role, account: Address);
+
+    /// Give up a role held by `account`
+    ///
+    /// ### Arguments
+    /// * `account` - The account giving up the role
+    /// * `role` - The role to give up
+    ///
+    /// ### Panics
+    /// If `account` does not authorize the call
+    /// If `role` is the owner or admin role
+    fn renounce_role(e: &Env, account: Address, role: Role);
+
+    /// Check whether an account holds a role
+    ///
+    /// ### Arguments
+    /// * `role` - The role to check
+    /// * `account` - The account to check
+    fn has_role(e: &Env, role: Role, account: Address) -> bool;
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Timelock only) Upgrade the contract to a new WASM bytecode. Queue it on the timelock itself
+    /// so the upgrade waits out its delay
+    ///
+    /// ### Arguments
+    /// * `new_wasm_hash` - The hash of the new WASM bytecode (must be uploaded first)
+    ///
+    /// ### Panics
+    /// If not called through `execute`
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
+}
+
+/// ### Timelock
+///
+/// Delays admin and upgrade calls on the Coopstable contracts it owns, giving members time to
+/// react before a queued change takes effect.
+#[contract]
+pub struct Timelock;
+
+#[contractimpl]
+impl TimelockTrait for Timelock {
+    fn __constructor(e: Env, owner: Address, admin: Address, guardian: Address, default_delay: u64) {
+        storage::write_owner(&e, owner);
+        storage::write_admin(&e, admin);
+        access_control::grant_role(&e, Role::Guardian, &guardian);
+        storage::write_default_delay(&e, default_delay);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
+    }
+
+    fn queue(e: &Env, target: Address, fn_name: Symbol, args: Vec<Val>, eta: u64) -> u64 {
+        storage::extend_instance(e);
+        access_control::role_holder(e, Role::Admin).unwrap().require_auth();
+
+        let min_delay = storage::read_min_delay(e, &fn_name);
+        if eta < e.ledger().timestamp() + min_delay {
+            panic_with_error!(e, TimelockError::DelayTooShort);
+        }
+        if target == e.current_contract_address() && !is_self_call(e, &fn_name) {
+            panic_with_error!(e, TimelockError::UnsupportedSelfCall);
+        }
+
+        let id = storage::next_operation_id(e);
+        let operation = Operation {
+            target: target.clone(),
+            fn_name: fn_name.clone(),
+            args: args.clone(),
+            eta,
+        };
+        storage::write_operation(e, id, &operation);
+        TimelockEvents::queue(e, id, target, fn_name, args, eta);
+
+        id
+    }
+
+    fn execute(e: &Env, id: u64) -> Val {
+        let Some(operation) = storage::read_operation(e, id) else {
+            panic_with_error!(e, TimelockError::OperationNotFound);
+        };
+
+        let now = e.ledger().timestamp();
+        if now < operation.eta {
+            panic_with_error!(e, TimelockError::OperationNotReady);
+        }
+        if now > operation.eta + GRACE_PERIOD {
+            panic_with_error!(e, TimelockError::OperationExpired);
+        }
+
+        // Remove before calling out so the operation can never run twice
+        storage::remove_operation(e, id);
+        let result: Val = if operation.target == e.current_contract_address() {
+            execute_self(e, &operation.fn_name, operation.args)
+        } else {
+            e.invoke_contract(&operation.target, &operation.fn_name, operation.args)
+        };
+        TimelockEvents::execute(e, id, operation.target, operation.fn_name);
+
+        result
+    }
+
+    fn cancel(e: &Env, caller: Address, id: u64) {
+        access_control::require_any_role(e, &[Role::Admin, Role::Guardian], &caller);
+
+        if storage::read_operation(e, id).is_none() {
+            panic_with_error!(e, TimelockError::OperationNotFound);
+        }
+
+        storage::remove_operation(e, id);
+        TimelockEvents::cancel(e, id, caller);
+    }
+
+    fn get_operation(e: &Env, id: u64) -> Option<Operation> {
+        storage::read_operation(e, id)
+    }
+
+    fn set_min_delay(e: &Env, fn_name: Symbol, delay: u64) {
+        require_self(e);
+        apply_min_delay(e, fn_name, delay);
+    }
+
+    fn get_min_delay(e: &Env, fn_name: Symbol) -> u64 {
+        storage::read_min_delay(e, &fn_name)
+    }
+
+    fn set_default_delay(e: &Env, delay: u64) {
+        require_self(e);
+        apply_default_delay(e, delay);
+    }
+
+    fn get_default_delay(e: &Env) -> u64 {
+        storage::read_default_delay(e)
+    }
+
+    fn grant_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        if role == Role::Owner {
+            panic_with_error!(e, AccessControlError::InvalidRole);
+        }
+        access_control::grant_role(e, role, &account);
+    }
+
+    fn revoke_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        access_control::revoke_role(e, role, &account);
+    }
+
+    fn renounce_role(e: &Env, account: Address, role: Role) {
+        access_control::renounce_role(e, role, &account);
+    }
+
+    fn has_role(e: &Env, role: Role, account: Address) -> bool {
+        access_control::has_role(e, role, &account)
+    }
+
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn upgrade(e: &Env, new_wasm_hash: BytesN<32>) {
+        require_self(e);
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    fn migrate(e: &Env) -> u32 {
+        require_owner(e);
+        migrations::migrate(e)
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
+}
@@ -0,0 +1,22 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the timelock contract. Common errors are codes that match up with the built-in
+/// contracts error reporting. Timelock specific errors start at 1500
+pub enum TimelockError {
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+    UnauthorizedError = 4,
+    NegativeAmountError = 8,
+    BalanceError = 10,
+    OverflowError = 12,
+
+    // Timelock Errors
+    OperationNotFound = 1500,
+    DelayTooShort = 1501,
+    OperationNotReady = 1502,
+    OperationExpired = 1503,
+    UnsupportedSelfCall = 1504,
+}
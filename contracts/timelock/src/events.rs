@@ -0,0 +1,30 @@
+use soroban_sdk::{Address, Env, Symbol, Val, Vec};
+
+pub struct TimelockEvents {}
+
+impl TimelockEvents {
+    pub fn queue(e: &Env, id: u64, target: Address, fn_name: Symbol, args: Vec<Val>, eta: u64) {
+        let topics = (Symbol::new(e, "queue"), id);
+        e.events().publish(topics, (target, fn_name, args, eta));
+    }
+
+    pub fn execute(e: &Env, id: u64, target: Address, fn_name: Symbol) {
+        let topics = (Symbol::new(e, "execute"), id);
+        e.events().publish(topics, (target, fn_name));
+    }
+
+    pub fn cancel(e: &Env, id: u64, caller: Address) {
+        let topics = (Symbol::new(e, "cancel"), id);
+        e.events().publish(topics, caller);
+    }
+
+    pub fn set_min_delay(e: &Env, fn_name: Symbol, delay: u64) {
+        let topics = (Symbol::new(e, "set_min_delay"), fn_name);
+        e.events().publish(topics, delay);
+    }
+
+    pub fn set_default_delay(e: &Env, delay: u64) {
+        let topics = (Symbol::new(e, "set_default_delay"),);
+        e.events().publish(topics, delay);
+    }
+}
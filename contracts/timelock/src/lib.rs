@@ -0,0 +1,8 @@
+#![no_std]
+pub mod contract;
+mod events;
+mod migrations;
+mod storage;
+mod storage_types;
+mod error;
+mod test;
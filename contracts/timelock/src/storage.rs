@@ -0,0 +1,74 @@
+use soroban_sdk::{Address, Env, Symbol};
+use access_control::role::Role;
+use crate::storage_types::{
+    DataKey,
+    Operation,
+    INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+    PERSISTENT_BUMP_AMOUNT,
+    PERSISTENT_LIFETIME_THRESHOLD,
+};
+
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn read_owner(e: &Env) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, Role::Owner).unwrap()
+}
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+pub fn write_admin(e: &Env, new_admin: Address) { access_control::grant_role(e, Role::Admin, &new_admin); }
+
+pub fn read_default_delay(e: &Env) -> u64 {
+    e.storage().instance().get(&DataKey::DefaultDelay).unwrap_or(0)
+}
+
+pub fn write_default_delay(e: &Env, delay: u64) {
+    e.storage().instance().set(&DataKey::DefaultDelay, &delay);
+}
+
+/// The delay for `fn_name`, falling back to the default delay
+pub fn read_min_delay(e: &Env, fn_name: &Symbol) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::MinDelay(fn_name.clone()))
+        .unwrap_or_else(|| read_default_delay(e))
+}
+
+pub fn write_min_delay(e: &Env, fn_name: &Symbol, delay: u64) {
+    e.storage().instance().set(&DataKey::MinDelay(fn_name.clone()), &delay);
+}
+
+pub fn next_operation_id(e: &Env) -> u64 {
+    let id: u64 = e.storage().instance().get(&DataKey::NextOperationId).unwrap_or(0);
+    e.storage().instance().set(&DataKey::NextOperationId, &(id + 1));
+    id
+}
+
+pub fn read_operation(e: &Env, id: u64) -> Option<Operation> {
+    let key = DataKey::Operation(id);
+    let operation = e.storage().persistent().get(&key);
+    if operation.is_some() {
+        extend_persistent(e, &key);
+    }
+    operation
+}
+
+pub fn write_operation(e: &Env, id: u64, operation: &Operation) {
+    let key = DataKey::Operation(id);
+    e.storage().persistent().set(&key, operation);
+    extend_persistent(e, &key);
+}
+
+pub fn remove_operation(e: &Env, id: u64) {
+    e.storage().persistent().remove(&DataKey::Operation(id));
+}
@@ -0,0 +1,29 @@
+use soroban_sdk::{contracttype, Address, Symbol, Val, Vec};
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// How long a queued operation stays executable after its ETA, in seconds
+pub(crate) const GRACE_PERIOD: u64 = 14 * 86400;
+
+/// A contract call waiting for its ETA
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Operation {
+    pub target: Address,
+    pub fn_name: Symbol,
+    pub args: Vec<Val>,
+    pub eta: u64, // Earliest execution timestamp
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    DefaultDelay,
+    MinDelay(Symbol),  // Minimum delay for one function selector
+    NextOperationId,
+    Operation(u64),
+}
@@ -0,0 +1,308 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+use access_control::role::Role;
+use yield_adapter_registry::contract::{YieldAdapterRegistry, YieldAdapterRegistryClient};
+
+use crate::contract::{Timelock, TimelockClient};
+
+const DEFAULT_DELAY: u64 = 2 * 86400;
+
+struct TestFixture {
+    env: Env,
+    timelock: TimelockClient<'static>,
+    registry: YieldAdapterRegistryClient<'static>,
+    owner: Address,
+    admin: Address,
+    guardian: Address,
+}
+
+impl TestFixture {
+    /// A timelock that owns a yield adapter registry
+    fn create() -> Self {
+        let env = Env::default();
+        env.ledger().set_timestamp(1_000_000);
+
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let guardian = Address::generate(&env);
+
+        let timelock_id = env.register(
+            Timelock,
+            (owner.clone(), admin.clone(), guardian.clone(), DEFAULT_DELAY),
+        );
+        let timelock = TimelockClient::new(&env, &timelock_id);
+
+        let registry_admin = Address::generate(&env);
+        let registry_id = env.register(YieldAdapterRegistry, (registry_admin, timelock_id.clone()));
+        let registry = YieldAdapterRegistryClient::new(&env, &registry_id);
+
+        Self {
+            env,
+            timelock,
+            registry,
+            owner,
+            admin,
+            guardian,
+        }
+    }
+
+    /// Queue `set_yield_adapter_admin(new_admin)` on the registry with the given ETA
+    fn queue_set_admin(&self, new_admin: &Address, eta: u64) -> u64 {
+        let args: Vec<Val> = vec![&self.env, new_admin.into_val(&self.env)];
+        self.timelock.queue(
+            &self.registry.address,
+            &Symbol::new(&self.env, "set_yield_adapter_admin"),
+            &args,
+            &eta,
+        )
+    }
+
+    /// Queue a call on the timelock itself and execute it once its delay has passed
+    fn execute_on_timelock(&self, fn_name: &str, args: Vec<Val>) {
+        let fn_name = Symbol::new(&self.env, fn_name);
+        let eta = self.now() + self.timelock.get_min_delay(&fn_name);
+        let id = self.timelock.queue(&self.timelock.address, &fn_name, &args, &eta);
+        self.jump_to(eta);
+        self.timelock.execute(&id);
+    }
+
+    fn now(&self) -> u64 {
+        self.env.ledger().timestamp()
+    }
+
+    fn jump_to(&self, timestamp: u64) {
+        self.env.ledger().set_timestamp(timestamp);
+    }
+}
+
+#[test]
+fn test_queue_and_execute() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let eta = fixture.now() + DEFAULT_DELAY;
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, eta);
+    assert_eq!(fixture.timelock.get_operation(&id).unwrap().eta, eta);
+
+    // Execution needs no signature: the registry sees the timelock, its owner, as the caller
+    fixture.jump_to(eta);
+    fixture.env.mock_auths(&[]);
+    fixture.timelock.execute(&id);
+
+    assert!(fixture.registry.has_role(&Role::Admin, &new_admin));
+    assert_eq!(fixture.timelock.get_operation(&id), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1502)")]
+fn test_execute_before_eta() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let eta = fixture.now() + DEFAULT_DELAY;
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, eta);
+
+    fixture.jump_to(eta - 1);
+    fixture.timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1503)")]
+fn test_execute_after_grace_period() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let eta = fixture.now() + DEFAULT_DELAY;
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, eta);
+
+    fixture.jump_to(eta + 14 * 86400 + 1);
+    fixture.timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1500)")]
+fn test_execute_twice() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let eta = fixture.now() + DEFAULT_DELAY;
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, eta);
+
+    fixture.jump_to(eta);
+    fixture.timelock.execute(&id);
+    fixture.timelock.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1501)")]
+fn test_queue_below_default_delay() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    fixture.queue_set_admin(&new_admin, fixture.now() + DEFAULT_DELAY - 1);
+}
+
+#[test]
+fn test_min_delay_per_selector() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let upgrade = Symbol::new(&fixture.env, "upgrade");
+    let set_admin = Symbol::new(&fixture.env, "set_yield_adapter_admin");
+
+    fixture.env.mock_all_auths();
+    fixture.execute_on_timelock(
+        "set_min_delay",
+        vec![&fixture.env, set_admin.into_val(&fixture.env), (7 * 86400u64).into_val(&fixture.env)],
+    );
+
+    assert_eq!(fixture.timelock.get_min_delay(&set_admin), 7 * 86400);
+    assert_eq!(fixture.timelock.get_min_delay(&upgrade), DEFAULT_DELAY);
+    assert!(fixture.timelock.try_queue(
+        &fixture.registry.address,
+        &set_admin,
+        &vec![&fixture.env, new_admin.into_val(&fixture.env)],
+        &(fixture.now() + DEFAULT_DELAY),
+    ).is_err());
+
+    fixture.queue_set_admin(&new_admin, fixture.now() + 7 * 86400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_queue_unauthorized() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+
+    fixture.env.mock_auths(&[]);
+    fixture.queue_set_admin(&new_admin, fixture.now() + DEFAULT_DELAY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1500)")]
+fn test_guardian_cancel() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let eta = fixture.now() + DEFAULT_DELAY;
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, eta);
+    fixture.timelock.cancel(&fixture.guardian, &id);
+    assert_eq!(fixture.timelock.get_operation(&id), None);
+
+    fixture.jump_to(eta);
+    fixture.timelock.execute(&id);
+}
+
+#[test]
+fn test_admin_cancel() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, fixture.now() + DEFAULT_DELAY);
+    fixture.timelock.cancel(&fixture.admin, &id);
+
+    assert_eq!(fixture.timelock.get_operation(&id), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1400)")]
+fn test_cancel_missing_role() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    let stranger = Address::generate(&fixture.env);
+
+    fixture.env.mock_all_auths();
+    let id = fixture.queue_set_admin(&new_admin, fixture.now() + DEFAULT_DELAY);
+    fixture.timelock.cancel(&stranger, &id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_min_delay_unauthorized() {
+    let fixture = TestFixture::create();
+
+    fixture.env.mock_auths(&[]);
+    fixture.timelock.set_min_delay(&Symbol::new(&fixture.env, "upgrade"), &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_min_delay_direct_owner_call() {
+    let fixture = TestFixture::create();
+    let upgrade = Symbol::new(&fixture.env, "upgrade");
+
+    // The owner alone cannot skip the delay; the change has to be queued on the timelock
+    fixture.env.mock_auths(&[MockAuth {
+        address: &fixture.owner,
+        invoke: &MockAuthInvoke {
+            contract: &fixture.timelock.address,
+            fn_name: "set_min_delay",
+            args: (upgrade.clone(), 0u64).into_val(&fixture.env),
+            sub_invokes: &[],
+        },
+    }]);
+    fixture.timelock.set_min_delay(&upgrade, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_default_delay_direct_owner_call() {
+    let fixture = TestFixture::create();
+
+    fixture.env.mock_auths(&[MockAuth {
+        address: &fixture.owner,
+        invoke: &MockAuthInvoke {
+            contract: &fixture.timelock.address,
+            fn_name: "set_default_delay",
+            args: (0u64,).into_val(&fixture.env),
+            sub_invokes: &[],
+        },
+    }]);
+    fixture.timelock.set_default_delay(&0);
+}
+
+#[test]
+fn test_set_default_delay_through_queue() {
+    let fixture = TestFixture::create();
+
+    fixture.env.mock_all_auths();
+    fixture.execute_on_timelock("set_default_delay", vec![&fixture.env, (86400u64).into_val(&fixture.env)]);
+    assert_eq!(fixture.timelock.get_default_delay(), 86400);
+
+    // The new delay applies to the next operation
+    let new_admin = Address::generate(&fixture.env);
+    fixture.queue_set_admin(&new_admin, fixture.now() + 86400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1504)")]
+fn test_queue_unsupported_self_call() {
+    let fixture = TestFixture::create();
+
+    fixture.env.mock_all_auths();
+    fixture.timelock.queue(
+        &fixture.timelock.address,
+        &Symbol::new(&fixture.env, "grant_role"),
+        &vec![&fixture.env],
+        &(fixture.now() + DEFAULT_DELAY),
+    );
+}
+
+#[test]
+fn test_version_and_migrate() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    assert_eq!(fixture.timelock.version(), 1);
+    assert_eq!(fixture.timelock.migrate(), 1);
+}
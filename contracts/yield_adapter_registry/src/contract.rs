@@ -1,8 +1,10 @@
 use crate::{
     events::YieldAdapterRegistryEvents,
     error::YieldAdapterRegistryError,
-    storage
+    storage,
+    migrations
 };
+use access_control::{error::AccessControlError, role::Role};
 use soroban_sdk::{contract, contractimpl, contractmeta, panic_with_error, Address, BytesN, Env, Symbol, Vec};
 
 contractmeta!(
@@ -29,6 +31,98 @@ pub trait YieldAdapterRegistryTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn set_yield_adapter_admin(e: &Env, new_admin: Address);
+
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Propose a new admin who takes over once they accept, as an alternative to setting it directly
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The proposed admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_admin(e: &Env, new_admin: Address);
+
+    /// (Proposed admin only) Accept a pending admin transfer
+    ///
+    /// ### Panics
+    /// If no admin transfer is pending
+    /// If the caller is not the proposed admin
+    fn accept_admin(e: &Env);
+
+    /// (Owner only) Cancel a pending admin transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no admin transfer is pending
+    fn cancel_admin_transfer(e: &Env);
+
+    /// Fetch the proposed admin, if an admin transfer is pending
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Grant a role to an account. Granting the admin role replaces the current admin
+    ///
+    /// ### Arguments
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner role
+    fn grant_role(e: &Env, role: Role, account: Address);
+
+    /// (Owner only) Revoke a role from an account
+    ///
+    /// ### Arguments
+    /// * `role` - The role to revoke
+    /// * `account` - The account losing the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner or admin role
+    fn revoke_role(e: &Env, role: Role, account: Address);
+
+    /// Give up a role held by `account`
+    ///
+    /// ### Arguments
+    /// * `account` - The account giving up the role
+    /// * `role` - The role to give up
+    ///
+    /// ### Panics
+    /// If `account` does not authorize the call
+    /// If `role` is the owner or admin role
+    fn renounce_role(e: &Env, account: Address, role: Role);
+
+    /// Check whether an account holds a role
+    ///
+    /// ### Arguments
+    /// * `role` - The role to check
+    /// * `account` - The account to check
+    fn has_role(e: &Env, role: Role, account: Address) -> bool;
     
     /// (Admin only) Register a new yield adapter for a specific protocol
     ///
@@ -69,6 +163,14 @@ pub trait YieldAdapterRegistryTrait {
     /// ### Arguments
     /// * `yield_type` - The type of yield generation
     fn get_adapters_with_assets(e: &Env, yield_type: Symbol) -> Vec<(Address, Vec<Address>)>;
+
+    /// Fetch all protocol identifiers with their supported assets for a specific yield type
+    ///
+    /// Returns a vector of tuples containing protocol identifiers and their supported assets
+    ///
+    /// ### Arguments
+    /// * `yield_type` - The type of yield generation
+    fn get_protocols_with_assets(e: &Env, yield_type: Symbol) -> Vec<(Symbol, Vec<Address>)>;
     
     /// (Admin only) Remove a registered adapter
     ///
@@ -133,6 +235,17 @@ pub trait YieldAdapterRegistryTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
 }
 
 /// ### YieldAdapterRegistry
@@ -147,13 +260,73 @@ impl YieldAdapterRegistryTrait for YieldAdapterRegistry {
     fn __constructor(e: Env, admin: Address, owner: Address) {
         storage::write_owner(&e, owner);
         storage::write_admin(&e, admin);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
     }
 
     fn set_yield_adapter_admin(e: &Env, new_admin: Address) {
         require_owner(e);
+        storage::write_admin(e, new_admin.clone());
         YieldAdapterRegistryEvents::set_admin(&e, new_admin);
     }
 
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn propose_admin(e: &Env, new_admin: Address) {
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Admin, &new_admin);
+    }
+
+    fn accept_admin(e: &Env) {
+        let new_admin = access_control::accept_transfer(e, Role::Admin);
+        YieldAdapterRegistryEvents::set_admin(e, new_admin);
+    }
+
+    fn cancel_admin_transfer(e: &Env) {
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Admin);
+    }
+
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Admin)
+    }
+
+    fn grant_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        if role == Role::Owner {
+            panic_with_error!(e, AccessControlError::InvalidRole);
+        }
+        access_control::grant_role(e, role, &account);
+    }
+
+    fn revoke_role(e: &Env, role: Role, account: Address) {
+        require_owner(e);
+        access_control::revoke_role(e, role, &account);
+    }
+
+    fn renounce_role(e: &Env, account: Address, role: Role) {
+        access_control::renounce_role(e, role, &account);
+    }
+
+    fn has_role(e: &Env, role: Role, account: Address) -> bool {
+        access_control::has_role(e, role, &account)
+    }
+
     fn register_adapter(
         e: &Env,
         yield_type: Symbol,
@@ -238,8 +411,24 @@ impl YieldAdapterRegistryTrait for YieldAdapterRegistry {
         storage::get_yield_adapters_with_assets(e, yield_type)
     }
 
+    fn get_protocols_with_assets(e: &Env, yield_type: Symbol) -> Vec<(Symbol, Vec<Address>)> {
+        storage::get_protocols_with_assets(e, yield_type)
+    }
+
     fn upgrade(e: &Env, new_wasm_hash: BytesN<32>) {
         require_owner(e);
         e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
+
+    fn migrate(e: &Env) -> u32 {
+        // Migrate before authorizing: an old layout may keep the owner where `require_owner` can't
+        // read it, and a failed authorization reverts the migration
+        let version = migrations::migrate(e);
+        require_owner(e);
+        version
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
 }
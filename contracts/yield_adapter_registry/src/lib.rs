@@ -1,6 +1,7 @@
 #![no_std]
 pub mod contract;
 mod events;
+mod migrations;
 mod storage;
 mod storage_types;
 mod error;
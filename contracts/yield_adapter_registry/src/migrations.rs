@@ -0,0 +1,40 @@
+use soroban_sdk::{contracttype, Address, Env};
+use access_control::role::Role;
+
+/// Schema version written by this build
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+/// Contracts deployed before versioning hold their data in the version 1 layout
+const UNVERSIONED: u32 = 1;
+
+/// Version 1 instance keys that later versions moved elsewhere
+#[derive(Clone)]
+#[contracttype]
+pub(crate) enum LegacyDataKey {
+    Owner,
+    Admin,
+}
+
+/// Bring stored data up to `SCHEMA_VERSION` and return the resulting version
+pub fn migrate(e: &Env) -> u32 {
+    versioning::migrate(e, UNVERSIONED, SCHEMA_VERSION, step)
+}
+
+pub fn version(e: &Env) -> u32 {
+    versioning::read_version(e, UNVERSIONED)
+}
+
+fn step(e: &Env, from: u32) {
+    if from == 1 {
+        v1_to_v2(e);
+    }
+}
+
+/// Version 2 keeps the owner and admin in the shared access control storage
+fn v1_to_v2(e: &Env) {
+    for (legacy, role) in [(LegacyDataKey::Owner, Role::Owner), (LegacyDataKey::Admin, Role::Admin)] {
+        if let Some(holder) = e.storage().instance().get::<_, Address>(&legacy) {
+            access_control::grant_role(e, role, &holder);
+            e.storage().instance().remove(&legacy);
+        }
+    }
+}
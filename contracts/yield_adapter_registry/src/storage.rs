@@ -2,8 +2,8 @@ use crate::storage_types::{
     YieldAdapterRegistryMap, 
     REGISTRY_BUMP_AMOUNT, 
     REGISTRY_LIFETIME_THRESHOLD,
-    DataKey,
 };
+use access_control::role::Role;
 use soroban_sdk::{Address, Env, Symbol, Vec};
 
 fn extend_instance(e: &Env) {
@@ -12,19 +12,14 @@ fn extend_instance(e: &Env) {
         .extend_ttl(REGISTRY_LIFETIME_THRESHOLD, REGISTRY_BUMP_AMOUNT);
 }
 
-pub fn read_admin(e: &Env) -> Address { read_address(e, &DataKey::Admin)}
-pub fn read_owner(e: &Env) -> Address { read_address(e, &DataKey::Owner)}
-pub fn write_admin(e: &Env, new_admin: Address) { write_address(e, &DataKey::Admin, &new_admin);}
-pub fn write_owner(e: &Env, new_owner: Address) { write_address(e, &DataKey::Owner, &new_owner);}
+pub fn read_admin(e: &Env) -> Address { read_role_holder(e, Role::Admin) }
+pub fn read_owner(e: &Env) -> Address { read_role_holder(e, Role::Owner) }
+pub fn write_admin(e: &Env, new_admin: Address) { access_control::grant_role(e, Role::Admin, &new_admin); }
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
 
-fn read_address(e: &Env, key: &DataKey) -> Address {
+fn read_role_holder(e: &Env, role: Role) -> Address {
     extend_instance(e);
-    e.storage().instance().get(key).unwrap()  
-}
-
-fn write_address(e: &Env, key: &DataKey, address: &Address) {
-    extend_instance(e);
-    e.storage().instance().set(key, address); 
+    access_control::role_holder(e, role).unwrap()
 }
 
 pub fn read_yield_adapter_registry(e: &Env, yield_type: Symbol) -> YieldAdapterRegistryMap {
@@ -108,3 +103,8 @@ pub fn get_yield_adapters_with_assets(e: &Env, yield_type: Symbol) -> Vec<(Addre
     registry_map.adapter_with_assets()
 }
 
+pub fn get_protocols_with_assets(e: &Env, yield_type: Symbol) -> Vec<(Symbol, Vec<Address>)> {
+    let registry_map = read_yield_adapter_registry(e, yield_type.clone());
+    registry_map.protocols_with_assets()
+}
+
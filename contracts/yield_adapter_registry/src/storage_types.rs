@@ -103,13 +103,28 @@ impl YieldAdapterRegistryMap {
 
         result
     }
-}
 
+    pub fn protocols_with_assets(&self) -> Vec<(Symbol, Vec<Address>)> {
+        let env = self.registry_map.env();
 
-#[derive(Clone)]
-#[contracttype]
-pub enum DataKey {
-    Owner,
-    Admin,
+        let mut result = Vec::new(env);
+
+        for protocol_id in self.registry_map.keys().iter() {
+            let mut supported_assets = Vec::new(env);
+
+            if let Some(asset_map) = self.supported_assets.get(protocol_id.clone()) {
+                for (asset_address, is_supported) in asset_map.iter() {
+                    if is_supported {
+                        supported_assets.push_back(asset_address);
+                    }
+                }
+            }
+
+            result.push_back((protocol_id, supported_assets));
+        }
+
+        result
+    }
 }
 
+
@@ -11,8 +11,10 @@ use soroban_sdk::{
 use crate::{
     contract::{YieldAdapterRegistry, YieldAdapterRegistryArgs, YieldAdapterRegistryClient},
     storage_types::YieldAdapterRegistryMap,
+    migrations::LegacyDataKey,
 };
 
+use access_control::role::Role;
 use yield_adapter::contract_types::{SupportedAdapter, SupportedYieldType};
 
 #[allow(dead_code)]
@@ -20,6 +22,7 @@ struct TestFixture {
     env: Env,
     registry: YieldAdapterRegistryClient<'static>,
     admin: Address,
+    owner: Address,
     user: Address,
 }
 
@@ -42,6 +45,7 @@ impl TestFixture {
             env,
             registry,
             admin,
+            owner,
             user,
         }
     }
@@ -134,6 +138,8 @@ fn test_set_yield_adapter_admin() {
         ),
     ];
     assert_eq!(published_event, expected_event);
+    assert!(fixture.registry.has_role(&Role::Admin, &new_admin));
+    assert!(!fixture.registry.has_role(&Role::Admin, &fixture.admin));
 
     // New admin should now be able to register adapters
     let (adapter_address, protocol) = fixture.create_adapter();
@@ -147,6 +153,69 @@ fn test_set_yield_adapter_admin() {
     assert!(fixture.verify_adapter_exists(protocol, &adapter_address));
 }
 
+#[test]
+fn test_grant_revoke_and_renounce_role() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.registry.grant_role(&Role::Keeper, &fixture.user);
+    assert!(fixture.registry.has_role(&Role::Keeper, &fixture.user));
+
+    fixture.registry.revoke_role(&Role::Keeper, &fixture.user);
+    assert!(!fixture.registry.has_role(&Role::Keeper, &fixture.user));
+
+    fixture.registry.grant_role(&Role::Keeper, &fixture.user);
+    fixture.registry.renounce_role(&fixture.user, &Role::Keeper);
+    assert!(!fixture.registry.has_role(&Role::Keeper, &fixture.user));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1401)")]
+fn test_grant_owner_role() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.registry.grant_role(&Role::Owner, &fixture.user);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1401)")]
+fn test_revoke_admin_role() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.registry.revoke_role(&Role::Admin, &fixture.admin);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.registry.propose_owner(&new_owner);
+    assert_eq!(fixture.registry.get_pending_owner(), Some(new_owner.clone()));
+    assert!(fixture.registry.has_role(&Role::Owner, &fixture.owner));
+
+    fixture.registry.accept_owner();
+    assert!(fixture.registry.has_role(&Role::Owner, &new_owner));
+    assert!(!fixture.registry.has_role(&Role::Owner, &fixture.owner));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1402)")]
+fn test_cancel_admin_transfer() {
+    let fixture = TestFixture::create();
+    let new_admin = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.registry.propose_admin(&new_admin);
+    fixture.registry.cancel_admin_transfer();
+    assert!(fixture.registry.has_role(&Role::Admin, &fixture.admin));
+
+    fixture.registry.accept_admin();
+}
+
 // Test adapter registration
 #[test]
 fn test_register_adapter() {
@@ -495,3 +564,83 @@ fn test_is_supported_asset() {
     );
     assert!(!is_supported_non_existent);
 }
+
+#[test]
+fn test_get_protocols_with_assets() {
+    let fixture = TestFixture::create();
+    let (adapter_address, protocol) = fixture.create_adapter();
+    let custom_adapter = Address::generate(&fixture.env);
+    let custom_protocol = SupportedAdapter::Custom(symbol_short!("SIMP"));
+    let asset1 = fixture.create_asset();
+    let asset2 = fixture.create_asset();
+
+    fixture.env.mock_all_auths();
+
+    fixture.registry.register_adapter(&SupportedYieldType::Lending.id(), &protocol.id(), &adapter_address);
+    fixture.registry.register_adapter(&SupportedYieldType::Lending.id(), &custom_protocol.id(), &custom_adapter);
+    fixture.registry.add_support_for_asset(&SupportedYieldType::Lending.id(), &protocol.id(), &asset1);
+    fixture.registry.add_support_for_asset(&SupportedYieldType::Lending.id(), &protocol.id(), &asset2);
+
+    let protocols = fixture.registry.get_protocols_with_assets(&SupportedYieldType::Lending.id());
+    assert_eq!(protocols.len(), 2);
+
+    let (blend_id, blend_assets) = protocols.iter().find(|(id, _)| *id == protocol.id()).unwrap();
+    assert_eq!(blend_id, protocol.id());
+    assert_eq!(blend_assets.len(), 2);
+    assert!(blend_assets.contains(&asset1));
+    assert!(blend_assets.contains(&asset2));
+
+    let (_, custom_assets) = protocols.iter().find(|(id, _)| *id == custom_protocol.id()).unwrap();
+    assert_eq!(custom_assets.len(), 0);
+}
+
+/// Lay the contract's storage out as a version 1 deployment: owner and admin under the legacy
+/// keys and no schema version
+fn simulate_v1_storage(env: &Env, contract: &Address) -> (Address, Address) {
+    let legacy_owner = Address::generate(env);
+    let legacy_admin = Address::generate(env);
+    env.as_contract(contract, || {
+        env.storage().instance().set(&LegacyDataKey::Owner, &legacy_owner);
+        env.storage().instance().set(&LegacyDataKey::Admin, &legacy_admin);
+        versioning::write_version(env, 1);
+    });
+    (legacy_owner, legacy_admin)
+}
+
+#[test]
+fn test_version_after_deploy() {
+    let fixture = TestFixture::create();
+
+    assert_eq!(fixture.registry.version(), 2);
+}
+
+#[test]
+fn test_migrate_v1_to_v2() {
+    let fixture = TestFixture::create();
+    let (legacy_owner, legacy_admin) = simulate_v1_storage(&fixture.env, &fixture.registry.address);
+    assert_eq!(fixture.registry.version(), 1);
+
+    fixture.env.mock_all_auths();
+    assert_eq!(fixture.registry.migrate(), 2);
+
+    assert_eq!(fixture.registry.version(), 2);
+    assert!(fixture.registry.has_role(&Role::Owner, &legacy_owner));
+    assert!(fixture.registry.has_role(&Role::Admin, &legacy_admin));
+    fixture.env.as_contract(&fixture.registry.address, || {
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Owner));
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Admin));
+    });
+
+    // Migrating again is a no-op
+    assert_eq!(fixture.registry.migrate(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_migrate_unauthorized() {
+    let fixture = TestFixture::create();
+    simulate_v1_storage(&fixture.env, &fixture.registry.address);
+
+    fixture.env.mock_auths(&[]);
+    fixture.registry.migrate();
+}
@@ -1,8 +1,9 @@
-use soroban_sdk::{vec, IntoVal, Symbol, contract, contractimpl, contractmeta, token::TokenClient, Address, BytesN, Env, Vec, panic_with_error};
+use soroban_sdk::{vec, IntoVal, Symbol, contract, contractimpl, contractmeta, token::TokenClient, Address, BytesN, Env, Map, Vec, panic_with_error};
+use access_control::{error::AccessControlError, role::Role};
 use crate::events::YieldDistributorEvents;
 use crate::error::YieldDistributorError;
-use crate::storage_types::Distribution;
-use crate::{storage, storage_types, utils};
+use crate::storage_types::{Distribution, PauseFlag};
+use crate::{storage, storage_types, utils, migrations};
 
 contractmeta!(
     key = "Description",
@@ -11,9 +12,54 @@ contractmeta!(
 
 fn require_admin(e: &Env) { storage::read_admin(e).require_auth(); }
 fn require_owner(e: &Env) { storage::read_owner(e).require_auth(); }
+fn require_member_manager(e: &Env, caller: &Address) {
+    access_control::require_any_role(e, &[Role::Admin, Role::MemberManager], caller);
+}
 
 fn require_yield_controller(e: &Env ) { storage::get_yield_controller(e).require_auth(); }
 
+/// Data a batched migration has only partly rewritten must not be changed until the migration finishes
+fn require_migrated(e: &Env) {
+    if migrations::version(e) != migrations::SCHEMA_VERSION {
+        panic_with_error!(e, YieldDistributorError::MigrationPending);
+    }
+}
+
+fn require_not_paused(e: &Env, flag: PauseFlag) {
+    if storage::is_paused(e, flag) {
+        panic_with_error!(e, YieldDistributorError::Paused);
+    }
+}
+
+fn transfer(e: &Env, token_client: &TokenClient, to: &Address, amount: i128) {
+    utils::authenticate_contract(
+        &e, 
+        token_client.address.clone(), 
+        Symbol::new(&e, "transfer"), 
+        vec![
+            e,
+            (&e.current_contract_address()).into_val(e),
+            to.into_val(e),
+            (&amount).into_val(e),
+        ]
+    );
+    token_client.transfer(&e.current_contract_address(), to, &amount);
+}
+
+/// Pay out a member's unclaimed balance, returning the amount claimed
+fn claim_member(e: &Env, token_client: &TokenClient, member: Address) -> i128 {
+    let amount = storage::take_unclaimed(e, &member);
+    if amount > 0 {
+        transfer(e, token_client, &member, amount);
+        YieldDistributorEvents::claim(e, token_client.address.clone(), member, amount);
+    }
+    amount
+}
+
+fn yield_token_client(e: &Env) -> Option<TokenClient> {
+    storage::read_yield_token(e).map(|token| TokenClient::new(e, &token))
+}
+
 pub trait YieldDistributorTrait {
     /// Initialize the Yield Distributor contract
     ///
@@ -46,28 +92,74 @@ pub trait YieldDistributorTrait {
     /// Fetch the address of the yield controller contract
     fn get_yield_controller(e: &Env) -> Address;
     
-    /// (Admin only) Add a member to receive yield distributions
+    /// (Admin or member manager) Add a member to receive yield distributions
     ///
     /// ### Arguments
+    /// * `caller` - The admin or an account holding the member manager role
     /// * `member` - The address of the member to add
     ///
     /// ### Panics
-    /// If the caller is not the admin
+    /// If the caller is neither the admin nor a member manager
     /// If the member already exists and is active
-    fn add_member(e: &Env, member: Address);
+    fn add_member(e: &Env, caller: Address, member: Address);
     
-    /// (Admin only) Remove a member from yield distributions
+    /// (Admin or member manager) Remove a member from yield distributions
     ///
     /// ### Arguments
+    /// * `caller` - The admin or an account holding the member manager role
     /// * `member` - The address of the member to remove
     ///
     /// ### Panics
-    /// If the caller is not the admin
+    /// If the caller is neither the admin nor a member manager
     /// If the member does not exist
-    fn remove_member(e: &Env, member: Address);
+    fn remove_member(e: &Env, caller: Address, member: Address);
     
-    /// Fetch the list of active members eligible for yield distribution
-    fn list_members(e: &Env) -> Vec<Address>;
+    /// Fetch a page of the active members eligible for yield distribution
+    ///
+    /// ### Arguments
+    /// * `offset` - The index of the first member to return
+    /// * `limit` - The maximum number of members to return
+    fn list_members(e: &Env, offset: u32, limit: u32) -> Vec<Address>;
+
+    /// Fetch the number of active members
+    fn get_member_count(e: &Env) -> u32;
+
+    /// Check whether an address is an active member
+    ///
+    /// ### Arguments
+    /// * `member` - The address to check
+    fn is_member(e: &Env, member: Address) -> bool;
+
+    /// Fetch when an active member joined, e.g. to check they were a member at a given time
+    ///
+    /// Returns `None` if the address is not an active member
+    ///
+    /// ### Arguments
+    /// * `member` - The address to check
+    fn get_member_joined_at(e: &Env, member: Address) -> Option<u64>;
+
+    /// (Admin or member manager) Set a member's weight. Each distribution splits the member share
+    /// in proportion to the weights; members start with a weight of 1, i.e. an equal split.
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or an account holding the member manager role
+    /// * `member` - The address of the member
+    /// * `weight` - The new weight, greater than zero
+    ///
+    /// ### Panics
+    /// If the caller is neither the admin nor a member manager
+    /// If the member does not exist
+    /// If the weight is zero
+    fn set_member_weight(e: &Env, caller: Address, member: Address, weight: u32);
+
+    /// Fetch a member's weight
+    ///
+    /// ### Arguments
+    /// * `member` - The address of the member
+    ///
+    /// ### Panics
+    /// If the member does not exist
+    fn get_member_weight(e: &Env, member: Address) -> u32;
 
     /// (Admin only) Set a new treasury address
     ///
@@ -89,11 +181,26 @@ pub trait YieldDistributorTrait {
     /// ### Panics
     /// If the caller is not the admin
     /// If share_bps exceeds 10000
+    /// If share_bps and the fee recipients' shares add up to more than 10000
     fn set_treasury_share(e: &Env, share_bps: u32);
     
     /// Fetch the treasury's share in basis points
     fn get_treasury_share(e: &Env) -> u32;
 
+    /// (Admin only) Replace the recipients paid alongside the treasury, e.g. an insurance or
+    /// development fund. Each recipient's share is taken from the yield before the member pool
+    ///
+    /// ### Arguments
+    /// * `recipients` - The share of each recipient in basis points; an empty map removes them all
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If the shares and the treasury share add up to more than 10000
+    fn set_fee_recipients(e: &Env, recipients: Map<Address, u32>);
+
+    /// Fetch the recipients paid alongside the treasury and their shares in basis points
+    fn get_fee_recipients(e: &Env) -> Map<Address, u32>;
+
     /// (Admin only) Set the distribution period
     ///
     /// ### Arguments
@@ -111,6 +218,17 @@ pub trait YieldDistributorTrait {
     
     /// Fetch the history of all distributions
     fn get_distribution_history(e: &Env) -> Vec<Distribution>;
+
+    /// Fetch the yield a member received in a processed epoch, from their weight when it was
+    /// distributed and the epoch's yield per unit of weight. Rounded down to a token unit, so the
+    /// amounts of one epoch can add up to slightly less than its member amount
+    ///
+    /// Returns 0 if the member was not active then or the epoch is not processed
+    ///
+    /// ### Arguments
+    /// * `epoch` - The epoch of the distribution
+    /// * `member` - The address of the member
+    fn get_member_distribution(e: &Env, epoch: u64, member: Address) -> i128;
     
     /// Fetch the timestamp of the next distribution
     fn get_next_distribution_time(e: &Env) -> u64;
@@ -124,7 +242,9 @@ pub trait YieldDistributorTrait {
     /// Fetch the current epoch number
     fn get_current_epoch(e: &Env) -> u64;
 
-    /// (Yield Controller only) Distribute yield to treasury and members
+    /// (Yield Controller only) Distribute yield to treasury, fee recipients and members. Every
+    /// share is credited to its recipient's unclaimed balance rather than transferred, so a
+    /// recipient that can't receive the token doesn't block the distribution
     ///
     /// Returns the total amount distributed
     ///
@@ -135,11 +255,49 @@ pub trait YieldDistributorTrait {
     /// ### Panics
     /// If the caller is not the yield controller
     /// If distribution is not yet available
-    /// If there are no active members
+    /// If distribution is paused
+    /// If `token` differs from the token of balances that are still unclaimed
     fn distribute_yield(e: &Env, token: Address, amount: i128) -> i128;
     
     /// Fetch the total amount of yield distributed across all epochs
     fn get_total_distributed(e: &Env) -> i128;
+
+    /// Transfer a member's unclaimed yield to the member. The treasury and fee recipients claim
+    /// their shares the same way. Anyone can claim on a recipient's behalf
+    ///
+    /// Returns the amount claimed
+    ///
+    /// ### Arguments
+    /// * `member` - The address of the member, treasury or fee recipient
+    ///
+    /// ### Panics
+    /// If distribution is paused
+    fn claim(e: &Env, member: Address) -> i128;
+
+    /// Transfer the unclaimed yield of several members. Anyone can claim on the members' behalf
+    ///
+    /// Returns the total amount claimed
+    ///
+    /// ### Arguments
+    /// * `members` - The addresses of the members
+    ///
+    /// ### Panics
+    /// If distribution is paused
+    fn claim_many(e: &Env, members: Vec<Address>) -> i128;
+
+    /// Fetch the yield credited to a member, the treasury or a fee recipient that has not been
+    /// claimed yet
+    ///
+    /// ### Arguments
+    /// * `member` - The address of the member, treasury or fee recipient
+    fn get_unclaimed(e: &Env, member: Address) -> i128;
+
+    /// Fetch the yield credited to all members, the treasury and fee recipients that has not been
+    /// claimed yet
+    fn get_total_unclaimed(e: &Env) -> i128;
+
+    /// Fetch the token unclaimed yield is paid in, if anything has been distributed
+    fn get_yield_token(e: &Env) -> Option<Address>;
     
     /// (Owner only) Set a new admin address
     ///
@@ -150,6 +308,123 @@ pub trait YieldDistributorTrait {
     /// If the caller is not the owner
     fn set_admin(e: &Env, new_admin: Address);
 
+    /// (Owner only) Propose a new owner. The current owner keeps control until the new owner accepts
+    ///
+    /// ### Arguments
+    /// * `new_owner` - The proposed owner address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_owner(e: &Env, new_owner: Address);
+
+    /// (Proposed owner only) Accept a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If no ownership transfer is pending
+    /// If the caller is not the proposed owner
+    fn accept_owner(e: &Env);
+
+    /// (Owner only) Cancel a pending ownership transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no ownership transfer is pending
+    fn cancel_ownership_transfer(e: &Env);
+
+    /// Fetch the proposed owner, if an ownership transfer is pending
+    fn get_pending_owner(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Propose a new admin who takes over once they accept, as an alternative to setting it directly
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The proposed admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn propose_admin(e: &Env, new_admin: Address);
+
+    /// (Proposed admin only) Accept a pending admin transfer
+    ///
+    /// ### Panics
+    /// If no admin transfer is pending
+    /// If the caller is not the proposed admin
+    fn accept_admin(e: &Env);
+
+    /// (Owner only) Cancel a pending admin transfer
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If no admin transfer is pending
+    fn cancel_admin_transfer(e: &Env);
+
+    /// Fetch the proposed admin, if an admin transfer is pending
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// (Owner only) Grant a role to an account. Granting the admin role replaces the current admin
+    ///
+    /// ### Arguments
+    /// * `role` - The role to grant
+    /// * `account` - The account receiving the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner role
+    fn grant_role(e: &Env, role: Role, account: Address);
+
+    /// (Owner only) Revoke a role from an account
+    ///
+    /// ### Arguments
+    /// * `role` - The role to revoke
+    /// * `account` - The account losing the role
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    /// If `role` is the owner or admin role
+    fn revoke_role(e: &Env, role: Role, account: Address);
+
+    /// Give up a role held by `account`
+    ///
+    /// ### Arguments
+    /// * `account` - The account giving up the role
+    /// * `role` - The role to give up
+    ///
+    /// ### Panics
+    /// If `account` does not authorize the call
+    /// If `role` is the owner or admin role
+    fn renounce_role(e: &Env, account: Address, role: Role);
+
+    /// Check whether an account holds a role
+    ///
+    /// ### Arguments
+    /// * `role` - The role to check
+    /// * `account` - The account to check
+    fn has_role(e: &Env, role: Role, account: Address) -> bool;
+
+    /// (Pauser only) Pause an operation
+    ///
+    /// ### Arguments
+    /// * `caller` - An account holding the pauser role
+    /// * `flag` - The operation to pause
+    ///
+    /// ### Panics
+    /// If the caller does not hold the pauser role
+    fn pause(e: &Env, caller: Address, flag: PauseFlag);
+
+    /// (Owner only) Unpause an operation
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to unpause
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn unpause(e: &Env, flag: PauseFlag);
+
+    /// Check whether an operation is paused
+    ///
+    /// ### Arguments
+    /// * `flag` - The operation to check
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool;
+
     /// (Yield Controller only) Advance to the next epoch with a zero-amount distribution
     ///
     /// Used when there is no yield to distribute but the epoch should still advance.
@@ -158,6 +433,7 @@ pub trait YieldDistributorTrait {
     /// ### Panics
     /// If the caller is not the yield controller
     /// If distribution is not yet available
+    /// If distribution is paused
     fn advance_epoch(e: &Env);
 
     /// (Owner only) Upgrade the contract to a new WASM bytecode
@@ -168,6 +444,19 @@ pub trait YieldDistributorTrait {
     /// ### Panics
     /// If the caller is not the owner
     fn upgrade(e: &Env, new_wasm_hash: BytesN<32>);
+
+    /// (Owner only) Migrate stored data to the schema of the current WASM, e.g. after an upgrade
+    ///
+    /// Returns the schema version after migrating. Each call rewrites at most one batch of members or
+    /// epochs, so on a long history it returns an earlier version; call it again until it returns
+    /// the current one. Until then every function that changes state, other than `upgrade`, panics.
+    ///
+    /// ### Panics
+    /// If the caller is not the owner
+    fn migrate(e: &Env) -> u32;
+
+    /// Fetch the schema version of the stored data
+    fn version(e: &Env) -> u32;
 }
 
 /// ### YieldDistributor
@@ -214,7 +503,9 @@ impl YieldDistributorTrait for YieldDistributor {
             distribution_total: 0,
             distribution_treasury: 0,
             distribution_member: 0,
-            members: Vec::new(&e),
+            member_count: 0,
+            reward_per_weight: 0,
+            fee_amounts: Map::new(&e),
             is_processed: false,
         };
         e.storage().persistent().set(&storage_types::DataKey::Distribution(0), &initial_distribution);
@@ -225,16 +516,19 @@ impl YieldDistributorTrait for YieldDistributor {
         );   
 
         e.storage().persistent().set(&storage_types::DataKey::TotalDistributed, &0i128);
+        versioning::write_version(&e, migrations::SCHEMA_VERSION);
     }
 
     fn set_yield_controller(e: &Env, yield_controller: Address) {
+        require_migrated(e);
         require_admin(e);
         storage::set_yield_controller(e, &yield_controller);
         YieldDistributorEvents::set_yield_controller(&e, yield_controller);
     }
 
-    fn add_member(e: &Env, member: Address) {
-        require_admin(e);
+    fn add_member(e: &Env, caller: Address, member: Address) {
+        require_migrated(e);
+        require_member_manager(e, &caller);
 
         if let Some(existing) = storage::get_member(e, &member) {
             if existing.active {
@@ -246,8 +540,9 @@ impl YieldDistributorTrait for YieldDistributor {
         YieldDistributorEvents::add_member(e, member);
     }
 
-    fn remove_member(e: &Env, member: Address) {
-        require_admin(e);
+    fn remove_member(e: &Env, caller: Address, member: Address) {
+        require_migrated(e);
+        require_member_manager(e, &caller);
 
         if let None = storage::get_member(e, &member) {
             panic_with_error!(e, YieldDistributorError::MemberDoesNotExist);
@@ -257,11 +552,45 @@ impl YieldDistributorTrait for YieldDistributor {
         YieldDistributorEvents::remove_member(e, member);
     }
 
-    fn list_members(e: &Env) -> Vec<Address> {
-        storage::get_active_members(e)
+    fn list_members(e: &Env, offset: u32, limit: u32) -> Vec<Address> {
+        storage::read_members_page(e, offset, limit)
+    }
+
+    fn get_member_count(e: &Env) -> u32 { storage::read_member_count(e) }
+
+    fn is_member(e: &Env, member: Address) -> bool { storage::is_active_member(e, &member) }
+
+    fn get_member_joined_at(e: &Env, member: Address) -> Option<u64> {
+        if !storage::is_active_member(e, &member) {
+            return None;
+        }
+        storage::get_member(e, &member).map(|existing| existing.joined_at)
+    }
+
+    fn set_member_weight(e: &Env, caller: Address, member: Address, weight: u32) {
+        require_migrated(e);
+        require_member_manager(e, &caller);
+
+        if storage::get_member(e, &member).is_none() {
+            panic_with_error!(e, YieldDistributorError::MemberDoesNotExist);
+        }
+        if weight == 0 {
+            panic_with_error!(e, YieldDistributorError::InvalidWeight);
+        }
+
+        storage::set_member_weight(e, &member, weight);
+        YieldDistributorEvents::set_member_weight(e, member, weight);
+    }
+
+    fn get_member_weight(e: &Env, member: Address) -> u32 {
+        match storage::get_member(e, &member) {
+            Some(existing) => existing.weight,
+            None => panic_with_error!(e, YieldDistributorError::MemberDoesNotExist),
+        }
     }
 
     fn set_treasury(e: &Env, treasury: Address) {
+        require_migrated(e);
         require_admin(e);
         storage::set_treasury(e, &treasury);
         YieldDistributorEvents::set_treasury(e, treasury);
@@ -270,14 +599,31 @@ impl YieldDistributorTrait for YieldDistributor {
     fn get_treasury(e: &Env) -> Address { storage::get_treasury(e) }
 
     fn set_treasury_share(e: &Env, share_bps: u32) {
+        require_migrated(e);
         require_admin(e);
+        if storage::total_fee_bps(share_bps, &storage::read_fee_recipients(e)) > 10000 {
+            panic_with_error!(e, YieldDistributorError::InvalidFeeSplit);
+        }
         storage::set_treasury_share_bps(e, share_bps);
         YieldDistributorEvents::set_treasury_share(e, share_bps);
     }
 
     fn get_treasury_share(e: &Env) -> u32 { storage::get_treasury_share_bps(e) }
 
+    fn set_fee_recipients(e: &Env, recipients: Map<Address, u32>) {
+        require_migrated(e);
+        require_admin(e);
+        if storage::total_fee_bps(storage::get_treasury_share_bps(e), &recipients) > 10000 {
+            panic_with_error!(e, YieldDistributorError::InvalidFeeSplit);
+        }
+        storage::write_fee_recipients(e, &recipients);
+        YieldDistributorEvents::set_fee_recipients(e, recipients);
+    }
+
+    fn get_fee_recipients(e: &Env) -> Map<Address, u32> { storage::read_fee_recipients(e) }
+
     fn set_distribution_period(e: &Env, period: u64) {
+        require_migrated(e);
         require_admin(e);
         storage::set_distribution_period(e, period);
         YieldDistributorEvents::set_distribution_period(e, period);
@@ -301,74 +647,62 @@ impl YieldDistributorTrait for YieldDistributor {
     fn is_distribution_available(e: &Env) -> bool { storage::check_distribution_availability(e) }
 
     fn distribute_yield(e: &Env, token: Address, amount: i128) -> i128 {
+        require_migrated(e);
         
         require_yield_controller(e);
+        require_not_paused(e, PauseFlag::Distribution);
 
         if !storage::check_distribution_availability(e) {
             return 0;
         }
 
-        let distribution = storage::read_distribution_of_current_epoch(e);
         let treasury_share_bps = storage::get_treasury_share_bps(e);
         let treasury = storage::get_treasury(e);
 
         let mut treasury_amount = (amount as i128 * treasury_share_bps as i128) / 10000;
-        let members_amount = amount - treasury_amount;
+        let mut fee_amounts = Map::new(e);
+        let mut fees_total = 0;
+        for (recipient, share_bps) in storage::read_fee_recipients(e).iter() {
+            let fee_amount = (amount * share_bps as i128) / 10000;
+            fee_amounts.set(recipient, fee_amount);
+            fees_total += fee_amount;
+        }
+        let mut members_amount = amount - treasury_amount - fees_total;
         
-        let per_member_amount = if distribution.members.len() > 0 {
-            members_amount / distribution.members.len() as i128
-        } else {
-            treasury_amount = amount; // if no members then it all goes to the treasury
-            0
-        };
+        if storage::read_member_count(e) == 0 {
+            treasury_amount = amount - fees_total; // if no members then the treasury gets the member pool
+            members_amount = 0;
+        }
 
-        let token_client = TokenClient::new(e, &token);
-        if per_member_amount > 0 {
-            for member in distribution.members.iter() {
-                utils::authenticate_contract(
-                    &e, 
-                    token_client.address.clone(), 
-                    Symbol::new(&e, "transfer"), 
-                    vec![
-                        e,
-                        (&e.current_contract_address()).into_val(e),
-                        (&member).into_val(e),
-                        (&per_member_amount).into_val(e),
-                    ]
-                );
-                token_client.transfer(
-                    &e.current_contract_address(),
-                    &member,
-                    &per_member_amount,
-                );
+        // Every recipient pulls their share with `claim`, so one that can't receive can't block the distribution
+        match storage::read_yield_token(e) {
+            Some(current) if current != token && storage::has_unclaimed(e) => {
+                panic_with_error!(e, YieldDistributorError::YieldTokenMismatch);
             }
+            _ => storage::write_yield_token(e, &token),
+        }
+        let reward_per_weight = storage::accrue_members(e, members_amount);
+        storage::credit_unclaimed(e, &treasury, treasury_amount);
+        for (recipient, fee_amount) in fee_amounts.iter() {
+            storage::credit_unclaimed(e, &recipient, fee_amount);
         }
-        utils::authenticate_contract(
-            &e, 
-            token_client.address.clone(), 
-            Symbol::new(&e, "transfer"), 
-            vec![
-                e,
-                (&e.current_contract_address()).into_val(e),
-                (&treasury).into_val(e),
-                (&treasury_amount).into_val(e),
-            ]
-        );
-        token_client.transfer(
-            &e.current_contract_address(),
-            &treasury,
-            &treasury_amount,
-        );
         
-        storage::record_distribution(e, amount, treasury_amount, members_amount);
+        storage::record_distribution(
+            e,
+            amount,
+            treasury_amount,
+            members_amount,
+            reward_per_weight,
+            fee_amounts.clone(),
+        );
         
         YieldDistributorEvents::distribute_yield(
             e,
             token,
             amount,
             treasury_amount,
-            distribution.members,
-            per_member_amount,
+            fee_amounts,
+            members_amount,
         );
 
         amount
@@ -377,28 +711,147 @@ impl YieldDistributorTrait for YieldDistributor {
     fn get_distribution_info(e: &Env) -> Distribution {  storage::read_distribution_of_current_epoch(e) }
 
     fn get_distribution_history(e: &Env) -> Vec<Distribution> { storage::read_distribution_history(e) }
+
+    fn get_member_distribution(e: &Env, epoch: u64, member: Address) -> i128 {
+        storage::read_member_distribution(e, epoch, &member)
+    }
     
     fn get_current_epoch(e: &Env) -> u64 { storage::read_epoch_current(e) }
 
     fn set_admin(e: &Env, new_admin: Address) {
+        require_migrated(e);
         require_owner(e);
         storage::write_admin(e, new_admin.clone());
         YieldDistributorEvents::set_admin(&e, new_admin);
     }
 
+    fn propose_owner(e: &Env, new_owner: Address) {
+        require_migrated(e);
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Owner, &new_owner);
+    }
+
+    fn accept_owner(e: &Env) {
+        require_migrated(e);
+        access_control::accept_transfer(e, Role::Owner);
+    }
+
+    fn cancel_ownership_transfer(e: &Env) {
+        require_migrated(e);
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Owner);
+    }
+
+    fn get_pending_owner(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Owner)
+    }
+
+    fn propose_admin(e: &Env, new_admin: Address) {
+        require_migrated(e);
+        require_owner(e);
+        access_control::propose_transfer(e, Role::Admin, &new_admin);
+    }
+
+    fn accept_admin(e: &Env) {
+        require_migrated(e);
+        let new_admin = access_control::accept_transfer(e, Role::Admin);
+        YieldDistributorEvents::set_admin(e, new_admin);
+    }
+
+    fn cancel_admin_transfer(e: &Env) {
+        require_migrated(e);
+        require_owner(e);
+        access_control::cancel_transfer(e, Role::Admin);
+    }
+
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        access_control::pending_holder(e, Role::Admin)
+    }
+
+    fn grant_role(e: &Env, role: Role, account: Address) {
+        require_migrated(e);
+        require_owner(e);
+        if role == Role::Owner {
+            panic_with_error!(e, AccessControlError::InvalidRole);
+        }
+        access_control::grant_role(e, role, &account);
+    }
+
+    fn revoke_role(e: &Env, role: Role, account: Address) {
+        require_migrated(e);
+        require_owner(e);
+        access_control::revoke_role(e, role, &account);
+    }
+
+    fn renounce_role(e: &Env, account: Address, role: Role) {
+        require_migrated(e);
+        access_control::renounce_role(e, role, &account);
+    }
+
+    fn has_role(e: &Env, role: Role, account: Address) -> bool {
+        access_control::has_role(e, role, &account)
+    }
+
+    fn pause(e: &Env, caller: Address, flag: PauseFlag) {
+        require_migrated(e);
+        access_control::require_role(e, Role::Pauser, &caller);
+        storage::write_paused(e, flag, true);
+        YieldDistributorEvents::pause(e, flag);
+    }
+
+    fn unpause(e: &Env, flag: PauseFlag) {
+        require_migrated(e);
+        require_owner(e);
+        storage::write_paused(e, flag, false);
+        YieldDistributorEvents::unpause(e, flag);
+    }
+
+    fn is_paused(e: &Env, flag: PauseFlag) -> bool { storage::is_paused(e, flag) }
+
     fn get_yield_controller(e: &Env) -> Address { storage::get_yield_controller(e) }
 
     fn get_total_distributed(e: &Env) -> i128 { storage::read_total_distributed(e) }
 
+    fn claim(e: &Env, member: Address) -> i128 {
+        require_migrated(e);
+        require_not_paused(e, PauseFlag::Distribution);
+        match yield_token_client(e) {
+            Some(token_client) => claim_member(e, &token_client, member),
+            None => 0,
+        }
+    }
+
+    fn claim_many(e: &Env, members: Vec<Address>) -> i128 {
+        require_migrated(e);
+        require_not_paused(e, PauseFlag::Distribution);
+        let Some(token_client) = yield_token_client(e) else {
+            return 0;
+        };
+
+        let mut total = 0;
+        for member in members.iter() {
+            total += claim_member(e, &token_client, member);
+        }
+        total
+    }
+
+    fn get_unclaimed(e: &Env, member: Address) -> i128 { storage::read_unclaimed(e, &member) }
+
+    fn get_total_unclaimed(e: &Env) -> i128 { storage::read_total_unclaimed(e) }
+
+    fn get_yield_token(e: &Env) -> Option<Address> { storage::read_yield_token(e) }
+
     fn advance_epoch(e: &Env) {
+        require_migrated(e);
         require_yield_controller(e);
+        require_not_paused(e, PauseFlag::Distribution);
 
         if !storage::check_distribution_availability(e) {
             panic_with_error!(e, YieldDistributorError::InternalError);
         }
 
         // Record a zero-amount distribution and advance to next epoch
-        storage::record_distribution(e, 0, 0, 0);
+        storage::record_distribution(e, 0, 0, 0, 0, Map::new(e));
 
         let new_epoch = storage::read_epoch_current(e);
         YieldDistributorEvents::advance_epoch(e, new_epoch);
@@ -408,4 +861,16 @@ impl YieldDistributorTrait for YieldDistributor {
         require_owner(e);
         e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
+
+    fn migrate(e: &Env) -> u32 {
+        // Migrate before authorizing: an old layout may keep the owner where `require_owner` can't
+        // read it, and a failed authorization reverts the migration
+        let version = migrations::migrate(e);
+        require_owner(e);
+        version
+    }
+
+    fn version(e: &Env) -> u32 {
+        migrations::version(e)
+    }
 }
@@ -16,4 +16,9 @@ pub enum YieldDistributorError {
     // YieldDistributorError Errors
     MemberAlreadyExists = 1200,
     MemberDoesNotExist = 1201,
+    Paused = 1202,
+    InvalidWeight = 1203,
+    YieldTokenMismatch = 1204,
+    InvalidFeeSplit = 1205,
+    MigrationPending = 1206,
 }
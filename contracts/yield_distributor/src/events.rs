@@ -1,4 +1,5 @@
-use soroban_sdk::{Address, Env, Symbol, Vec};
+use soroban_sdk::{Address, Env, Map, Symbol};
+use crate::storage_types::PauseFlag;
 
 pub struct YieldDistributorEvents {}
 
@@ -13,6 +14,11 @@ impl YieldDistributorEvents {
         e.events().publish(topics, member);
     }
 
+    pub fn set_member_weight(e: &Env, member: Address, weight: u32) {
+        let topics = (Symbol::new(e, "set_member_weight"), member);
+        e.events().publish(topics, weight);
+    }
+
     pub fn set_treasury(e: &Env, treasury: Address) {
         let topics = (Symbol::new(e, "set_treasury"),);
         e.events().publish(topics, treasury);
@@ -23,6 +29,11 @@ impl YieldDistributorEvents {
         e.events().publish(topics, share_bps);
     }
 
+    pub fn set_fee_recipients(e: &Env, recipients: Map<Address, u32>) {
+        let topics = (Symbol::new(e, "set_fee_recipients"),);
+        e.events().publish(topics, recipients);
+    }
+
     pub fn set_distribution_period(e: &Env, period: u64) {
         let topics = (Symbol::new(e, "set_distribution_period"),);
         e.events().publish(topics, period);
@@ -48,13 +59,28 @@ impl YieldDistributorEvents {
         asset: Address,
         total_amount: i128,
         treasury_amount: i128,
-        members: Vec<Address>,
-        per_member_amount: i128,
+        fee_amounts: Map<Address, i128>,
+        members_amount: i128,
     ) {
         let topics = (Symbol::new(e, "distribute_yield"), asset);
         e.events().publish(
             topics,
-            (total_amount, treasury_amount, members, per_member_amount),
+            (total_amount, treasury_amount, fee_amounts, members_amount),
         );
     }
+
+    pub fn claim(e: &Env, token: Address, member: Address, amount: i128) {
+        let topics = (Symbol::new(e, "claim"), token, member);
+        e.events().publish(topics, amount);
+    }
+
+    pub fn pause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "pause"),);
+        e.events().publish(topics, flag);
+    }
+
+    pub fn unpause(e: &Env, flag: PauseFlag) {
+        let topics = (Symbol::new(e, "unpause"),);
+        e.events().publish(topics, flag);
+    }
 }
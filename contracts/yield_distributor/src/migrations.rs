@@ -0,0 +1,154 @@
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
+use access_control::role::Role;
+use crate::storage;
+use crate::storage_types::{DataKey, Distribution, Member, DEFAULT_MEMBER_WEIGHT};
+
+/// Schema version written by this build
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+/// Members or epochs a migration step rewrites per `migrate()` call, so a long history fits the budget
+const MIGRATION_BATCH_SIZE: u32 = 25;
+/// Contracts deployed before versioning hold their data in the version 1 layout
+const UNVERSIONED: u32 = 1;
+
+/// Version 1 keys that version 2 moved elsewhere
+#[derive(Clone)]
+#[contracttype]
+pub(crate) enum LegacyDataKey {
+    Owner,
+    Admin,
+    Members, // Every member address ever added
+}
+
+/// Version 1 member record, before members carried a weight
+#[derive(Clone)]
+#[contracttype]
+pub(crate) struct LegacyMember {
+    pub address: Address,
+    pub active: bool,
+    pub joined_at: u64,
+}
+
+/// Version 1 distribution record, holding a copy of the active member list
+#[derive(Clone)]
+#[contracttype]
+pub(crate) struct LegacyDistribution {
+    pub distribution_end_timestamp: u64,
+    pub distribution_start_timestamp: u64,
+    pub distribution_total: i128,
+    pub distribution_treasury: i128,
+    pub distribution_member: i128,
+    pub members: Vec<Address>,
+    pub is_processed: bool,
+    pub epoch: u64,
+}
+
+/// Bring stored data towards `SCHEMA_VERSION` and return the resulting version. Each call rewrites at most
+/// one batch; the owner calls it again until it returns `SCHEMA_VERSION`.
+pub fn migrate(e: &Env) -> u32 {
+    versioning::migrate_in_batches(e, UNVERSIONED, SCHEMA_VERSION, step)
+}
+
+pub fn version(e: &Env) -> u32 {
+    versioning::read_version(e, UNVERSIONED)
+}
+
+fn step(e: &Env, from: u32) -> bool {
+    match from {
+        1 => v1_to_v2(e),
+        _ => true,
+    }
+}
+
+/// Run `migrate_item` on the next batch of a step's `total` items, resuming where the last call stopped.
+/// Returns whether every item has been migrated.
+fn in_batches(e: &Env, total: u32, mut migrate_item: impl FnMut(u32)) -> bool {
+    let start = versioning::read_cursor(e);
+    let end = total.min(start.saturating_add(MIGRATION_BATCH_SIZE));
+    for item in start..end {
+        migrate_item(item);
+    }
+    versioning::write_cursor(e, end);
+    end == total
+}
+
+/// Version 2 keeps the owner and admin in the shared access control storage, gives every member a weight
+/// of 1, indexes the active members for the reward-per-weight accumulator and replaces each distribution's
+/// member list with its length, keeping the equal split a processed epoch paid its members
+fn v1_to_v2(e: &Env) -> bool {
+    for (legacy, role) in [(LegacyDataKey::Owner, Role::Owner), (LegacyDataKey::Admin, Role::Admin)] {
+        if let Some(holder) = e.storage().instance().get::<_, Address>(&legacy) {
+            access_control::grant_role(e, role, &holder);
+            e.storage().instance().remove(&legacy);
+        }
+    }
+
+    let members: Vec<Address> = e.storage().persistent().get(&LegacyDataKey::Members).unwrap_or(Vec::new(e));
+    let member_count = members.len();
+    let epoch_count = storage::read_epoch_current(e) as u32 + 1;
+    let done = in_batches(e, member_count + epoch_count, |item| {
+        if item < member_count {
+            migrate_member(e, members.get_unchecked(item));
+        } else {
+            migrate_distribution(e, (item - member_count) as u64);
+        }
+    });
+    if done {
+        e.storage().persistent().remove(&LegacyDataKey::Members);
+    }
+    done
+}
+
+fn migrate_member(e: &Env, address: Address) {
+    let key = DataKey::Member(address.clone());
+    let Some(legacy) = e.storage().persistent().get::<_, LegacyMember>(&key) else {
+        return;
+    };
+    let member = Member {
+        address: legacy.address,
+        active: legacy.active,
+        joined_at: legacy.joined_at,
+        weight: DEFAULT_MEMBER_WEIGHT,
+    };
+    e.storage().persistent().set(&key, &member);
+
+    if member.active {
+        let count = storage::read_member_count(e);
+        storage::write_member_at(e, count, &address);
+        storage::write_member_count(e, count + 1);
+        storage::write_total_weight(e, storage::read_total_weight(e) + DEFAULT_MEMBER_WEIGHT as u64);
+        storage::write_weight_checkpoint(e, &address, DEFAULT_MEMBER_WEIGHT);
+    }
+}
+
+fn migrate_distribution(e: &Env, epoch: u64) {
+    let key = DataKey::Distribution(epoch);
+    let Some(legacy) = e.storage().persistent().get::<_, LegacyDistribution>(&key) else {
+        return;
+    };
+
+    // Version 1 paid every member of a processed epoch an equal share
+    if legacy.is_processed && !legacy.members.is_empty() {
+        let per_member_amount = legacy.distribution_member / legacy.members.len() as i128;
+        let mut member_amounts = Map::new(e);
+        for member in legacy.members.iter() {
+            member_amounts.set(member, per_member_amount);
+        }
+        let amounts_key = DataKey::LegacyMemberAmounts(epoch);
+        e.storage().persistent().set(&amounts_key, &member_amounts);
+        storage::extend_persistent(e, &amounts_key);
+    }
+
+    let distribution = Distribution {
+        distribution_end_timestamp: legacy.distribution_end_timestamp,
+        distribution_start_timestamp: legacy.distribution_start_timestamp,
+        distribution_total: legacy.distribution_total,
+        distribution_treasury: legacy.distribution_treasury,
+        distribution_member: legacy.distribution_member,
+        member_count: legacy.members.len(),
+        reward_per_weight: 0,
+        fee_amounts: Map::new(e),
+        is_processed: legacy.is_processed,
+        epoch: legacy.epoch,
+    };
+    e.storage().persistent().set(&key, &distribution);
+}
@@ -3,13 +3,18 @@ use crate::storage_types::{
     Distribution, 
     DistributionConfig, 
     Member, 
+    PauseFlag,
     CURRENT_EPOCH_KEY,
+    DEFAULT_MEMBER_WEIGHT,
     INSTANCE_BUMP_AMOUNT, 
     INSTANCE_LIFETIME_THRESHOLD, 
     PERSISTENT_BUMP_AMOUNT, 
-    PERSISTENT_LIFETIME_THRESHOLD
+    PERSISTENT_LIFETIME_THRESHOLD,
+    REWARD_SCALE,
+    WeightCheckpoint,
 };
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{Address, Env, Map, Vec};
+use access_control::role::Role;
 
 pub fn extend_instance(e: &Env) {
     e.storage()
@@ -23,8 +28,15 @@ pub fn extend_persistent(e: &Env, key: &DataKey) {
         .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 }
 
-pub fn read_admin(e: &Env) -> Address { read_address(e, &DataKey::Admin)}
-pub fn read_owner(e: &Env) -> Address { read_address(e, &DataKey::Owner)}
+pub fn read_admin(e: &Env) -> Address { read_role_holder(e, Role::Admin) }
+pub fn read_owner(e: &Env) -> Address { read_role_holder(e, Role::Owner) }
+pub fn write_admin(e: &Env, new_admin: Address) { access_control::grant_role(e, Role::Admin, &new_admin); }
+pub fn write_owner(e: &Env, new_owner: Address) { access_control::grant_role(e, Role::Owner, &new_owner); }
+
+fn read_role_holder(e: &Env, role: Role) -> Address {
+    extend_instance(e);
+    access_control::role_holder(e, role).unwrap()
+}
 
 fn read_address(e: &Env, key: &DataKey) -> Address {
     extend_instance(e);
@@ -36,8 +48,6 @@ fn write_address(e: &Env, key: &DataKey, address: &Address) {
     e.storage().instance().set(key, address); 
 }
 
-pub fn write_admin(e: &Env, new_admin: Address) { write_address(e, &DataKey::Admin, &new_admin);}
-pub fn write_owner(e: &Env, new_owner: Address) { write_address(e, &DataKey::Owner, &new_owner);}
 
 pub fn get_treasury(e: &Env) -> Address { read_address(e, &DataKey::Treasury) }
 
@@ -60,6 +70,24 @@ pub fn set_treasury_share_bps(e: &Env, share_bps: u32) {
     write_distribution_config(e, config);
 }
 
+pub fn read_fee_recipients(e: &Env) -> Map<Address, u32> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::FeeRecipients).unwrap_or(Map::new(e))
+}
+
+pub fn write_fee_recipients(e: &Env, recipients: &Map<Address, u32>) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::FeeRecipients, recipients);
+}
+
+/// Sum of the treasury share and every fee recipient's share, in basis points
+pub fn total_fee_bps(treasury_share_bps: u32, recipients: &Map<Address, u32>) -> u64 {
+    recipients
+        .values()
+        .iter()
+        .fold(treasury_share_bps as u64, |total, share_bps| total + share_bps as u64)
+}
+
 pub fn get_yield_controller(e: &Env) -> Address { read_address(e, &DataKey::YieldController) }
 
 pub fn set_yield_controller(e: &Env, controller: &Address) { write_address(e, &DataKey::YieldController, controller);}
@@ -79,37 +107,48 @@ pub fn set_distribution_period(e: &Env, period: u64) {
 pub fn add_member(e: &Env, address: &Address) {
     
     extend_instance(e);
+    let key = DataKey::Member(address.clone());
+    // A returning member keeps the weight they were given before they were removed
+    let weight = e
+        .storage()
+        .persistent()
+        .get::<DataKey, Member>(&key)
+        .map_or(DEFAULT_MEMBER_WEIGHT, |existing| existing.weight);
     let member = Member {
         address: address.clone(),
         active: true,
         joined_at: e.ledger().timestamp(),
+        weight,
     };
-    
-    let key = DataKey::Member(address.clone());
+
     e.storage().persistent().set(&key, &member);
     extend_persistent(e, &key);
 
-    let members_key = DataKey::Members;
-    let mut members: Vec<Address> = match e.storage().persistent().get(&members_key) {
-        Some(existing) => existing,
-        None => Vec::new(e),
-    };
-    if !members.iter().any(|a| a == address.clone()) {
-        members.push_back(address.clone());
-        e.storage().persistent().set(&members_key, &members);
-        extend_persistent(e, &members_key);
+    if !is_active_member(e, address) {
+        // Start accruing from the current accumulator, not from what was distributed before joining
+        settle_member(e, address);
+        let count = read_member_count(e);
+        write_member_at(e, count, address);
+        write_member_count(e, count + 1);
+        write_total_weight(e, read_total_weight(e) + weight as u64);
+        write_weight_checkpoint(e, address, weight);
     }
     
-    // Update current distribution with new member list
-    update_current_distribution_members(e);
+    // Update current distribution with new member count
+    update_current_distribution_member_count(e);
 }
 
 pub fn remove_member(e: &Env, address: &Address) {
     extend_instance(e);
+    settle_member(e, address);
 
     // Update member record to inactive
     let key = DataKey::Member(address.clone());
     if let Some(mut member) = e.storage().persistent().get::<DataKey, Member>(&key) {
+        if is_active_member(e, address) {
+            write_total_weight(e, read_total_weight(e) - member.weight as u64);
+            write_weight_checkpoint(e, address, 0);
+        }
         member.active = false;
         e.storage().persistent().set(&key, &member);
         e.storage().persistent().extend_ttl(
@@ -119,54 +158,280 @@ pub fn remove_member(e: &Env, address: &Address) {
         );
     }
 
-    // Update current distribution with new member list
-    update_current_distribution_members(e);
+    // Move the last active member into the freed slot so the index stays dense
+    let index_key = DataKey::MemberIndex(address.clone());
+    if let Some(index) = e.storage().persistent().get::<DataKey, u32>(&index_key) {
+        let last = read_member_count(e) - 1;
+        if index != last {
+            let moved = read_member_at(e, last);
+            write_member_at(e, index, &moved);
+        }
+        e.storage().persistent().remove(&DataKey::MemberAt(last));
+        e.storage().persistent().remove(&index_key);
+        write_member_count(e, last);
+    }
+
+    // Update current distribution with new member count
+    update_current_distribution_member_count(e);
     
-    // Note: We don't remove from the members list to maintain history
+    // Note: We keep the member record to maintain history
+}
+
+pub fn set_member_weight(e: &Env, address: &Address, weight: u32) {
+    extend_instance(e);
+    // Settle at the old weight so the change only applies to later distributions
+    settle_member(e, address);
+    let key = DataKey::Member(address.clone());
+    if let Some(mut member) = e.storage().persistent().get::<DataKey, Member>(&key) {
+        if is_active_member(e, address) {
+            write_total_weight(e, read_total_weight(e) - member.weight as u64 + weight as u64);
+            write_weight_checkpoint(e, address, weight);
+        }
+        member.weight = weight;
+        e.storage().persistent().set(&key, &member);
+        extend_persistent(e, &key);
+    }
 }
 
+/// Record that `address` has `weight` from the current epoch on. A change earlier in the same epoch is
+/// replaced, since only the weight when the epoch is distributed counts.
+pub(crate) fn write_weight_checkpoint(e: &Env, address: &Address, weight: u32) {
+    let key = DataKey::WeightHistory(address.clone());
+    let mut history: Vec<WeightCheckpoint> = e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    let epoch = read_epoch_current(e);
+    if history.last().is_some_and(|last| last.epoch == epoch) {
+        history.pop_back();
+    }
+    history.push_back(WeightCheckpoint { epoch, weight });
+    e.storage().persistent().set(&key, &history);
+    extend_persistent(e, &key);
+}
+
+/// Weight `address` had when `epoch` was distributed
+fn read_weight_at(e: &Env, address: &Address, epoch: u64) -> u32 {
+    let key = DataKey::WeightHistory(address.clone());
+    let history: Vec<WeightCheckpoint> = e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    history
+        .iter()
+        .rev()
+        .find(|checkpoint| checkpoint.epoch <= epoch)
+        .map_or(0, |checkpoint| checkpoint.weight)
+}
+
+/// Yield a member received in a processed epoch, rounded down to a token unit; zero for other epochs
+pub fn read_member_distribution(e: &Env, epoch: u64, address: &Address) -> i128 {
+    let legacy_key = DataKey::LegacyMemberAmounts(epoch);
+    if let Some(amounts) = e.storage().persistent().get::<DataKey, Map<Address, i128>>(&legacy_key) {
+        return amounts.get(address.clone()).unwrap_or(0);
+    }
+    match e.storage().persistent().get::<DataKey, Distribution>(&DataKey::Distribution(epoch)) {
+        Some(distribution) if distribution.is_processed => {
+            read_weight_at(e, address, epoch) as i128 * distribution.reward_per_weight / REWARD_SCALE
+        },
+        _ => 0,
+    }
+}
+
+/// Spread `amount` over the active members by weight and return the scaled amount per unit of weight.
+/// Each member's share is settled into their unclaimed balance later, so a distribution costs the same
+/// however many members there are.
+pub fn accrue_members(e: &Env, amount: i128) -> i128 {
+    let total_weight = read_total_weight(e) as i128;
+    if total_weight == 0 || amount <= 0 {
+        return 0;
+    }
+    let per_weight = amount * REWARD_SCALE / total_weight;
+    write_reward_per_weight(e, read_reward_per_weight(e) + per_weight);
+    write_pending_rewards(e, read_pending_rewards(e) + per_weight * total_weight);
+    per_weight
+}
+
+/// Scaled yield an active member has accrued since their share was last settled
+fn accrued_rewards(e: &Env, address: &Address) -> i128 {
+    if !is_active_member(e, address) {
+        return 0;
+    }
+    let weight = get_member(e, address).map_or(DEFAULT_MEMBER_WEIGHT, |member| member.weight);
+    weight as i128 * (read_reward_per_weight(e) - read_reward_checkpoint(e, address))
+}
+
+/// Move a member's accrued share into their unclaimed balance and checkpoint the accumulator.
+/// The part below one token unit is rounded away and stays in the distributor.
+pub fn settle_member(e: &Env, address: &Address) {
+    let accrued = accrued_rewards(e, address);
+    if accrued > 0 {
+        write_pending_rewards(e, read_pending_rewards(e) - accrued);
+        credit_unclaimed(e, address, accrued / REWARD_SCALE);
+    }
+
+    let key = DataKey::RewardCheckpoint(address.clone());
+    e.storage().persistent().set(&key, &read_reward_per_weight(e));
+    extend_persistent(e, &key);
+}
+
+fn read_settled(e: &Env, address: &Address) -> i128 {
+    let key = DataKey::Unclaimed(address.clone());
+    match e.storage().persistent().get(&key) {
+        Some(amount) => {
+            extend_persistent(e, &key);
+            amount
+        },
+        None => 0,
+    }
+}
+
+/// Add yield to an account's unclaimed balance, e.g. the treasury's or a fee recipient's share
+pub fn credit_unclaimed(e: &Env, address: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let key = DataKey::Unclaimed(address.clone());
+    e.storage().persistent().set(&key, &(read_settled(e, address) + amount));
+    extend_persistent(e, &key);
+    write_total_settled(e, read_total_settled(e) + amount);
+}
+
+/// A member's claimable yield, including the share not yet settled
+pub fn read_unclaimed(e: &Env, address: &Address) -> i128 {
+    read_settled(e, address) + accrued_rewards(e, address) / REWARD_SCALE
+}
+
+/// Clear a member's unclaimed balance, returning the amount that was owed
+pub fn take_unclaimed(e: &Env, address: &Address) -> i128 {
+    settle_member(e, address);
+    let amount = read_settled(e, address);
+    if amount > 0 {
+        e.storage().persistent().remove(&DataKey::Unclaimed(address.clone()));
+        write_total_settled(e, read_total_settled(e) - amount);
+    }
+    amount
+}
+
+/// Yield owed to members, including shares not yet settled
+pub fn read_total_unclaimed(e: &Env) -> i128 {
+    read_total_settled(e) + read_pending_rewards(e) / REWARD_SCALE
+}
+
+/// Whether any member yield is still held for members, down to accrued remainders below one token unit
+pub fn has_unclaimed(e: &Env) -> bool {
+    read_total_settled(e) > 0 || read_pending_rewards(e) > 0
+}
+
+fn read_total_settled(e: &Env) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::TotalUnclaimed)
+        .unwrap_or(0i128)
+}
+
+fn write_total_settled(e: &Env, total: i128) {
+    e.storage().persistent().set(&DataKey::TotalUnclaimed, &total);
+    extend_persistent(e, &DataKey::TotalUnclaimed);
+}
+
+pub(crate) fn read_total_weight(e: &Env) -> u64 {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::TotalWeight).unwrap_or(0)
+}
+
+pub(crate) fn write_total_weight(e: &Env, total: u64) {
+    e.storage().instance().set(&DataKey::TotalWeight, &total);
+}
+
+fn read_reward_per_weight(e: &Env) -> i128 {
+    e.storage().instance().get(&DataKey::RewardPerWeight).unwrap_or(0)
+}
+
+fn write_reward_per_weight(e: &Env, reward_per_weight: i128) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::RewardPerWeight, &reward_per_weight);
+}
+
+fn read_reward_checkpoint(e: &Env, address: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::RewardCheckpoint(address.clone()))
+        .unwrap_or(0)
+}
+
+fn read_pending_rewards(e: &Env) -> i128 {
+    e.storage().instance().get(&DataKey::PendingRewards).unwrap_or(0)
+}
+
+fn write_pending_rewards(e: &Env, pending: i128) {
+    extend_instance(e);
+    e.storage().instance().set(&DataKey::PendingRewards, &pending);
+}
+
+pub fn read_yield_token(e: &Env) -> Option<Address> {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::YieldToken)
+}
+
+pub fn write_yield_token(e: &Env, token: &Address) { write_address(e, &DataKey::YieldToken, token); }
+
 pub fn get_member(e: &Env, address: &Address) -> Option<Member> {
     extend_instance(e);
     let key = DataKey::Member(address.clone());
     e.storage().persistent().get(&key)
 }
 
-pub fn get_active_members(e: &Env) -> Vec<Address> {
+pub fn is_active_member(e: &Env, address: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::MemberIndex(address.clone()))
+}
+
+pub fn read_member_count(e: &Env) -> u32 {
     extend_instance(e);
+    e.storage().instance().get(&DataKey::MemberCount).unwrap_or(0)
+}
 
-    let members_key = DataKey::Members;
+pub(crate) fn write_member_count(e: &Env, count: u32) {
+    e.storage().instance().set(&DataKey::MemberCount, &count);
+}
 
-    if let Some(all_members) = e
-        .storage()
-        .persistent()
-        .get::<DataKey, Vec<Address>>(&members_key)
-    {
-        let mut active = Vec::new(e);
-
-        for address in all_members.iter() {
-            if let Some(member) = get_member(e, &address) {
-                if member.active {
-                    active.push_back(address);
-                }
-            }
-        }
+pub(crate) fn read_member_at(e: &Env, index: u32) -> Address {
+    let key = DataKey::MemberAt(index);
+    extend_persistent(e, &key);
+    e.storage().persistent().get(&key).unwrap()
+}
 
-        return active;
-    }
+pub(crate) fn write_member_at(e: &Env, index: u32, address: &Address) {
+    let key = DataKey::MemberAt(index);
+    e.storage().persistent().set(&key, address);
+    extend_persistent(e, &key);
+
+    let index_key = DataKey::MemberIndex(address.clone());
+    e.storage().persistent().set(&index_key, &index);
+    extend_persistent(e, &index_key);
+}
 
-    Vec::new(e)
+/// Fetch up to `limit` active members starting at `offset`
+pub fn read_members_page(e: &Env, offset: u32, limit: u32) -> Vec<Address> {
+    let end = read_member_count(e).min(offset.saturating_add(limit));
+    let mut members = Vec::new(e);
+    for index in offset..end {
+        members.push_back(read_member_at(e, index));
+    }
+    members
 }
 
-fn update_current_distribution_members(e: &Env) {
+fn update_current_distribution_member_count(e: &Env) {
     let current_epoch = read_epoch_current(e);
     if e.storage().persistent().has(&DataKey::Distribution(current_epoch)) {
         let mut current_distribution = read_distribution(e, current_epoch);
-        current_distribution.members = get_active_members(e);
+        current_distribution.member_count = read_member_count(e);
         write_distribution(e, current_epoch, current_distribution);
     }
 }
 
-pub fn record_distribution(e: &Env, total: i128, treasury_amount: i128, member_amount: i128) {
+pub fn record_distribution(
+    e: &Env,
+    total: i128,
+    treasury_amount: i128,
+    member_amount: i128,
+    reward_per_weight: i128,
+    fee_amounts: Map<Address, i128>,
+) {
     
     let epoch = read_epoch_current(e);
     
@@ -176,6 +441,8 @@ pub fn record_distribution(e: &Env, total: i128, treasury_amount: i128, member_a
     distribution.distribution_total = total;
     distribution.distribution_treasury = treasury_amount;
     distribution.distribution_member = member_amount;
+    distribution.reward_per_weight = reward_per_weight;
+    distribution.fee_amounts = fee_amounts;
     distribution.is_processed = true;
 
     write_distribution(e, epoch, distribution);
@@ -199,7 +466,9 @@ pub fn record_distribution(e: &Env, total: i128, treasury_amount: i128, member_a
         distribution_total: 0,
         distribution_treasury: 0,
         distribution_member: 0,
-        members: get_active_members(e),
+        member_count: read_member_count(e),
+        reward_per_weight: 0,
+        fee_amounts: Map::new(e),
         is_processed: false,
     };
     write_distribution(e, next_epoch, next_distribution);
@@ -253,7 +522,9 @@ pub fn read_distribution_of_current_epoch(e: &Env) -> Distribution {
             distribution_total: 0,
             distribution_treasury: 0,
             distribution_member: 0,
-            members: Vec::new(e),
+            member_count: 0,
+            reward_per_weight: 0,
+            fee_amounts: Map::new(e),
             is_processed: false,
         }
     }
@@ -359,3 +630,19 @@ pub fn read_epoch_current(e: &Env) -> u64 {
         .get(&CURRENT_EPOCH_KEY)
         .unwrap_or(0)
 }
+
+// Pause storage functions
+
+pub fn is_paused(e: &Env, flag: PauseFlag) -> bool {
+    extend_instance(e);
+    e.storage().instance().get(&DataKey::Paused(flag)).unwrap_or(false)
+}
+
+pub fn write_paused(e: &Env, flag: PauseFlag, paused: bool) {
+    if paused {
+        e.storage().instance().set(&DataKey::Paused(flag), &true);
+    } else {
+        e.storage().instance().remove(&DataKey::Paused(flag));
+    }
+    extend_instance(e);
+}
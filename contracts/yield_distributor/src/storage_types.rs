@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Symbol, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, Map, Symbol};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
@@ -6,6 +6,12 @@ pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
 pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
+/// Weight of a member whose weight was never set; equal weights split the member share equally
+pub(crate) const DEFAULT_MEMBER_WEIGHT: u32 = 1;
+
+/// Scale of the reward-per-weight accumulator, so small distributions over large total weights still accrue
+pub(crate) const REWARD_SCALE: i128 = 1_000_000_000_000;
+
 // Keys for instance storage
 pub (crate) const CURRENT_EPOCH_KEY: Symbol = symbol_short!("EPOCH");
 
@@ -24,6 +30,7 @@ pub struct Member {
     pub address: Address,
     pub active: bool,
     pub joined_at: u64,
+    pub weight: u32, // Share of the member amount relative to the other members' weights
 }
 
 #[derive(Clone)]
@@ -34,24 +41,56 @@ pub struct Distribution {
     pub distribution_total: i128,
     pub distribution_treasury: i128,
     pub distribution_member: i128,
-    pub members: Vec<Address>,
+    pub member_count: u32, // Active members when the epoch started or was processed
+    pub reward_per_weight: i128, // Member yield per unit of weight once processed, scaled by REWARD_SCALE
+    pub fee_amounts: Map<Address, i128>, // Amount paid to each fee recipient once processed
     pub is_processed: bool, // In seconds
     pub epoch: u64,
 }
 
+/// A member's weight from `epoch` on, zero while the member is not active
+#[derive(Clone)]
+#[contracttype]
+pub struct WeightCheckpoint {
+    pub epoch: u64,
+    pub weight: u32,
+}
+
+/// Operations that can be paused independently during an incident
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PauseFlag {
+    Deposit,
+    Withdraw,
+    Harvest,
+    Distribution,
+    Mint,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    Admin,
-    Owner,
     YieldController,
     Treasury,
+    FeeRecipients,     // Map of fee recipient to share in basis points, paid besides the treasury
     Member(Address),   // Map address to Member
-    Members,           // Vec of all member addresses
+    MemberAt(u32),     // Active member at an index, 0..MemberCount
+    MemberIndex(Address), // Index of an active member
+    MemberCount,
     Distributions,     // Vec of all distribution timestamps
     Distribution(u64), // distribution to epoch
     DistributionConfig,
     Epoch(u64),
     EpochStartTimestamp(u64),
     TotalDistributed,
+    Paused(PauseFlag),
+    Unclaimed(Address), // Member, treasury or fee recipient address to yield settled but not yet claimed
+    TotalUnclaimed,     // Sum of the settled unclaimed balances
+    YieldToken,         // Token the unclaimed balances are held in
+    TotalWeight,        // Sum of the active members' weights
+    RewardPerWeight,    // Member yield distributed per unit of weight so far, scaled by REWARD_SCALE
+    RewardCheckpoint(Address), // RewardPerWeight when the member's share was last settled
+    PendingRewards,     // Scaled member yield accrued in RewardPerWeight that no member has settled yet
+    WeightHistory(Address),     // Member's WeightCheckpoints, oldest first
+    LegacyMemberAmounts(u64),   // Amount each member received in an epoch distributed before the accumulator
 }
@@ -1,13 +1,17 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::{Address as _, Events, Ledger},
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
     token::{StellarAssetClient, TokenClient},
-    vec, Address, Env, IntoVal, Symbol,
+    map, vec, Address, Env, IntoVal, Map, Symbol, Vec,
 };
 
+use access_control::role::Role;
 use crate::{
     contract::{YieldDistributor, YieldDistributorClient},
+    storage,
+    storage_types::{DataKey, Distribution, Member, PauseFlag, REWARD_SCALE},
+    migrations::{LegacyDataKey, LegacyDistribution, LegacyMember},
 };
 
 struct TestFixture {
@@ -133,9 +137,14 @@ impl TestFixture {
 
     fn add_members(&self) {
         self.env.mock_all_auths();
-        self.distributor.add_member(&self.member1);
-        self.distributor.add_member(&self.member2);
-        self.distributor.add_member(&self.member3);
+        self.distributor.add_member(&self.admin, &self.member1);
+        self.distributor.add_member(&self.admin, &self.member2);
+        self.distributor.add_member(&self.admin, &self.member3);
+    }
+
+    fn claim_all(&self) -> i128 {
+        let members = vec![&self.env, self.member1.clone(), self.member2.clone(), self.member3.clone()];
+        self.distributor.claim_many(&members)
     }
 
     fn mint_tokens_to_distributor(&self, amount: i128) {
@@ -154,7 +163,7 @@ fn test_constructor() {
     assert_eq!(fixture.distributor.get_yield_controller(), fixture.yield_controller);
     assert_eq!(fixture.distributor.get_distribution_period(), fixture.distribution_period);
     
-    let members = fixture.distributor.list_members();
+    let members = fixture.distributor.list_members(&0, &10);
     assert_eq!(members.len(), 0);
     assert!(fixture.distributor.is_distribution_available());
 }
@@ -167,9 +176,9 @@ fn test_add_member() {
 
     let _ = fixture.env.events().all();
 
-    fixture.distributor.add_member(&fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
 
-    let members = fixture.distributor.list_members();
+    let members = fixture.distributor.list_members(&0, &10);
     assert_eq!(members.len(), 1);
     assert_eq!(members.get(0).unwrap(), fixture.member1);
     fixture.assert_event_with_address_data(
@@ -185,7 +194,35 @@ fn test_add_member_unauthorized() {
 
     fixture.env.mock_auths(&[]);
     
-    fixture.distributor.add_member(&fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1400)")]
+fn test_add_member_missing_role() {
+    let fixture = TestFixture::create();
+    let caller = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.add_member(&caller, &fixture.member1);
+}
+
+#[test]
+fn test_member_manager_manages_members() {
+    let fixture = TestFixture::create();
+    let manager = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.grant_role(&Role::MemberManager, &manager);
+    fixture.distributor.add_member(&manager, &fixture.member1);
+    fixture.distributor.add_member(&manager, &fixture.member2);
+    fixture.distributor.remove_member(&manager, &fixture.member1);
+    assert_eq!(fixture.distributor.list_members(&0, &10), vec![&fixture.env, fixture.member2.clone()]);
+
+    // A renounced member manager can no longer touch the member list
+    fixture.distributor.renounce_role(&manager, &Role::MemberManager);
+    assert!(!fixture.distributor.has_role(&Role::MemberManager, &manager));
+    assert!(fixture.distributor.try_add_member(&manager, &fixture.member3).is_err());
 }
 
 #[test]
@@ -195,8 +232,8 @@ fn test_add_member_already_exists() {
     
     fixture.env.mock_all_auths();
 
-    fixture.distributor.add_member(&fixture.member1);
-    fixture.distributor.add_member(&fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
 }
 
 #[test]
@@ -205,10 +242,10 @@ fn test_add_multiple_members() {
     
     fixture.env.mock_all_auths();
 
-    fixture.distributor.add_member(&fixture.member1);
-    fixture.distributor.add_member(&fixture.member2);
-    fixture.distributor.add_member(&fixture.member3);
-    let members = fixture.distributor.list_members();
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member2);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member3);
+    let members = fixture.distributor.list_members(&0, &10);
     assert_eq!(members.len(), 3);
     assert!(members.contains(&fixture.member1));
     assert!(members.contains(&fixture.member2));
@@ -221,14 +258,14 @@ fn test_remove_member() {
     
     fixture.env.mock_all_auths();
 
-    fixture.distributor.add_member(&fixture.member1);
-    fixture.distributor.add_member(&fixture.member2);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member2);
 
     let _ = fixture.env.events().all();
 
-    fixture.distributor.remove_member(&fixture.member1);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
 
-    let members = fixture.distributor.list_members();
+    let members = fixture.distributor.list_members(&0, &10);
     assert_eq!(members.len(), 1);
     assert_eq!(members.get(0).unwrap(), fixture.member2);
     fixture.assert_event_with_address_data(
@@ -243,11 +280,11 @@ fn test_remove_member_unauthorized() {
     let fixture = TestFixture::create();
 
     fixture.env.mock_all_auths();
-    fixture.distributor.add_member(&fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
 
     fixture.env.mock_auths(&[]);
     
-    fixture.distributor.remove_member(&fixture.member1);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
 }
 
 #[test]
@@ -257,7 +294,7 @@ fn test_remove_member_does_not_exist() {
     
     fixture.env.mock_all_auths();
 
-    fixture.distributor.remove_member(&fixture.member1);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
 }
 
 #[test]
@@ -392,10 +429,19 @@ fn test_yield_distribution_basic() {
     let member_share = total_amount - treasury_share;
     let per_member_amount = member_share / 3; // 3 members
 
-    assert_eq!(fixture.token_client().balance(&fixture.treasury), treasury_share);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), treasury_share);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 0);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), per_member_amount);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), treasury_share + per_member_amount * 3);
+
+    assert_eq!(fixture.claim_all(), per_member_amount * 3);
     assert_eq!(fixture.token_client().balance(&fixture.member1), per_member_amount);
     assert_eq!(fixture.token_client().balance(&fixture.member2), per_member_amount);
     assert_eq!(fixture.token_client().balance(&fixture.member3), per_member_amount);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), treasury_share);
+
+    let epoch0 = fixture.distributor.get_distribution_history().get(0).unwrap();
+    assert_eq!(epoch0.reward_per_weight, per_member_amount * REWARD_SCALE);
 }
 
 #[test]
@@ -423,7 +469,12 @@ fn test_yield_distribution_no_members() {
 
     let result = fixture.distributor.distribute_yield(&fixture.token_id, &total_amount);
     assert_eq!(result, total_amount);
-    assert_eq!(fixture.token_client().balance(&fixture.treasury), total_amount);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), total_amount);
+
+    // The member pool went to the treasury, so the record must not claim it was paid to members
+    let epoch0 = fixture.distributor.get_distribution_history().get(0).unwrap();
+    assert_eq!(epoch0.distribution_treasury, total_amount);
+    assert_eq!(epoch0.distribution_member, 0);
 }
 
 #[test]
@@ -442,7 +493,8 @@ fn test_yield_distribution_zero_treasury_share() {
     let result = fixture.distributor.distribute_yield(&fixture.token_id, &total_amount);
     assert_eq!(result, total_amount);
     let per_member_amount = total_amount / 3; // 3 members
-    assert_eq!(fixture.token_client().balance(&fixture.treasury), 0);
+    fixture.claim_all();
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), 0);
     assert_eq!(fixture.token_client().balance(&fixture.member1), per_member_amount);
     assert_eq!(fixture.token_client().balance(&fixture.member2), per_member_amount);
     assert_eq!(fixture.token_client().balance(&fixture.member3), per_member_amount);
@@ -463,7 +515,8 @@ fn test_yield_distribution_100_percent_treasury() {
 
     let result = fixture.distributor.distribute_yield(&fixture.token_id, &total_amount);
     assert_eq!(result, total_amount);
-    assert_eq!(fixture.token_client().balance(&fixture.treasury), total_amount);
+    assert_eq!(fixture.claim_all(), 0);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), total_amount);
     assert_eq!(fixture.token_client().balance(&fixture.member1), 0);
     assert_eq!(fixture.token_client().balance(&fixture.member2), 0);
     assert_eq!(fixture.token_client().balance(&fixture.member3), 0);
@@ -492,19 +545,19 @@ fn test_member_management_cycle() {
     
     fixture.env.mock_all_auths();
 
-    fixture.distributor.add_member(&fixture.member1);
-    fixture.distributor.add_member(&fixture.member2);
-    assert_eq!(fixture.distributor.list_members().len(), 2);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member2);
+    assert_eq!(fixture.distributor.list_members(&0, &10).len(), 2);
 
-    fixture.distributor.remove_member(&fixture.member1);
-    assert_eq!(fixture.distributor.list_members().len(), 1);
-    assert_eq!(fixture.distributor.list_members().get(0).unwrap(), fixture.member2);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
+    assert_eq!(fixture.distributor.list_members(&0, &10).len(), 1);
+    assert_eq!(fixture.distributor.list_members(&0, &10).get(0).unwrap(), fixture.member2);
 
-    fixture.distributor.add_member(&fixture.member1);
-    assert_eq!(fixture.distributor.list_members().len(), 2);
-    fixture.distributor.remove_member(&fixture.member1);
-    fixture.distributor.remove_member(&fixture.member2);
-    assert_eq!(fixture.distributor.list_members().len(), 0);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    assert_eq!(fixture.distributor.list_members(&0, &10).len(), 2);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member2);
+    assert_eq!(fixture.distributor.list_members(&0, &10).len(), 0);
 }
 
 #[test]
@@ -572,7 +625,8 @@ fn test_small_amount_distribution() {
 
     let result = fixture.distributor.distribute_yield(&fixture.token_id, &small_amount);
     assert_eq!(result, small_amount);
-    let total_distributed = fixture.token_client().balance(&fixture.treasury) +
+    fixture.claim_all();
+    let total_distributed = fixture.distributor.get_unclaimed(&fixture.treasury) +
                           fixture.token_client().balance(&fixture.member1) +
                           fixture.token_client().balance(&fixture.member2) +
                           fixture.token_client().balance(&fixture.member3);
@@ -592,7 +646,7 @@ fn test_large_amount_distribution() {
     let result = fixture.distributor.distribute_yield(&fixture.token_id, &large_amount);
     assert_eq!(result, large_amount);
     let treasury_share = (large_amount * fixture.treasury_share_bps as i128) / 10000;
-    assert_eq!(fixture.token_client().balance(&fixture.treasury), treasury_share);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), treasury_share);
 }
 
 // ============================================================================
@@ -699,4 +753,622 @@ fn test_advance_epoch_unauthorized() {
     fixture.env.mock_auths(&[]);
 
     fixture.distributor.advance_epoch();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let fixture = TestFixture::create();
+    let pauser = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.grant_role(&Role::Pauser, &pauser);
+    assert!(fixture.distributor.has_role(&Role::Pauser, &pauser));
+
+    fixture.distributor.pause(&pauser, &PauseFlag::Distribution);
+    assert!(fixture.distributor.is_paused(&PauseFlag::Distribution));
+    assert!(!fixture.distributor.is_paused(&PauseFlag::Mint));
+
+    fixture.distributor.unpause(&PauseFlag::Distribution);
+    assert!(!fixture.distributor.is_paused(&PauseFlag::Distribution));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1202)")]
+fn test_distribute_yield_paused() {
+    let fixture = TestFixture::create();
+    let total_amount = 10000i128;
+
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(total_amount);
+    fixture.env.mock_all_auths_allowing_non_root_auth();
+
+    fixture.distributor.grant_role(&Role::Pauser, &fixture.admin);
+    fixture.distributor.pause(&fixture.admin, &PauseFlag::Distribution);
+
+    fixture.distributor.distribute_yield(&fixture.token_id, &total_amount);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1400)")]
+fn test_pause_without_pauser_role() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.pause(&fixture.admin, &PauseFlag::Distribution);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_unpause_requires_owner() {
+    let fixture = TestFixture::create();
+    let pauser = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+    fixture.distributor.grant_role(&Role::Pauser, &pauser);
+    fixture.distributor.pause(&pauser, &PauseFlag::Distribution);
+
+    fixture.env.mock_auths(&[MockAuth {
+        address: &pauser,
+        invoke: &MockAuthInvoke {
+            contract: &fixture.distributor.address,
+            fn_name: "unpause",
+            args: (PauseFlag::Distribution,).into_val(&fixture.env),
+            sub_invokes: &[],
+        },
+    }]);
+    fixture.distributor.unpause(&PauseFlag::Distribution);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let fixture = TestFixture::create();
+    let new_owner = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.propose_owner(&new_owner);
+    assert_eq!(fixture.distributor.get_pending_owner(), Some(new_owner.clone()));
+    assert!(fixture.distributor.has_role(&Role::Owner, &fixture.owner));
+
+    fixture.distributor.accept_owner();
+    assert!(fixture.distributor.has_role(&Role::Owner, &new_owner));
+    assert!(!fixture.distributor.has_role(&Role::Owner, &fixture.owner));
+}
+
+/// Lay the contract's storage out as a version 1 deployment: owner and admin under the legacy keys,
+/// a list of every member address copied into each distribution, members without a weight, no
+/// accumulator and no schema version
+fn simulate_v1_storage(env: &Env, contract: &Address) -> (Address, Address) {
+    let legacy_owner = Address::generate(env);
+    let legacy_admin = Address::generate(env);
+    env.as_contract(contract, || {
+        env.storage().instance().set(&LegacyDataKey::Owner, &legacy_owner);
+        env.storage().instance().set(&LegacyDataKey::Admin, &legacy_admin);
+
+        // Version 1 paid members out during the distribution and kept no per-member yield entries
+        let members = storage::read_members_page(env, 0, u32::MAX);
+        for (index, address) in members.iter().enumerate() {
+            let key = DataKey::Member(address.clone());
+            let member: Member = env.storage().persistent().get(&key).unwrap();
+            let legacy = LegacyMember {
+                address: member.address,
+                active: member.active,
+                joined_at: member.joined_at,
+            };
+            env.storage().persistent().set(&key, &legacy);
+            env.storage().persistent().remove(&DataKey::MemberAt(index as u32));
+            env.storage().persistent().remove(&DataKey::MemberIndex(address.clone()));
+            env.storage().persistent().remove(&DataKey::RewardCheckpoint(address.clone()));
+            env.storage().persistent().remove(&DataKey::WeightHistory(address.clone()));
+            env.storage().persistent().remove(&DataKey::Unclaimed(address));
+        }
+        env.storage().persistent().set(&LegacyDataKey::Members, &members);
+        env.storage().persistent().remove(&DataKey::TotalUnclaimed);
+        for key in [
+            DataKey::MemberCount,
+            DataKey::TotalWeight,
+            DataKey::RewardPerWeight,
+            DataKey::PendingRewards,
+            DataKey::YieldToken,
+        ] {
+            env.storage().instance().remove(&key);
+        }
+
+        for epoch in 0..=storage::read_epoch_current(env) {
+            let key = DataKey::Distribution(epoch);
+            let distribution: Distribution = env.storage().persistent().get(&key).unwrap();
+            let legacy = LegacyDistribution {
+                distribution_end_timestamp: distribution.distribution_end_timestamp,
+                distribution_start_timestamp: distribution.distribution_start_timestamp,
+                distribution_total: distribution.distribution_total,
+                distribution_treasury: distribution.distribution_treasury,
+                distribution_member: distribution.distribution_member,
+                members: members.clone(),
+                is_processed: distribution.is_processed,
+                epoch: distribution.epoch,
+            };
+            env.storage().persistent().set(&key, &legacy);
+        }
+        versioning::write_version(env, 1);
+    });
+    (legacy_owner, legacy_admin)
+}
+
+#[test]
+fn test_version_after_deploy() {
+    let fixture = TestFixture::create();
+
+    assert_eq!(fixture.distributor.version(), 2);
+}
+
+#[test]
+fn test_migrate_v1_to_latest() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(18000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+    let (legacy_owner, legacy_admin) = simulate_v1_storage(&fixture.env, &fixture.distributor.address);
+    assert_eq!(fixture.distributor.version(), 1);
+
+    fixture.env.mock_all_auths();
+    assert_eq!(fixture.distributor.migrate(), 2);
+
+    assert_eq!(fixture.distributor.version(), 2);
+    assert!(fixture.distributor.has_role(&Role::Owner, &legacy_owner));
+    assert!(fixture.distributor.has_role(&Role::Admin, &legacy_admin));
+    fixture.env.as_contract(&fixture.distributor.address, || {
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Owner));
+        assert!(!fixture.env.storage().instance().has(&LegacyDataKey::Admin));
+        assert!(!fixture.env.storage().persistent().has(&LegacyDataKey::Members));
+    });
+
+    // The member list becomes the index and every member gets the default weight
+    assert_eq!(fixture.distributor.get_member_count(), 3);
+    assert_eq!(
+        fixture.distributor.list_members(&0, &10),
+        vec![&fixture.env, fixture.member1.clone(), fixture.member2.clone(), fixture.member3.clone()]
+    );
+    assert_eq!(fixture.distributor.get_member_weight(&fixture.member1), 1);
+
+    // Past epochs keep their totals and the equal split they paid, but no yield per unit of weight
+    let epoch0 = fixture.distributor.get_distribution_history().get(0).unwrap();
+    assert_eq!(epoch0.distribution_member, 8100);
+    assert_eq!(epoch0.member_count, 3);
+    assert_eq!(epoch0.reward_per_weight, 0);
+    assert_eq!(epoch0.fee_amounts.len(), 0);
+    assert_eq!(fixture.distributor.get_member_distribution(&0, &fixture.member1), 2700);
+
+    // Later distributions accrue by weight
+    fixture.distributor.set_member_weight(&legacy_admin, &fixture.member3, &2);
+    fixture.distributor.remove_member(&legacy_admin, &fixture.member2);
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), 2700);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member3), 5400);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member3), 5400);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member2), 0);
+
+    // Migrating again is a no-op
+    assert_eq!(fixture.distributor.migrate(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_migrate_unauthorized() {
+    let fixture = TestFixture::create();
+    simulate_v1_storage(&fixture.env, &fixture.distributor.address);
+
+    fixture.env.mock_auths(&[]);
+    fixture.distributor.migrate();
+}
+
+#[test]
+fn test_weighted_distribution() {
+    let fixture = TestFixture::create();
+    let total_amount = 12000i128;
+
+    fixture.add_members();
+    fixture.distributor.set_member_weight(&fixture.admin, &fixture.member2, &2);
+    fixture.distributor.set_member_weight(&fixture.admin, &fixture.member3, &3);
+    fixture.mint_tokens_to_distributor(total_amount);
+
+    fixture.distributor.distribute_yield(&fixture.token_id, &total_amount);
+
+    // 10% to the treasury, the remaining 10800 split 1:2:3
+    fixture.claim_all();
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), 1200);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 1800);
+    assert_eq!(fixture.token_client().balance(&fixture.member2), 3600);
+    assert_eq!(fixture.token_client().balance(&fixture.member3), 5400);
+
+    // A member's share of the epoch is their weight times the yield per unit of weight
+    let epoch0 = fixture.distributor.get_distribution_history().get(0).unwrap();
+    assert_eq!(epoch0.reward_per_weight, 1800 * REWARD_SCALE);
+}
+
+#[test]
+fn test_readded_member_keeps_weight() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.distributor.set_member_weight(&fixture.admin, &fixture.member2, &3);
+
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member2);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member2);
+    assert_eq!(fixture.distributor.get_member_weight(&fixture.member2), 3);
+
+    fixture.mint_tokens_to_distributor(10000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &10000);
+
+    // 10% to the treasury, the remaining 9000 split 1:3:1
+    fixture.claim_all();
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 1800);
+    assert_eq!(fixture.token_client().balance(&fixture.member2), 5400);
+    assert_eq!(fixture.token_client().balance(&fixture.member3), 1800);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1203)")]
+fn test_set_member_weight_zero() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+
+    fixture.distributor.set_member_weight(&fixture.admin, &fixture.member1, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1201)")]
+fn test_set_member_weight_unknown_member() {
+    let fixture = TestFixture::create();
+    let stranger = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+
+    fixture.distributor.set_member_weight(&fixture.admin, &stranger, &2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1400)")]
+fn test_set_member_weight_missing_role() {
+    let fixture = TestFixture::create();
+    let caller = Address::generate(&fixture.env);
+    fixture.add_members();
+
+    fixture.distributor.set_member_weight(&caller, &fixture.member1, &2);
+}
+
+#[test]
+fn test_claim_on_behalf_of_member() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(9000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    // Claiming needs no signature: the yield only ever goes to the member
+    fixture.env.mock_auths(&[]);
+    assert_eq!(fixture.distributor.claim(&fixture.member1), 2700);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 2700);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), 0);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), 900 + 5400);
+
+    // Nothing left to claim
+    assert_eq!(fixture.distributor.claim(&fixture.member1), 0);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 2700);
+}
+
+#[test]
+fn test_unclaimed_accrues_across_epochs() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(18000);
+    fixture.env.mock_all_auths_allowing_non_root_auth();
+
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    // Removed members keep what they were credited
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), 5400);
+    assert_eq!(fixture.distributor.claim(&fixture.member1), 5400);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 5400);
+}
+
+#[test]
+fn test_claim_before_any_distribution() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+
+    assert_eq!(fixture.distributor.get_yield_token(), None);
+    assert_eq!(fixture.distributor.claim(&fixture.member1), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1202)")]
+fn test_claim_paused() {
+    let fixture = TestFixture::create();
+    let pauser = Address::generate(&fixture.env);
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(9000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    fixture.distributor.grant_role(&Role::Pauser, &pauser);
+    fixture.distributor.pause(&pauser, &PauseFlag::Distribution);
+
+    fixture.distributor.claim(&fixture.member1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1204)")]
+fn test_distribute_other_token_with_unclaimed_balances() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(9000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    let other_token = fixture
+        .env
+        .register_stellar_asset_contract_v2(fixture.token_admin.clone())
+        .address();
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&other_token, &9000);
+}
+
+#[test]
+fn test_list_members_pagination() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+
+    assert_eq!(fixture.distributor.get_member_count(), 3);
+    assert_eq!(
+        fixture.distributor.list_members(&0, &2),
+        vec![&fixture.env, fixture.member1.clone(), fixture.member2.clone()]
+    );
+    assert_eq!(fixture.distributor.list_members(&2, &2), vec![&fixture.env, fixture.member3.clone()]);
+    assert_eq!(fixture.distributor.list_members(&3, &2).len(), 0);
+    assert_eq!(fixture.distributor.list_members(&1, &u32::MAX).len(), 2);
+}
+
+#[test]
+fn test_remove_member_keeps_index_dense() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+
+    // The last member moves into the removed member's slot
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member1);
+    assert_eq!(fixture.distributor.get_member_count(), 2);
+    assert!(!fixture.distributor.is_member(&fixture.member1));
+    assert_eq!(
+        fixture.distributor.list_members(&0, &10),
+        vec![&fixture.env, fixture.member3.clone(), fixture.member2.clone()]
+    );
+    assert_eq!(fixture.distributor.get_distribution_info().member_count, 2);
+
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member2);
+    fixture.distributor.add_member(&fixture.admin, &fixture.member1);
+    assert!(fixture.distributor.is_member(&fixture.member1));
+    assert_eq!(
+        fixture.distributor.list_members(&0, &10),
+        vec![&fixture.env, fixture.member3.clone(), fixture.member1.clone()]
+    );
+}
+
+#[test]
+fn test_migrate_resumes_in_batches() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.env.mock_all_auths();
+    for _ in 0..30 {
+        fixture.distributor.advance_epoch();
+        fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    }
+
+    simulate_v1_storage(&fixture.env, &fixture.distributor.address);
+
+    // 3 members and 31 epochs take two batches
+    assert_eq!(fixture.distributor.migrate(), 1);
+    assert_eq!(fixture.distributor.version(), 1);
+    assert!(fixture.distributor.try_claim(&fixture.member1).is_err());
+    assert!(fixture.distributor.try_advance_epoch().is_err());
+    assert_eq!(fixture.distributor.migrate(), 2);
+    assert_eq!(fixture.distributor.migrate(), 2);
+    fixture.distributor.advance_epoch();
+
+    let history = fixture.distributor.get_distribution_history();
+    assert_eq!(history.len(), 31);
+    assert!(history.iter().all(|distribution| distribution.fee_amounts.is_empty()));
+    assert_eq!(fixture.distributor.get_distribution_info().epoch, 31);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1206)")]
+fn test_distribute_during_migration() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.env.mock_all_auths();
+    for _ in 0..30 {
+        fixture.distributor.advance_epoch();
+        fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    }
+    fixture.mint_tokens_to_distributor(9000);
+
+    simulate_v1_storage(&fixture.env, &fixture.distributor.address);
+    assert_eq!(fixture.distributor.migrate(), 1);
+
+    // Only part of the members and epochs have been rewritten
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+}
+
+#[test]
+fn test_distribution_does_not_write_member_balances() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(9000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    // Shares stay in the accumulator until a member claims or their weight changes
+    fixture.env.as_contract(&fixture.distributor.address, || {
+        assert!(!fixture.env.storage().persistent().has(&DataKey::Unclaimed(fixture.member1.clone())));
+    });
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), 2700);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), 900 + 8100);
+}
+
+#[test]
+fn test_weight_change_applies_to_later_distributions() {
+    let fixture = TestFixture::create();
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(18000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    // The first epoch is settled at the old weight, the second split 1:1:4
+    fixture.distributor.set_member_weight(&fixture.admin, &fixture.member3, &4);
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    assert_eq!(fixture.claim_all(), 16200);
+    assert_eq!(fixture.token_client().balance(&fixture.member1), 2700 + 1350);
+    assert_eq!(fixture.token_client().balance(&fixture.member2), 2700 + 1350);
+    assert_eq!(fixture.token_client().balance(&fixture.member3), 2700 + 5400);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), 1800); // The treasury's shares
+
+    // Each epoch keeps the amounts of the weights it was distributed under
+    assert_eq!(fixture.distributor.get_member_distribution(&0, &fixture.member3), 2700);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member3), 5400);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member1), 1350);
+}
+
+#[test]
+fn test_member_distribution_per_epoch() {
+    let fixture = TestFixture::create();
+    let late_member = Address::generate(&fixture.env);
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(18000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    // Members who leave or join only show up in the epochs they were active for
+    fixture.distributor.remove_member(&fixture.admin, &fixture.member2);
+    fixture.distributor.add_member(&fixture.admin, &late_member);
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    assert_eq!(fixture.distributor.get_member_distribution(&0, &fixture.member2), 2700);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member2), 0);
+    assert_eq!(fixture.distributor.get_member_distribution(&0, &late_member), 0);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &late_member), 2700);
+    assert_eq!(fixture.distributor.get_member_distribution(&1, &fixture.member1), 2700);
+
+    // The current epoch has not been distributed yet
+    assert_eq!(fixture.distributor.get_member_distribution(&2, &fixture.member1), 0);
+}
+
+#[test]
+fn test_new_member_only_accrues_later_distributions() {
+    let fixture = TestFixture::create();
+    let late_member = Address::generate(&fixture.env);
+    fixture.add_members();
+    fixture.mint_tokens_to_distributor(18000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+
+    fixture.distributor.add_member(&fixture.admin, &late_member);
+    assert_eq!(fixture.distributor.get_unclaimed(&late_member), 0);
+
+    fixture.env.ledger().set_timestamp(fixture.env.ledger().timestamp() + fixture.distribution_period);
+    fixture.distributor.distribute_yield(&fixture.token_id, &9000);
+    assert_eq!(fixture.distributor.get_unclaimed(&late_member), 2025);
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.member1), 2700 + 2025);
+}
+
+#[test]
+fn test_fee_recipients_distribution() {
+    let fixture = TestFixture::create();
+    let insurance = Address::generate(&fixture.env);
+    let development = Address::generate(&fixture.env);
+    fixture.add_members();
+
+    let recipients = map![&fixture.env, (insurance.clone(), 500u32), (development.clone(), 1500u32)];
+    fixture.distributor.set_fee_recipients(&recipients);
+    assert_eq!(fixture.distributor.get_fee_recipients(), recipients);
+
+    fixture.mint_tokens_to_distributor(10000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &10000);
+
+    // 10% treasury, 5% insurance and 15% development before the members split the remaining 70%
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), 1000);
+    assert_eq!(fixture.distributor.get_unclaimed(&insurance), 500);
+    assert_eq!(fixture.distributor.get_unclaimed(&development), 1500);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), 3000 + 6999);
+
+    let epoch0 = fixture.distributor.get_distribution_history().get(0).unwrap();
+    assert_eq!(epoch0.distribution_member, 7000);
+    assert_eq!(epoch0.fee_amounts.get(insurance), Some(500));
+    assert_eq!(epoch0.fee_amounts.get(development), Some(1500));
+}
+
+#[test]
+fn test_fee_recipients_without_members() {
+    let fixture = TestFixture::create();
+    let insurance = Address::generate(&fixture.env);
+    fixture.env.mock_all_auths();
+    fixture.distributor.set_fee_recipients(&map![&fixture.env, (insurance.clone(), 2000u32)]);
+
+    fixture.mint_tokens_to_distributor(10000);
+    fixture.distributor.distribute_yield(&fixture.token_id, &10000);
+
+    // The treasury takes the member pool, the fee recipients keep their shares
+    assert_eq!(fixture.distributor.get_unclaimed(&fixture.treasury), 8000);
+    assert_eq!(fixture.distributor.get_unclaimed(&insurance), 2000);
+}
+
+#[test]
+fn test_fee_recipients_claim_their_shares() {
+    let fixture = TestFixture::create();
+    let insurance = Address::generate(&fixture.env);
+    fixture.add_members();
+    fixture.distributor.set_fee_recipients(&map![&fixture.env, (insurance.clone(), 1000u32)]);
+    fixture.mint_tokens_to_distributor(10000);
+
+    // Nothing is transferred during the distribution, so a recipient that can't receive can't revert it
+    fixture.distributor.distribute_yield(&fixture.token_id, &10000);
+    assert_eq!(fixture.token_client().balance(&fixture.treasury), 0);
+    assert_eq!(fixture.token_client().balance(&insurance), 0);
+    assert_eq!(fixture.token_client().balance(&fixture.distributor.address), 10000);
+
+    fixture.env.mock_auths(&[]);
+    assert_eq!(fixture.distributor.claim(&fixture.treasury), 1000);
+    assert_eq!(fixture.distributor.claim(&insurance), 1000);
+    assert_eq!(fixture.token_client().balance(&fixture.treasury), 1000);
+    assert_eq!(fixture.token_client().balance(&insurance), 1000);
+    assert_eq!(fixture.distributor.get_unclaimed(&insurance), 0);
+    assert_eq!(fixture.distributor.get_total_unclaimed(), 7999);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1205)")]
+fn test_fee_recipients_exceed_total() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+
+    // Together with the 10% treasury share this is 100.01%
+    let recipients = map![
+        &fixture.env,
+        (Address::generate(&fixture.env), 5000u32),
+        (Address::generate(&fixture.env), 4001u32)
+    ];
+    fixture.distributor.set_fee_recipients(&recipients);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1205)")]
+fn test_treasury_share_exceeds_total_with_fee_recipients() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_all_auths();
+    fixture.distributor.set_fee_recipients(&map![&fixture.env, (Address::generate(&fixture.env), 3000u32)]);
+
+    fixture.distributor.set_treasury_share(&7001u32);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_fee_recipients_unauthorized() {
+    let fixture = TestFixture::create();
+    fixture.env.mock_auths(&[]);
+
+    fixture.distributor.set_fee_recipients(&map![&fixture.env, (Address::generate(&fixture.env), 500u32)]);
+}
@@ -11,8 +11,10 @@ async function claimYield() {
   
   // Contract addresses (replace with actual deployed addresses)
   const LENDING_YIELD_CONTROLLER_ADDRESS = 'CBT3CXNXEXDRMNSLA2HQKKEWHR5TEOGYUMPA3UXQXCU2VXQQ67B2MZML';
+  const USDC_TOKEN_ADDRESS = 'CAQCFVLOBK5GIULPNZRGATJJMIZL5BSP7X5YJVMGCPTUEPFM4AVSRCJU';
+  const PROTOCOL = 'BC_LA'; // Blend Capital Lending Adapter
   
-  // Admin keypair (only admin can claim yield)
+  // Admin keypair (any keeper may claim once distribution is available and earns the keeper bounty)
   const adminKeypair = Keypair.random(); // In production, load the admin keypair securely
   const adminPublicKey = adminKeypair.publicKey();
   
@@ -32,7 +34,7 @@ async function claimYield() {
     
     // Step 2: Check current available yield
     console.log('📊 Checking available yield...');
-    const yieldResult = await lendingClient.get_yield();
+    const yieldResult = await lendingClient.get_yield({ protocol: PROTOCOL, asset: USDC_TOKEN_ADDRESS });
     const availableYield = yieldResult.result;
     
     console.log(`Available yield: ${Number(availableYield) / 10_000_000} tokens`);
@@ -88,7 +90,8 @@ async function claimYield() {
     
     // Step 6: Claim the yield
     console.log('🎯 Claiming yield...');
-    const claimTransaction = await lendingClient.claim_yield();
+    // Runs harvest -> recompound -> finalize in one transaction, resuming any pending harvest
+    const claimTransaction = await lendingClient.claim_yield({ caller: adminPublicKey, protocol: PROTOCOL, asset: USDC_TOKEN_ADDRESS });
     
     // Sign and submit the claim transaction
     const signedClaim = await claimTransaction.signAuthEntries({
@@ -113,8 +116,9 @@ async function claimYield() {
     if (claimStatus.status === 'SUCCESS') {
       console.log('🎉 Yield claimed successfully!');
       
-      // Step 8: Get the claimed amount from the transaction result
-      const claimedAmount = claimTransaction.result;
+      // Step 8: Get the stage reached and claimed amount from the transaction result
+      const { stage, amount: claimedAmount } = claimTransaction.result;
+      console.log(`🏁 Stage reached: ${stage}`);
       console.log(`💰 Claimed yield amount: ${Number(claimedAmount) / 10_000_000} tokens`);
       
       // Step 9: Get distribution details
@@ -177,6 +181,8 @@ async function checkClaimStatus() {
   const rpcUrl = 'https://soroban-testnet.stellar.org';
   const networkPassphrase = 'Test SDF Network ; September 2015';
   const LENDING_YIELD_CONTROLLER_ADDRESS = 'CBT3CXNXEXDRMNSLA2HQKKEWHR5TEOGYUMPA3UXQXCU2VXQQ67B2MZML';
+  const USDC_TOKEN_ADDRESS = 'CAQCFVLOBK5GIULPNZRGATJJMIZL5BSP7X5YJVMGCPTUEPFM4AVSRCJU';
+  const PROTOCOL = 'BC_LA'; // Blend Capital Lending Adapter
   
   const userKeypair = Keypair.random();
   const userPublicKey = userKeypair.publicKey();
@@ -192,7 +198,7 @@ async function checkClaimStatus() {
     });
     
     // Get current yield
-    const yield_result = await lendingClient.get_yield();
+    const yield_result = await lendingClient.get_yield({ protocol: PROTOCOL, asset: USDC_TOKEN_ADDRESS });
     const availableYield = yield_result.result;
     
     // Get distributor info
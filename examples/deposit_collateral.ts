@@ -92,6 +92,7 @@ async function depositCollateral() {
       user: userPublicKey,
       asset: USDC_TOKEN_ADDRESS,
       amount: depositAmount,
+      min_out: undefined, // optional lower bound on cUSD issued
     });
     
     // Sign and submit the deposit transaction
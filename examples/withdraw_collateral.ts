@@ -112,6 +112,8 @@ async function withdrawCollateral() {
       user: userPublicKey,
       asset: USDC_TOKEN_ADDRESS,
       amount: withdrawAmount,
+      max_burn: undefined, // optional upper bound on cUSD burned
+      allow_partial: false, // set to withdraw only what the pool's liquidity allows
     });
     
     // Sign and submit the withdrawal transaction
@@ -0,0 +1,14 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the access control package. Access control errors start at 1400
+pub enum AccessControlError {
+    /// The account does not hold the required role
+    MissingRole = 1400,
+    /// The role can only be moved to a new holder, not revoked or renounced
+    InvalidRole = 1401,
+    /// No transfer of the role is pending
+    NoPendingTransfer = 1402,
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{Address, Env, Symbol};
+use crate::role::Role;
+
+pub struct AccessControlEvents {}
+
+impl AccessControlEvents {
+    pub fn role_granted(e: &Env, role: Role, account: Address) {
+        let topics = (Symbol::new(e, "role_granted"), role);
+        e.events().publish(topics, account);
+    }
+
+    pub fn role_revoked(e: &Env, role: Role, account: Address) {
+        let topics = (Symbol::new(e, "role_revoked"), role);
+        e.events().publish(topics, account);
+    }
+
+    pub fn transfer_proposed(e: &Env, role: Role, account: Address) {
+        let topics = (Symbol::new(e, "transfer_proposed"), role);
+        e.events().publish(topics, account);
+    }
+
+    pub fn transfer_cancelled(e: &Env, role: Role, account: Address) {
+        let topics = (Symbol::new(e, "transfer_cancelled"), role);
+        e.events().publish(topics, account);
+    }
+}
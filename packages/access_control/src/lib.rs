@@ -0,0 +1,137 @@
+#![no_std]
+pub mod error;
+pub mod events;
+pub mod role;
+mod storage;
+
+use soroban_sdk::{panic_with_error, Address, Env};
+use crate::error::AccessControlError;
+use crate::events::AccessControlEvents;
+use crate::role::Role;
+
+/// Check whether `account` holds `role`
+pub fn has_role(e: &Env, role: Role, account: &Address) -> bool {
+    if role.is_unique() {
+        storage::read_holder(e, role).as_ref() == Some(account)
+    } else {
+        storage::has_member(e, role, account)
+    }
+}
+
+/// Fetch the holder of a role that only one account can hold
+pub fn role_holder(e: &Env, role: Role) -> Option<Address> {
+    storage::read_holder(e, role)
+}
+
+/// Grant `role` to `account`. A role only one account can hold is moved from its previous holder,
+/// and any pending transfer of it is dropped. Callers are responsible for authorization.
+pub fn grant_role(e: &Env, role: Role, account: &Address) {
+    if role.is_unique() {
+        if let Some(previous) = storage::read_holder(e, role) {
+            if previous == *account {
+                return;
+            }
+            AccessControlEvents::role_revoked(e, role, previous);
+        }
+        // A transfer proposed by or to the previous holder must not outlive the replacement
+        if let Some(pending) = storage::read_pending(e, role) {
+            storage::remove_pending(e, role);
+            AccessControlEvents::transfer_cancelled(e, role, pending);
+        }
+        storage::write_holder(e, role, account);
+    } else {
+        if storage::has_member(e, role, account) {
+            return;
+        }
+        storage::write_member(e, role, account);
+    }
+    AccessControlEvents::role_granted(e, role, account.clone());
+}
+
+/// Revoke `role` from `account`. Callers are responsible for authorization.
+///
+/// ### Panics
+/// If `role` can only be held by one account; it can only be moved with `grant_role`
+pub fn revoke_role(e: &Env, role: Role, account: &Address) {
+    if role.is_unique() {
+        panic_with_error!(e, AccessControlError::InvalidRole);
+    }
+    if storage::has_member(e, role, account) {
+        storage::remove_member(e, role, account);
+        AccessControlEvents::role_revoked(e, role, account.clone());
+    }
+}
+
+/// Give up a role held by `account`
+///
+/// ### Panics
+/// If `account` does not authorize the call
+/// If `role` can only be held by one account
+pub fn renounce_role(e: &Env, role: Role, account: &Address) {
+    account.require_auth();
+    revoke_role(e, role, account);
+}
+
+/// Authorize `account` and check it holds `role`
+///
+/// ### Panics
+/// If `account` does not authorize the call or does not hold `role`
+pub fn require_role(e: &Env, role: Role, account: &Address) {
+    require_any_role(e, &[role], account);
+}
+
+/// Authorize `account` and check it holds at least one of `roles`
+///
+/// ### Panics
+/// If `account` does not authorize the call or holds none of `roles`
+pub fn require_any_role(e: &Env, roles: &[Role], account: &Address) {
+    account.require_auth();
+    if !roles.iter().any(|role| has_role(e, *role, account)) {
+        panic_with_error!(e, AccessControlError::MissingRole);
+    }
+}
+
+/// Propose `account` as the next holder of a role only one account can hold. The current holder
+/// keeps the role until `account` calls `accept_transfer`. Callers are responsible for authorization.
+///
+/// ### Panics
+/// If `role` can be held by many accounts
+pub fn propose_transfer(e: &Env, role: Role, account: &Address) {
+    if !role.is_unique() {
+        panic_with_error!(e, AccessControlError::InvalidRole);
+    }
+    storage::write_pending(e, role, account);
+    AccessControlEvents::transfer_proposed(e, role, account.clone());
+}
+
+/// Fetch the account proposed to take over a role, if a transfer is pending
+pub fn pending_holder(e: &Env, role: Role) -> Option<Address> {
+    storage::read_pending(e, role)
+}
+
+/// Move a role to the account proposed for it and return that account
+///
+/// ### Panics
+/// If no transfer of `role` is pending
+/// If the proposed account does not authorize the call
+pub fn accept_transfer(e: &Env, role: Role) -> Address {
+    let Some(pending) = storage::read_pending(e, role) else {
+        panic_with_error!(e, AccessControlError::NoPendingTransfer);
+    };
+    pending.require_auth();
+    storage::remove_pending(e, role);
+    grant_role(e, role, &pending);
+    pending
+}
+
+/// Drop a pending transfer of `role`. Callers are responsible for authorization.
+///
+/// ### Panics
+/// If no transfer of `role` is pending
+pub fn cancel_transfer(e: &Env, role: Role) {
+    let Some(pending) = storage::read_pending(e, role) else {
+        panic_with_error!(e, AccessControlError::NoPendingTransfer);
+    };
+    storage::remove_pending(e, role);
+    AccessControlEvents::transfer_cancelled(e, role, pending);
+}
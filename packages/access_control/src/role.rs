@@ -0,0 +1,26 @@
+use soroban_sdk::contracttype;
+
+/// Named roles shared by the coopstable contracts
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    /// Upgrades the contract and manages every other role
+    Owner,
+    /// Manages operational settings
+    Admin,
+    /// Pauses operations during an incident
+    Pauser,
+    /// Drives the harvest pipeline at any time
+    Keeper,
+    /// Adds and removes yield distribution members
+    MemberManager,
+    /// Cancels queued timelock operations
+    Guardian,
+}
+
+impl Role {
+    /// Whether the role is held by exactly one account
+    pub fn is_unique(&self) -> bool {
+        matches!(self, Role::Owner | Role::Admin)
+    }
+}
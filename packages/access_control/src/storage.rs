@@ -0,0 +1,45 @@
+use soroban_sdk::{contracttype, Address, Env};
+use crate::role::Role;
+
+#[derive(Clone)]
+#[contracttype]
+enum AccessControlKey {
+    /// The account holding a role only one account can hold
+    Holder(Role),
+    /// Set while an account holds a role
+    Member(Role, Address),
+    /// The account proposed to take over a role only one account can hold
+    Pending(Role),
+}
+
+pub fn read_holder(e: &Env, role: Role) -> Option<Address> {
+    e.storage().instance().get(&AccessControlKey::Holder(role))
+}
+
+pub fn write_holder(e: &Env, role: Role, account: &Address) {
+    e.storage().instance().set(&AccessControlKey::Holder(role), account);
+}
+
+pub fn has_member(e: &Env, role: Role, account: &Address) -> bool {
+    e.storage().instance().has(&AccessControlKey::Member(role, account.clone()))
+}
+
+pub fn write_member(e: &Env, role: Role, account: &Address) {
+    e.storage().instance().set(&AccessControlKey::Member(role, account.clone()), &true);
+}
+
+pub fn remove_member(e: &Env, role: Role, account: &Address) {
+    e.storage().instance().remove(&AccessControlKey::Member(role, account.clone()));
+}
+
+pub fn read_pending(e: &Env, role: Role) -> Option<Address> {
+    e.storage().instance().get(&AccessControlKey::Pending(role))
+}
+
+pub fn write_pending(e: &Env, role: Role, account: &Address) {
+    e.storage().instance().set(&AccessControlKey::Pending(role), account);
+}
+
+pub fn remove_pending(e: &Env, role: Role) {
+    e.storage().instance().remove(&AccessControlKey::Pending(role));
+}
@@ -129,6 +129,20 @@ pub fn get_balance(e: &Env, user: Address, asset: Address) -> i128 {
     0
 }
 
+/// The user's balance, limited by the reserve's liquidity (supplied minus borrowed)
+pub fn max_withdrawable(e: &Env, user: Address, asset: Address) -> i128 {
+    let pool_id: Address = storage::read_lend_pool_id(e);
+    let pool_client = PoolClient::new(e, &pool_id);
+    let reserve = pool_client.get_reserve(&asset);
+
+    let supplied = (reserve.data.b_supply * reserve.data.b_rate) / SCALAR_12;
+    // Debt rounds up, as in the pool, so liquidity is never overstated
+    let borrowed = (reserve.data.d_supply * reserve.data.d_rate + SCALAR_12 - 1) / SCALAR_12;
+    let liquidity = (supplied - borrowed).max(0);
+
+    liquidity.min(get_balance(e, user, asset))
+}
+
 fn get_reserve_token_id(e: &Env, asset: Address) -> Option<u32> {
 
     let pool_id: Address = storage::read_lend_pool_id(e);
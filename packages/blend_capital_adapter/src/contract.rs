@@ -110,6 +110,8 @@ impl LendingAdapter for BlendCapitalAdapter {
     fn get_total_deposited(e: &Env, asset: Address) -> i128 { storage::read_deposit(e, &storage::get_yield_controller(e), &asset) }
     
     fn get_balance(e: &Env, user: Address, asset: Address) -> i128 { adapter::get_balance(e, user, asset) }
+
+    fn max_withdrawable(e: &Env, asset: Address) -> i128 { adapter::max_withdrawable(e, storage::get_yield_controller(e), asset) }
     
     fn get_apy(env: &Env, asset: Address) -> u32 { adapter::get_apy(env, asset) }
     
@@ -1,6 +1,6 @@
 #![no_std]
 mod constants;
-mod contract;
+pub mod contract;
 mod adapter;
 pub mod contract_types;
 mod storage;
@@ -11,6 +11,7 @@ pub struct PoolConfig {
 const KEY_RESERVES: Symbol = symbol_short!("RES");
 const KEY_USER_POS: Symbol = symbol_short!("USERPOS");
 const KEY_B_RATE: Symbol = symbol_short!("BRATE");
+const KEY_LIQUIDITY: Symbol = symbol_short!("LIQ");
 
 #[contract]
 pub struct PoolContract;
@@ -52,6 +53,15 @@ impl PoolContract {
                     let reserve_list = Self::get_reserve_list(env.clone());
                     if let Some(idx) = reserve_list.iter().position(|a| a == request.address) {
                         let current = positions.collateral.get(idx as u32).unwrap_or(0);
+
+                        // Withdrawals beyond the available liquidity fail, as in a fully utilized pool
+                        let liquidity_key = (KEY_LIQUIDITY, request.address.clone());
+                        if let Some(liquidity) = env.storage().instance().get::<_, i128>(&liquidity_key) {
+                            if request.amount > liquidity {
+                                panic!("insufficient liquidity");
+                            }
+                            env.storage().instance().set(&liquidity_key, &(liquidity - request.amount));
+                        }
                         
                         // Convert withdrawal amount to b_token amount using to_b_token_up logic
                         let reserve = Self::get_reserve(env.clone(), request.address.clone());
@@ -105,8 +115,15 @@ impl PoolContract {
             .get(&(KEY_B_RATE, asset.clone()))
             .unwrap_or(1_000_000_000_000);
         
-        let mut reserve = default_reserve(asset);
+        let mut reserve = default_reserve(asset.clone());
         reserve.data.b_rate = b_rate;
+
+        // Report the configured liquidity as the gap between supplied and borrowed
+        let scalar_12 = 1_000_000_000_000i128;
+        if let Some(liquidity) = env.storage().instance().get::<_, i128>(&(KEY_LIQUIDITY, asset)) {
+            let borrowed = (reserve.data.d_supply * reserve.data.d_rate) / scalar_12;
+            reserve.data.b_supply = ((liquidity + borrowed) * scalar_12 + b_rate - 1) / b_rate;
+        }
         reserve
     }
 
@@ -130,6 +147,14 @@ impl PoolContract {
         100
     }
 
+    // Limit how much of an asset can be withdrawn; `None` removes the limit
+    pub fn set_liquidity(env: Env, asset: Address, liquidity: Option<i128>) {
+        match liquidity {
+            Some(liquidity) => env.storage().instance().set(&(KEY_LIQUIDITY, asset), &liquidity),
+            None => env.storage().instance().remove(&(KEY_LIQUIDITY, asset)),
+        }
+    }
+
     // Update b_rate helper to store the new rate
     pub fn update_b_rate(env: Env, asset: Address, new_rate: i128) {
         env.storage().instance().set(&(KEY_B_RATE, asset), &new_rate);
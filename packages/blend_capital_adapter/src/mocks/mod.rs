@@ -1,2 +1,3 @@
 pub mod blend_pool_mock;
 pub mod mock_adapter;
+pub mod price_oracle_mock;
@@ -0,0 +1,35 @@
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Env, Symbol};
+use yield_adapter::price_oracle::{Asset, PriceData};
+
+#[derive(Clone)]
+#[contracttype]
+enum OracleKey {
+    Price(Asset),
+}
+
+const KEY_DECIMALS: Symbol = symbol_short!("DECIMALS");
+
+#[contract]
+pub struct PriceOracleMock;
+
+#[contractimpl]
+impl PriceOracleMock {
+    pub fn __constructor(env: Env, decimals: u32) {
+        env.storage().instance().set(&KEY_DECIMALS, &decimals);
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage().instance().get(&KEY_DECIMALS).unwrap_or(14)
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&OracleKey::Price(asset))
+    }
+
+    // Helper function to set the price of an asset for testing
+    pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&OracleKey::Price(asset), &PriceData { price, timestamp });
+    }
+}
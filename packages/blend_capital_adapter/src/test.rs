@@ -208,6 +208,23 @@ fn test_withdraw_full_amount() {
     assert_eq!(stored_deposit, None);
 }
 
+#[test]
+fn test_max_withdrawable() {
+    let fixture = TestFixture::create();
+    let client = fixture.lending_adapter_client();
+
+    fixture.env.mock_all_auths();
+    client.deposit(&fixture.user1, &fixture.usdc_token_id, &1000_0000000);
+
+    // Limited by the pool's liquidity
+    fixture.pool.set_liquidity(&fixture.usdc_token_id, &Some(300_0000000));
+    assert_eq!(client.max_withdrawable(&fixture.usdc_token_id), 300_0000000);
+
+    // Limited by the controller's balance
+    fixture.pool.set_liquidity(&fixture.usdc_token_id, &Some(5000_0000000));
+    assert_eq!(client.max_withdrawable(&fixture.usdc_token_id), 1000_0000000);
+}
+
 #[test]
 #[should_panic(expected = "Error(Auth, InvalidAction)")]
 fn test_withdraw_unauthorized() {
@@ -0,0 +1,68 @@
+#![no_std]
+use soroban_sdk::{contracttype, Env, Symbol};
+
+#[derive(Clone)]
+#[contracttype]
+enum VersioningKey {
+    /// The storage schema version the contract's data is laid out in
+    SchemaVersion,
+    /// How far a batched migration step has got, reset whenever the version advances
+    MigrationCursor,
+}
+
+/// Fetch the stored schema version. Contracts deployed before versioning have none and report `unversioned`.
+pub fn read_version(e: &Env, unversioned: u32) -> u32 {
+    e.storage()
+        .instance()
+        .get(&VersioningKey::SchemaVersion)
+        .unwrap_or(unversioned)
+}
+
+/// Store the schema version, e.g. from a constructor that lays out data in the latest schema
+pub fn write_version(e: &Env, version: u32) {
+    e.storage().instance().set(&VersioningKey::SchemaVersion, &version);
+}
+
+/// Run `step(e, from)` for each version from the stored one up to `target`, then store `target`.
+/// Returns the version the data is in afterwards; data already at or past `target` is left untouched.
+/// Callers are responsible for authorization.
+pub fn migrate(e: &Env, unversioned: u32, target: u32, step: fn(&Env, u32)) -> u32 {
+    let from = read_version(e, unversioned);
+    if from >= target {
+        return from;
+    }
+    for version in from..target {
+        step(e, version);
+    }
+    write_version(e, target);
+    e.events().publish((Symbol::new(e, "migrate"),), (from, target));
+    target
+}
+
+/// Like `migrate`, but for steps too large for one transaction. A step returns `false` when it has done one
+/// batch of its work and more is left; the stored version then stays at that step and the next call resumes it.
+/// Returns the version the data is in afterwards, which is below `target` until every step has finished.
+/// Callers are responsible for authorization.
+pub fn migrate_in_batches(e: &Env, unversioned: u32, target: u32, step: fn(&Env, u32) -> bool) -> u32 {
+    let from = read_version(e, unversioned);
+    let mut version = from;
+    while version < target && step(e, version) {
+        version += 1;
+        write_version(e, version);
+        e.storage().instance().remove(&VersioningKey::MigrationCursor);
+    }
+    if version > from {
+        e.events().publish((Symbol::new(e, "migrate"),), (from, version));
+    }
+    version
+}
+
+/// Fetch the position a batched migration step stopped at, 0 when the step has not started
+pub fn read_cursor(e: &Env) -> u32 {
+    e.storage().instance().get(&VersioningKey::MigrationCursor).unwrap_or(0)
+}
+
+/// Store the position a batched migration step has reached
+pub fn write_cursor(e: &Env, cursor: u32) {
+    e.storage().instance().set(&VersioningKey::MigrationCursor, &cursor);
+}
@@ -125,6 +125,13 @@ pub trait LendingAdapter {
     /// * `asset` - The address of the asset to check balance for
     fn get_balance(e: &Env, user: Address, asset: Address) -> i128;
     
+    /// Fetch how much of an asset the yield controller can withdraw right now,
+    /// limited by both its balance and the protocol's available liquidity
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the asset to check
+    fn max_withdrawable(e: &Env, asset: Address) -> i128;
+    
     /// Fetch the address of the protocol's native token (e.g., BLND for Blend)
     fn protocol_token(e: &Env) -> Address;
 
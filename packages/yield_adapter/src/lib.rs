@@ -3,3 +3,4 @@ pub mod constants;
 pub mod contract_types;
 pub mod events;
 pub mod lending_adapter;
+pub mod price_oracle;
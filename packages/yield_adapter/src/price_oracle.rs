@@ -0,0 +1,34 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+/// Asset identifier as defined by SEP-40
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Price record as defined by SEP-40
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// ### PriceOracle
+///
+/// Subset of the SEP-40 price feed interface used to value collateral in USD.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Fetch the number of decimals used by the oracle's prices
+    fn decimals(env: &Env) -> u32;
+
+    /// Fetch the most recent price of an asset
+    ///
+    /// Returns `None` if the oracle has no price for the asset
+    ///
+    /// ### Arguments
+    /// * `asset` - The asset to fetch the price of
+    fn lastprice(env: &Env, asset: Asset) -> Option<PriceData>;
+}